@@ -0,0 +1,420 @@
+use imgui::{Key, Ui};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+use crate::keybindings::KeyScope;
+
+/// How many commands the palette remembers as recently run
+const MAX_RECENT: usize = 20;
+
+/// A key chord: a key pressed with modifiers, such as Ctrl+Shift+P. Saved as its
+/// display text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Shortcut {
+    pub key: Key,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl Shortcut {
+    pub const fn ctrl(key: Key) -> Self {
+        Self { key, ctrl: true, shift: false, alt: false }
+    }
+
+    pub const fn ctrl_shift(key: Key) -> Self {
+        Self { key, ctrl: true, shift: true, alt: false }
+    }
+
+    /// A key pressed on its own
+    pub const fn key(key: Key) -> Self {
+        Self { key, ctrl: false, shift: false, alt: false }
+    }
+
+    /// Whether a focused text field would use the chord itself, for typing or
+    /// editing, so it shouldn't run an action
+    pub fn is_text_editing(&self) -> bool {
+        if self.alt {
+            return false;
+        }
+        !self.ctrl
+            || matches!(
+                self.key,
+                Key::A | Key::C | Key::V | Key::X | Key::Z | Key::Y
+                    | Key::LeftArrow | Key::RightArrow | Key::Home | Key::End | Key::Backspace | Key::Delete
+            )
+    }
+
+    /// Whether the shortcut was pressed this frame, with exactly its modifiers held
+    pub fn is_pressed(&self, ui: &Ui) -> bool {
+        let io = ui.io();
+        io.key_ctrl == self.ctrl
+            && io.key_shift == self.shift
+            && io.key_alt == self.alt
+            && ui.is_key_pressed_no_repeat(self.key)
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        write!(f, "{}", key_name(self.key))
+    }
+}
+
+impl FromStr for Shortcut {
+    type Err = String;
+
+    /// Parse the display text, e.g. "Ctrl+Shift+P"
+    fn from_str(text: &str) -> Result<Self, String> {
+        let (mut ctrl, mut shift, mut alt) = (false, false, false);
+        let mut key = None;
+        for part in text.split('+').map(str::trim) {
+            match part.to_lowercase().as_str() {
+                "ctrl" => ctrl = true,
+                "shift" => shift = true,
+                "alt" => alt = true,
+                _ => {
+                    key = Key::VARIANTS.iter().copied().find(|k| key_name(*k).eq_ignore_ascii_case(part));
+                    if key.is_none() {
+                        return Err(format!("unknown key \"{}\" in \"{}\"", part, text));
+                    }
+                }
+            }
+        }
+        let key = key.ok_or_else(|| format!("no key in \"{}\"", text))?;
+        Ok(Self { key, ctrl, shift, alt })
+    }
+}
+
+impl TryFrom<String> for Shortcut {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, String> {
+        text.parse()
+    }
+}
+
+impl From<Shortcut> for String {
+    fn from(shortcut: Shortcut) -> String {
+        shortcut.to_string()
+    }
+}
+
+/// Name of a key as shown in shortcuts, e.g. "P" or "5" rather than "Alpha5"
+fn key_name(key: Key) -> String {
+    let name = format!("{:?}", key);
+    name.strip_prefix("Alpha").map(str::to_string).unwrap_or(name)
+}
+
+/// Keys a shortcut can be bound to: not modifiers on their own, mouse buttons or gamepad input
+pub fn is_bindable(key: Key) -> bool {
+    let name = format!("{:?}", key);
+    let modifier = ["Ctrl", "Shift", "Alt", "Super"].iter().any(|m| name.ends_with(m));
+    let other_device = ["Gamepad", "Mouse", "Reserved", "Mod"].iter().any(|prefix| name.starts_with(prefix));
+    !modifier && !other_device
+}
+
+/// A named command that menus, the command palette and shortcuts can run
+#[derive(Debug, Clone)]
+pub struct Action {
+    pub id: String,
+    pub category: String, // shown before the name in the palette, e.g. "File"
+    pub name: String,
+    pub scope: KeyScope, // where its shortcuts work
+    pub shortcuts: Vec<Shortcut>, // the first is the one menus show
+    pub default_shortcuts: Vec<Shortcut>, // as registered, before user overrides
+}
+
+impl Action {
+    /// The shortcut shown next to the action
+    pub fn shortcut(&self) -> Option<Shortcut> {
+        self.shortcuts.first().copied()
+    }
+
+    /// "Category: Name", as listed in the palette
+    pub fn full_name(&self) -> String {
+        format!("{}: {}", self.category, self.name)
+    }
+}
+
+/// An entry of a menu built from the registry
+#[derive(Debug, Clone, PartialEq)]
+pub enum MenuEntry {
+    Action(String),
+    Separator,
+    Submenu(String), // path of the submenu, e.g. "View/Layout"
+}
+
+/// Every command the editor can run, and where each is listed in the menu bar.
+/// Actions are identified by id; running them is up to the owner of the registry.
+#[derive(Default)]
+pub struct ActionRegistry {
+    actions: Vec<Action>, // in registration order
+    menu_items: Vec<(String, Option<String>)>, // menu path and action id, None for a separator
+    recent: Vec<String>, // most recently run first
+    overrides: BTreeMap<String, Vec<Shortcut>>, // user shortcuts by action id, kept for actions not registered yet
+}
+
+impl ActionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an action, replacing any with the same id. Any of `shortcuts` runs
+    /// it, unless the user has bound it differently.
+    pub fn register(&mut self, id: &str, category: &str, name: &str, shortcuts: &[Shortcut]) {
+        self.register_scoped(id, category, name, KeyScope::Global, shortcuts);
+    }
+
+    /// Register an action whose shortcuts only work in `scope`
+    pub fn register_scoped(&mut self, id: &str, category: &str, name: &str, scope: KeyScope, shortcuts: &[Shortcut]) {
+        let action = Action {
+            id: id.to_string(),
+            category: category.to_string(),
+            name: name.to_string(),
+            scope,
+            shortcuts: self.overrides.get(id).cloned().unwrap_or_else(|| shortcuts.to_vec()),
+            default_shortcuts: shortcuts.to_vec(),
+        };
+        match self.actions.iter_mut().find(|a| a.id == id) {
+            Some(existing) => *existing = action,
+            None => self.actions.push(action),
+        }
+    }
+
+    /// Remove the actions whose ids start with `prefix`, e.g. to re-register a
+    /// changing set of them
+    pub fn unregister_prefix(&mut self, prefix: &str) {
+        self.actions.retain(|a| !a.id.starts_with(prefix));
+        self.menu_items.retain(|(_, id)| !matches!(id, Some(id) if id.starts_with(prefix)));
+    }
+
+    /// List an action in a menu. Submenus are separated by '/', as in "View/Layout".
+    pub fn add_to_menu(&mut self, menu: &str, id: &str) {
+        self.menu_items.push((menu.to_string(), Some(id.to_string())));
+    }
+
+    pub fn add_separator(&mut self, menu: &str) {
+        self.menu_items.push((menu.to_string(), None));
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Action> {
+        self.actions.iter().find(|a| a.id == id)
+    }
+
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// Top-level menus, in the order they were first added to
+    pub fn menus(&self) -> Vec<String> {
+        let mut menus: Vec<String> = Vec::new();
+        for (path, _) in &self.menu_items {
+            let top = path.split('/').next().unwrap_or_default();
+            if !menus.iter().any(|m| m == top) {
+                menus.push(top.to_string());
+            }
+        }
+        menus
+    }
+
+    /// What `menu` lists, with each submenu placed where its first item was added
+    pub fn menu_entries(&self, menu: &str) -> Vec<MenuEntry> {
+        let mut entries = Vec::new();
+        for (path, id) in &self.menu_items {
+            if path == menu {
+                entries.push(match id {
+                    Some(id) => MenuEntry::Action(id.clone()),
+                    None => MenuEntry::Separator,
+                });
+            } else if let Some(rest) = path.strip_prefix(menu).and_then(|rest| rest.strip_prefix('/')) {
+                let child = format!("{}/{}", menu, rest.split('/').next().unwrap_or_default());
+                let submenu = MenuEntry::Submenu(child);
+                if !entries.contains(&submenu) {
+                    entries.push(submenu);
+                }
+            }
+        }
+        entries
+    }
+
+    /// Remember that an action was run, for the palette's recent-first ordering
+    pub fn record_use(&mut self, id: &str) {
+        self.recent.retain(|r| r != id);
+        self.recent.insert(0, id.to_string());
+        self.recent.truncate(MAX_RECENT);
+    }
+
+    /// Recently run action ids, most recent first
+    pub fn recent(&self) -> &[String] {
+        &self.recent
+    }
+
+    pub fn set_recent(&mut self, recent: Vec<String>) {
+        self.recent = recent;
+        self.recent.truncate(MAX_RECENT);
+    }
+
+    /// Bind an action to `shortcuts` instead of its defaults
+    pub fn set_shortcuts(&mut self, id: &str, shortcuts: Vec<Shortcut>) {
+        let Some(action) = self.actions.iter_mut().find(|a| a.id == id) else { return };
+        if shortcuts == action.default_shortcuts {
+            self.overrides.remove(id);
+        } else {
+            self.overrides.insert(id.to_string(), shortcuts.clone());
+        }
+        action.shortcuts = shortcuts;
+    }
+
+    /// Go back to the shortcuts an action was registered with
+    pub fn reset_shortcuts(&mut self, id: &str) {
+        if let Some(defaults) = self.get(id).map(|a| a.default_shortcuts.clone()) {
+            self.set_shortcuts(id, defaults);
+        }
+    }
+
+    pub fn is_overridden(&self, id: &str) -> bool {
+        self.overrides.contains_key(id)
+    }
+
+    /// The user's shortcuts, by action id
+    pub fn overrides(&self) -> &BTreeMap<String, Vec<Shortcut>> {
+        &self.overrides
+    }
+
+    /// Apply saved user shortcuts, replacing any set before
+    pub fn set_overrides(&mut self, overrides: BTreeMap<String, Vec<Shortcut>>) {
+        self.overrides = overrides;
+        for action in &mut self.actions {
+            action.shortcuts = self.overrides.get(&action.id).cloned().unwrap_or_else(|| action.default_shortcuts.clone());
+        }
+    }
+
+    /// Other actions bound to one of `id`'s shortcuts in the same scope
+    pub fn conflicts(&self, id: &str) -> Vec<&Action> {
+        let Some(action) = self.get(id) else { return Vec::new() };
+        self.actions
+            .iter()
+            .filter(|other| other.id != action.id && other.scope == action.scope)
+            .filter(|other| other.shortcuts.iter().any(|s| action.shortcuts.contains(s)))
+            .collect()
+    }
+
+    /// Shortcuts bound to more than one action in the same scope, with those actions
+    pub fn all_conflicts(&self) -> HashMap<(KeyScope, Shortcut), Vec<String>> {
+        let mut bound: HashMap<(KeyScope, Shortcut), Vec<String>> = HashMap::new();
+        for action in &self.actions {
+            for shortcut in &action.shortcuts {
+                bound.entry((action.scope, *shortcut)).or_default().push(action.id.clone());
+            }
+        }
+        bound.retain(|_, ids| ids.len() > 1);
+        bound
+    }
+
+    /// The action whose shortcut was pressed this frame, with keyboard focus in
+    /// `focus`. Bindings of more specific scopes win over global ones.
+    pub fn pressed(&self, ui: &Ui, focus: KeyScope) -> Option<&Action> {
+        focus.active_scopes().iter().find_map(|&scope| {
+            self.actions.iter().filter(|a| a.scope == scope).find(|action| {
+                action.shortcuts.iter().any(|s| {
+                    // Text fields keep the keys they type and edit with
+                    let shadowed = focus == KeyScope::TextEditing && scope != KeyScope::TextEditing && s.is_text_editing();
+                    !shadowed && s.is_pressed(ui)
+                })
+            })
+        })
+    }
+}
+
+/// Score how well `query` fuzzily matches `text`, or None if its characters don't
+/// all appear in order. Consecutive characters and word starts score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for q in query.chars().flat_map(char::to_lowercase).filter(|c| !c.is_whitespace()) {
+        let found = (position..text.len()).find(|&i| text[i] == q)?;
+        score += 1;
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 8; // start of a word
+        }
+        match previous {
+            Some(previous) if found == previous + 1 => score += 5,
+            Some(previous) => score -= (found - previous - 1).min(5) as i32,
+            None => score -= found.min(10) as i32,
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> ActionRegistry {
+        let mut actions = ActionRegistry::new();
+        actions.register("file.save", "File", "Save", &[Shortcut::ctrl(Key::S)]);
+        actions.register("file.save_all", "File", "Save All", &[Shortcut::ctrl_shift(Key::S)]);
+        actions.register_scoped("viewport.snap", "Viewport", "Snap", KeyScope::Viewport, &[Shortcut::ctrl(Key::S)]);
+        actions
+    }
+
+    fn ids(actions: Vec<&Action>) -> Vec<&str> {
+        actions.iter().map(|a| a.id.as_str()).collect()
+    }
+
+    #[test]
+    fn same_shortcut_in_other_scope_is_not_a_conflict() {
+        let actions = registry();
+        assert!(actions.conflicts("file.save").is_empty());
+        assert!(actions.all_conflicts().is_empty());
+    }
+
+    #[test]
+    fn rebinding_reports_conflicts_in_the_same_scope() {
+        let mut actions = registry();
+        actions.set_shortcuts("file.save_all", vec![Shortcut::ctrl(Key::S)]);
+        assert_eq!(ids(actions.conflicts("file.save")), ["file.save_all"]);
+        assert_eq!(ids(actions.conflicts("file.save_all")), ["file.save"]);
+
+        let conflicts = actions.all_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[&(KeyScope::Global, Shortcut::ctrl(Key::S))], ["file.save", "file.save_all"]);
+
+        actions.reset_shortcuts("file.save_all");
+        assert!(actions.conflicts("file.save").is_empty());
+    }
+
+    #[test]
+    fn overrides_apply_to_actions_registered_later() {
+        let mut actions = ActionRegistry::new();
+        let overrides = BTreeMap::from([("file.save".to_string(), vec![Shortcut::ctrl(Key::W)])]);
+        actions.set_overrides(overrides);
+        actions.register("file.save", "File", "Save", &[Shortcut::ctrl(Key::S)]);
+        assert_eq!(actions.get("file.save").unwrap().shortcuts, [Shortcut::ctrl(Key::W)]);
+        assert!(actions.is_overridden("file.save"));
+    }
+
+    #[test]
+    fn shortcut_text_round_trips() {
+        for shortcut in [Shortcut::ctrl_shift(Key::P), Shortcut::key(Key::F2), Shortcut::ctrl(Key::Delete)] {
+            assert_eq!(shortcut.to_string().parse::<Shortcut>(), Ok(shortcut));
+        }
+    }
+}
@@ -1,18 +1,57 @@
 use imgui::*;
 use crate::game_engine_ui::GameEngineUI;
+use crate::session::Session;
+use crate::ui::detached_window::{DetachedContent, DetachedWindow};
 
 pub struct App {
     engine_ui: GameEngineUI,
 }
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new(session: &Session) -> Self {
         Self {
-            engine_ui: GameEngineUI::new()
+            engine_ui: GameEngineUI::new(session)
         }
     }
 
     pub fn run(&mut self, ui: &Ui) {
         self.engine_ui.render(ui);
     }
+
+    /// Ask to close the application. The UI may first prompt about unsaved changes.
+    pub fn request_exit(&mut self) {
+        self.engine_ui.request_exit();
+    }
+
+    /// Whether the application should shut down
+    pub fn should_exit(&self) -> bool {
+        self.engine_ui.should_exit()
+    }
+
+    /// UI state to save for the next launch. Window geometry is filled in by the caller.
+    pub fn session(&self) -> Session {
+        self.engine_ui.session()
+    }
+
+    /// Panels and tabs that want OS windows of their own. Windows are opened and
+    /// closed to match.
+    pub fn detached_windows(&self) -> Vec<DetachedWindow> {
+        self.engine_ui.detached_windows()
+    }
+
+    /// Draw the content of a detached window. `origin` is the window's position
+    /// relative to the main window, in logical pixels.
+    pub fn run_detached(&mut self, ui: &Ui, content: DetachedContent, origin: [f32; 2]) {
+        self.engine_ui.render_detached(ui, content, origin);
+    }
+
+    /// A detached window was moved or resized by the OS
+    pub fn move_detached(&mut self, content: DetachedContent, position: [f32; 2], size: [f32; 2]) {
+        self.engine_ui.move_detached(content, position, size);
+    }
+
+    /// A detached window's close button was pressed
+    pub fn close_detached(&mut self, content: DetachedContent) {
+        self.engine_ui.close_detached(content);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::SystemTime;
+use crate::project::PROJECT_EXTENSION;
+
+/// Folder next to a project file that holds its assets
+pub const ASSETS_DIR: &str = "assets";
+
+/// Where the assets of the project saved at `project_file` live
+pub fn asset_dir(project_file: &Path) -> PathBuf {
+    project_file.parent().unwrap_or(Path::new(".")).join(ASSETS_DIR)
+}
+
+/// Broad type of an asset, from its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetKind {
+    Folder,
+    Image,
+    Model,
+    Audio,
+    Script,
+    Scene,
+    Material,
+    Shader,
+    Other,
+}
+
+impl AssetKind {
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+        match extension.as_str() {
+            "png" | "jpg" | "jpeg" | "bmp" | "tga" | "gif" | "webp" | "hdr" | "dds" => Self::Image,
+            "fbx" | "obj" | "gltf" | "glb" | "dae" | "blend" => Self::Model,
+            "wav" | "mp3" | "ogg" | "flac" => Self::Audio,
+            "rs" | "lua" | "wasm" | "js" | "py" => Self::Script,
+            "mat" => Self::Material,
+            "wgsl" | "glsl" | "hlsl" | "spv" => Self::Shader,
+            e if e == PROJECT_EXTENSION => Self::Scene,
+            _ => Self::Other,
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::Folder => "📁",
+            Self::Image => "🖼",
+            Self::Model => "🧊",
+            Self::Audio => "🔊",
+            Self::Script => "📜",
+            Self::Scene => "🌍",
+            Self::Material => "🎨",
+            Self::Shader => "✨",
+            Self::Other => "📄",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Folder => "Folder",
+            Self::Image => "Image",
+            Self::Model => "Model",
+            Self::Audio => "Audio",
+            Self::Script => "Script",
+            Self::Scene => "Scene",
+            Self::Material => "Material",
+            Self::Shader => "Shader",
+            Self::Other => "File",
+        }
+    }
+}
+
+/// A file or folder found by a scan
+#[derive(Debug, Clone)]
+pub struct AssetEntry {
+    pub path: PathBuf,
+    pub name: String,
+    pub kind: AssetKind,
+    pub size: u64, // bytes; 0 for folders
+    pub modified: Option<SystemTime>,
+}
+
+impl AssetEntry {
+    pub fn is_folder(&self) -> bool {
+        self.kind == AssetKind::Folder
+    }
+}
+
+/// Every file and folder under an asset directory, as of the last scan
+#[derive(Debug, Default)]
+pub struct AssetTree {
+    pub root: PathBuf,
+    children: HashMap<PathBuf, Vec<AssetEntry>>, // by the folder they are in
+}
+
+impl AssetTree {
+    /// Walk `root`, skipping hidden files. Symlinked folders aren't followed, so
+    /// links back up the tree can't loop.
+    pub fn scan(root: &Path) -> Result<Self, String> {
+        let mut tree = Self { root: root.to_path_buf(), children: HashMap::new() };
+        let mut pending = vec![root.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            let read = fs::read_dir(&dir).map_err(|e| format!("{}: {}", dir.display(), e));
+            let read = match read {
+                Ok(read) => read,
+                // An unreadable subfolder shows up empty rather than failing the scan
+                Err(e) if dir != root => {
+                    log::warn!("Skipping asset folder {}", e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            let mut entries = Vec::new();
+            for item in read.flatten() {
+                let name = item.file_name().to_string_lossy().into_owned();
+                if name.starts_with('.') {
+                    continue;
+                }
+                let path = item.path();
+                let Ok(metadata) = fs::metadata(&path) else { continue };
+                let kind = if metadata.is_dir() { AssetKind::Folder } else { AssetKind::from_path(&path) };
+                let is_link = item.file_type().is_ok_and(|t| t.is_symlink());
+                if metadata.is_dir() && !is_link {
+                    pending.push(path.clone());
+                }
+                entries.push(AssetEntry {
+                    path,
+                    name,
+                    kind,
+                    size: if metadata.is_dir() { 0 } else { metadata.len() },
+                    modified: metadata.modified().ok(),
+                });
+            }
+            tree.children.insert(dir, entries);
+        }
+        Ok(tree)
+    }
+
+    /// Files and folders directly inside `dir`
+    pub fn children(&self, dir: &Path) -> &[AssetEntry] {
+        self.children.get(dir).map_or(&[], Vec::as_slice)
+    }
+
+    /// Folders directly inside `dir`, by name
+    pub fn subfolders(&self, dir: &Path) -> Vec<&AssetEntry> {
+        let mut folders: Vec<&AssetEntry> = self.children(dir).iter().filter(|e| e.is_folder()).collect();
+        folders.sort_by_key(|e| e.name.to_lowercase());
+        folders
+    }
+
+    /// Everything anywhere under `dir`
+    pub fn descendants(&self, dir: &Path) -> Vec<&AssetEntry> {
+        let mut found = Vec::new();
+        let mut pending = vec![dir];
+        while let Some(dir) = pending.pop() {
+            for entry in self.children(dir) {
+                if entry.is_folder() {
+                    pending.push(&entry.path);
+                }
+                found.push(entry);
+            }
+        }
+        found
+    }
+
+    /// Whether the scan found the folder `dir`
+    pub fn has_folder(&self, dir: &Path) -> bool {
+        self.children.contains_key(dir)
+    }
+
+    pub fn folder_count(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.children.values().flatten().filter(|e| !e.is_folder()).count()
+    }
+}
+
+/// A scan running on the tokio runtime's blocking threads, so big projects don't
+/// stall frames
+pub struct AssetScan {
+    receiver: Receiver<Result<AssetTree, String>>,
+}
+
+impl AssetScan {
+    pub fn start(root: &Path) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let dir = root.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            let _ = sender.send(AssetTree::scan(&dir));
+        });
+        Self { receiver }
+    }
+
+    /// The scan's result once it has finished
+    pub fn poll(&self) -> Option<Result<AssetTree, String>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err("the scan stopped unexpectedly".to_string())),
+        }
+    }
+}
+
+/// Give a file or folder a new name in the same folder. Returns its new path.
+pub fn rename(path: &Path, new_name: &str) -> Result<PathBuf, String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() || new_name.contains(['/', '\\']) || new_name == "." || new_name == ".." {
+        return Err(format!("'{}' is not a valid name", new_name));
+    }
+    let target = path.with_file_name(new_name);
+    if target == path {
+        return Ok(target);
+    }
+    move_to(path, &target)
+}
+
+/// Move a file or folder into `dir`. Returns its new path.
+pub fn move_into(path: &Path, dir: &Path) -> Result<PathBuf, String> {
+    if dir.starts_with(path) {
+        return Err(format!("Can't move {} into itself", path.display()));
+    }
+    let name = path.file_name().ok_or_else(|| format!("{} has no name", path.display()))?;
+    move_to(path, &dir.join(name))
+}
+
+fn move_to(path: &Path, target: &Path) -> Result<PathBuf, String> {
+    if target.exists() {
+        return Err(format!("{} already exists", target.display()));
+    }
+    fs::rename(path, target).map_err(|e| format!("Failed to move {}: {}", path.display(), e))?;
+    Ok(target.to_path_buf())
+}
+
+/// Delete a file, or a folder and everything in it
+pub fn delete(path: &Path) -> Result<(), String> {
+    let result = if path.is_dir() { fs::remove_dir_all(path) } else { fs::remove_file(path) };
+    result.map_err(|e| format!("Failed to delete {}: {}", path.display(), e))
+}
+
+/// Make a folder in `parent` named `name`, or "name 2", "name 3", ... if taken
+pub fn create_folder(parent: &Path, name: &str) -> Result<PathBuf, String> {
+    let mut path = parent.join(name);
+    let mut n = 2;
+    while path.exists() {
+        path = parent.join(format!("{} {}", name, n));
+        n += 1;
+    }
+    fs::create_dir_all(&path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Open the system file manager with `path` selected, or showing its folder where
+/// the file manager can't select files
+pub fn show_in_file_manager(path: &Path) -> Result<(), String> {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("explorer");
+        command.arg(format!("/select,{}", path.display()));
+        command
+    } else if cfg!(target_os = "macos") {
+        let mut command = Command::new("open");
+        command.arg("-R").arg(path);
+        command
+    } else {
+        let mut command = Command::new("xdg-open");
+        command.arg(if path.is_dir() { path } else { path.parent().unwrap_or(path) });
+        command
+    };
+    command.spawn().map(|_| ()).map_err(|e| format!("Failed to open the file manager: {}", e))
+}
@@ -0,0 +1,372 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use lazy_static::lazy_static;
+
+/// Type of a command argument or console variable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    Bool,
+    Int,
+    Float,
+    Text,
+}
+
+impl ValueKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Bool => "bool",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Text => "text",
+        }
+    }
+}
+
+/// A parsed command argument or a console variable's value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f32),
+    Text(String),
+}
+
+impl Value {
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Self::Bool(_) => ValueKind::Bool,
+            Self::Int(_) => ValueKind::Int,
+            Self::Float(_) => ValueKind::Float,
+            Self::Text(_) => ValueKind::Text,
+        }
+    }
+
+    /// Read `text` as a value of `kind`. Bools also accept 1/0 and on/off.
+    pub fn parse(kind: ValueKind, text: &str) -> Result<Self, String> {
+        let invalid = || format!("'{}' is not a valid {}", text, kind.name());
+        match kind {
+            ValueKind::Bool => match text.to_lowercase().as_str() {
+                "1" | "true" | "on" | "yes" => Ok(Self::Bool(true)),
+                "0" | "false" | "off" | "no" => Ok(Self::Bool(false)),
+                _ => Err(invalid()),
+            },
+            ValueKind::Int => text.parse().map(Self::Int).map_err(|_| invalid()),
+            ValueKind::Float => text.parse().map(Self::Float).map_err(|_| invalid()),
+            ValueKind::Text => Ok(Self::Text(text.to_string())),
+        }
+    }
+
+    pub fn as_bool(&self) -> bool {
+        match self {
+            Self::Bool(value) => *value,
+            Self::Int(value) => *value != 0,
+            Self::Float(value) => *value != 0.0,
+            Self::Text(value) => !value.is_empty(),
+        }
+    }
+
+    pub fn as_f32(&self) -> f32 {
+        match self {
+            Self::Bool(value) => *value as i32 as f32,
+            Self::Int(value) => *value as f32,
+            Self::Float(value) => *value,
+            Self::Text(value) => value.parse().unwrap_or_default(),
+        }
+    }
+
+    /// The value as typed, without the quotes text is shown with
+    pub fn as_text(&self) -> String {
+        match self {
+            Self::Text(value) => value.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Int(value) => write!(f, "{}", value),
+            Self::Float(value) => write!(f, "{}", value),
+            Self::Text(value) => write!(f, "\"{}\"", value),
+        }
+    }
+}
+
+/// An argument a command takes
+#[derive(Debug, Clone, Copy)]
+pub struct Arg {
+    pub name: &'static str,
+    pub kind: ValueKind,
+    pub optional: bool, // may be left out, along with any after it
+}
+
+impl Arg {
+    pub fn required(name: &'static str, kind: ValueKind) -> Self {
+        Self { name, kind, optional: false }
+    }
+
+    pub fn optional(name: &'static str, kind: ValueKind) -> Self {
+        Self { name, kind, optional: true }
+    }
+}
+
+/// Runs a command with its parsed arguments. Returns text to print, or an error.
+pub type CommandFn = Arc<dyn Fn(&[Value]) -> Result<Option<String>, String> + Send + Sync>;
+
+/// A command typed into the Console
+pub struct Command {
+    pub name: String,
+    pub help: String,
+    pub args: Vec<Arg>,
+    run: CommandFn,
+}
+
+impl Command {
+    /// How to call the command, e.g. "help [command:text]"
+    pub fn usage(&self) -> String {
+        let mut usage = self.name.clone();
+        for arg in &self.args {
+            let (open, close) = if arg.optional { ('[', ']') } else { ('<', '>') };
+            usage.push_str(&format!(" {}{}:{}{}", open, arg.name, arg.kind.name(), close));
+        }
+        usage
+    }
+
+    /// Parse the words typed after the command's name. A text argument in last
+    /// place takes the rest of the line.
+    fn parse_args(&self, words: &[String]) -> Result<Vec<Value>, String> {
+        let mut values = Vec::new();
+        for (i, arg) in self.args.iter().enumerate() {
+            let Some(word) = words.get(i) else {
+                if arg.optional {
+                    break;
+                }
+                return Err(format!("Missing <{}>. Usage: {}", arg.name, self.usage()));
+            };
+            if arg.kind == ValueKind::Text && i == self.args.len() - 1 {
+                values.push(Value::Text(words[i..].join(" ")));
+                return Ok(values);
+            }
+            values.push(Value::parse(arg.kind, word).map_err(|e| format!("{} for <{}>", e, arg.name))?);
+        }
+        if words.len() > self.args.len() {
+            return Err(format!("Too many arguments. Usage: {}", self.usage()));
+        }
+        Ok(values)
+    }
+}
+
+/// A setting read by engine code and changed from the Console, e.g. `vsync 0`
+#[derive(Debug, Clone)]
+pub struct Cvar {
+    pub name: String,
+    pub help: String,
+    pub value: Value,
+    pub default: Value,
+    pub range: Option<(f32, f32)>, // limits for numeric values
+}
+
+#[derive(Default)]
+struct ConsoleRegistry {
+    commands: BTreeMap<String, Command>,
+    cvars: BTreeMap<String, Cvar>,
+}
+
+lazy_static! {
+    static ref REGISTRY: Mutex<ConsoleRegistry> = Mutex::new(ConsoleRegistry::default());
+}
+
+/// Add a command, replacing any with the same name
+pub fn register_command(
+    name: &str,
+    help: &str,
+    args: Vec<Arg>,
+    run: impl Fn(&[Value]) -> Result<Option<String>, String> + Send + Sync + 'static,
+) {
+    let command = Command { name: name.to_string(), help: help.to_string(), args, run: Arc::new(run) };
+    REGISTRY.lock().unwrap().commands.insert(name.to_string(), command);
+}
+
+/// Add a console variable. Registering it again keeps its current value.
+pub fn register_cvar(name: &str, help: &str, default: Value) {
+    register_cvar_with_range(name, help, default, None);
+}
+
+/// Add a numeric console variable that is clamped to `min..=max`
+pub fn register_ranged_cvar(name: &str, help: &str, default: f32, min: f32, max: f32) {
+    register_cvar_with_range(name, help, Value::Float(default), Some((min, max)));
+}
+
+fn register_cvar_with_range(name: &str, help: &str, default: Value, range: Option<(f32, f32)>) {
+    let mut registry = REGISTRY.lock().unwrap();
+    let value = registry.cvars.get(name).map_or_else(|| default.clone(), |cvar| cvar.value.clone());
+    let cvar = Cvar { name: name.to_string(), help: help.to_string(), value, default, range };
+    registry.cvars.insert(name.to_string(), cvar);
+}
+
+/// Current value of a console variable
+pub fn cvar(name: &str) -> Option<Value> {
+    REGISTRY.lock().unwrap().cvars.get(name).map(|cvar| cvar.value.clone())
+}
+
+/// Set a console variable from text, as typed in the Console. Returns the new value.
+pub fn set_cvar(name: &str, text: &str) -> Result<Value, String> {
+    let mut registry = REGISTRY.lock().unwrap();
+    let cvar = registry.cvars.get_mut(name).ok_or_else(|| format!("Unknown variable '{}'", name))?;
+    let mut value = Value::parse(cvar.default.kind(), text)?;
+    if let (Value::Float(number), Some((min, max))) = (&mut value, cvar.range) {
+        *number = number.clamp(min, max);
+    }
+    cvar.value = value.clone();
+    Ok(value)
+}
+
+/// Names of commands and variables starting with `prefix`, in order
+pub fn completions(prefix: &str) -> Vec<String> {
+    let registry = REGISTRY.lock().unwrap();
+    let mut names: Vec<String> = registry
+        .commands
+        .keys()
+        .chain(registry.cvars.keys())
+        .filter(|name| name.starts_with(prefix))
+        .cloned()
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Run a line typed into the Console, echoing it and its output to the log
+pub fn execute(line: &str) {
+    let line = line.trim();
+    if line.is_empty() {
+        return;
+    }
+    log::info!("> {}", line);
+    match run(line) {
+        Ok(Some(output)) => {
+            for text in output.lines() {
+                log::info!("{}", text);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => log::error!("{}", e),
+    }
+}
+
+/// Run a command, or show or set a variable when the line starts with its name
+fn run(line: &str) -> Result<Option<String>, String> {
+    let words = split_words(line)?;
+    let Some((name, args)) = words.split_first() else {
+        return Ok(None);
+    };
+
+    // Commands run without the lock held, so they can use the console themselves
+    let command = {
+        let registry = REGISTRY.lock().unwrap();
+        if let Some(command) = registry.commands.get(name) {
+            Some((Arc::clone(&command.run), command.parse_args(args)?))
+        } else if let Some(cvar) = registry.cvars.get(name) {
+            if args.is_empty() {
+                return Ok(Some(format!("{} = {} (default {})", cvar.name, cvar.value, cvar.default)));
+            }
+            None
+        } else {
+            return Err(format!("Unknown command '{}'. Type 'help' for a list.", name));
+        }
+    };
+    match command {
+        Some((run, values)) => run(&values),
+        None => {
+            let value = set_cvar(name, &args.join(" "))?;
+            Ok(Some(format!("{} = {}", name, value)))
+        }
+    }
+}
+
+/// Split a line into words, keeping "quoted text" together
+fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_quotes = false;
+    let mut has_word = false;
+    for c in line.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_word = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_word {
+                    words.push(std::mem::take(&mut word));
+                    has_word = false;
+                }
+            }
+            c => {
+                word.push(c);
+                has_word = true;
+            }
+        }
+    }
+    if in_quotes {
+        return Err("Missing closing quote".to_string());
+    }
+    if has_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Commands for finding out what the console can do
+pub fn register_builtin_commands() {
+    register_command(
+        "help",
+        "List the commands, or describe one command or variable",
+        vec![Arg::optional("name", ValueKind::Text)],
+        |args| {
+            let registry = REGISTRY.lock().unwrap();
+            let Some(name) = args.first().map(Value::as_text) else {
+                let lines: Vec<String> = registry
+                    .commands
+                    .values()
+                    .map(|command| format!("{} - {}", command.usage(), command.help))
+                    .collect();
+                return Ok(Some(format!("{}\nType 'cvars' to list the variables.", lines.join("\n"))));
+            };
+            if let Some(command) = registry.commands.get(&name) {
+                Ok(Some(format!("{}\n{}", command.usage(), command.help)))
+            } else if let Some(cvar) = registry.cvars.get(&name) {
+                Ok(Some(format!("{} ({}) = {}\n{}", cvar.name, cvar.default.kind().name(), cvar.value, cvar.help)))
+            } else {
+                Err(format!("No command or variable named '{}'", name))
+            }
+        },
+    );
+    register_command("cvars", "List the console variables and their values", Vec::new(), |_| {
+        let registry = REGISTRY.lock().unwrap();
+        let lines: Vec<String> = registry
+            .cvars
+            .values()
+            .map(|cvar| format!("{} = {} - {}", cvar.name, cvar.value, cvar.help))
+            .collect();
+        Ok(Some(lines.join("\n")))
+    });
+    register_command(
+        "reset",
+        "Set a console variable back to its default",
+        vec![Arg::required("variable", ValueKind::Text)],
+        |args| {
+            let name = args[0].as_text();
+            let mut registry = REGISTRY.lock().unwrap();
+            let cvar = registry.cvars.get_mut(&name).ok_or_else(|| format!("Unknown variable '{}'", name))?;
+            cvar.value = cvar.default.clone();
+            Ok(Some(format!("{} = {}", cvar.name, cvar.value)))
+        },
+    );
+    register_command("echo", "Print text to the console", vec![Arg::optional("text", ValueKind::Text)], |args| {
+        Ok(Some(args.first().map_or_else(String::new, Value::as_text)))
+    });
+}
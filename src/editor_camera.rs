@@ -0,0 +1,281 @@
+use glam::{Mat4, Vec3};
+use imgui::{Key, MouseButton, Ui};
+use serde::{Deserialize, Serialize};
+
+const FIELD_OF_VIEW: f32 = 60.0; // vertical, degrees
+const NEAR_PLANE: f32 = 0.1;
+const FAR_PLANE: f32 = 1000.0;
+const MIN_DISTANCE: f32 = 0.5;
+const MAX_DISTANCE: f32 = 500.0;
+const LOOK_SPEED: f32 = 0.005; // radians per pixel
+const MAX_PITCH: f32 = 1.55;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Projection {
+    Perspective,
+    Orthographic,
+}
+
+/// What dragging with the right mouse button does. Middle drag always pans and
+/// Alt + left drag always orbits.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CameraMode {
+    Orbit,
+    Pan,
+    Fly,
+}
+
+impl CameraMode {
+    pub const ALL: [CameraMode; 3] = [CameraMode::Orbit, CameraMode::Pan, CameraMode::Fly];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Orbit => "Orbit",
+            Self::Pan => "Pan",
+            Self::Fly => "Fly",
+        }
+    }
+
+    pub fn help_text(&self) -> &'static str {
+        match self {
+            Self::Orbit => "RMB: Orbit | MMB: Pan | Wheel: Zoom",
+            Self::Pan => "RMB: Pan | Alt+LMB: Orbit | Wheel: Zoom",
+            Self::Fly => "RMB: Look | WASD/QE: Move | Shift: Fast",
+        }
+    }
+}
+
+/// Viewport camera orbiting a target point. Fly mode moves the target along with
+/// the eye, so switching modes never makes the view jump.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EditorCamera {
+    pub projection: Projection,
+    pub mode: CameraMode,
+    target: [f32; 3],
+    yaw: f32,   // radians around +Y
+    pitch: f32, // radians, positive looks down
+    distance: f32,
+    pub fly_speed: f32, // units per second
+}
+
+impl Default for EditorCamera {
+    fn default() -> Self {
+        Self {
+            projection: Projection::Perspective,
+            mode: CameraMode::Orbit,
+            target: [0.0, 0.0, 0.0],
+            yaw: -0.71,
+            pitch: 0.45,
+            distance: 20.0,
+            fly_speed: 10.0,
+        }
+    }
+}
+
+impl EditorCamera {
+    pub fn target(&self) -> Vec3 {
+        Vec3::from(self.target)
+    }
+
+    /// Direction the camera looks in
+    pub fn forward(&self) -> Vec3 {
+        Vec3::new(
+            self.yaw.sin() * self.pitch.cos(),
+            -self.pitch.sin(),
+            self.yaw.cos() * self.pitch.cos(),
+        )
+    }
+
+    pub fn right(&self) -> Vec3 {
+        self.forward().cross(Vec3::Y).normalize()
+    }
+
+    pub fn up(&self) -> Vec3 {
+        self.right().cross(self.forward())
+    }
+
+    pub fn eye(&self) -> Vec3 {
+        self.target() - self.forward() * self.distance
+    }
+
+    pub fn view_matrix(&self) -> Mat4 {
+        Mat4::look_at_rh(self.eye(), self.target(), Vec3::Y)
+    }
+
+    pub fn projection_matrix(&self, aspect: f32) -> Mat4 {
+        match self.projection {
+            Projection::Perspective => {
+                Mat4::perspective_rh(FIELD_OF_VIEW.to_radians(), aspect, NEAR_PLANE, FAR_PLANE)
+            }
+            Projection::Orthographic => {
+                // Same framing of the target as the perspective view at this distance
+                let half_height = self.distance * (FIELD_OF_VIEW.to_radians() * 0.5).tan();
+                let half_width = half_height * aspect;
+                Mat4::orthographic_rh(-half_width, half_width, -half_height, half_height, -FAR_PLANE, FAR_PLANE)
+            }
+        }
+    }
+
+    pub fn view_projection(&self, aspect: f32) -> Mat4 {
+        self.projection_matrix(aspect) * self.view_matrix()
+    }
+
+    /// Projection for a viewport drawn at `origin` with `size` in screen pixels
+    pub fn screen_projection(&self, origin: [f32; 2], size: [f32; 2]) -> ScreenProjection {
+        ScreenProjection::new(origin, size, self.view_projection(size[0] / size[1]), self.up())
+    }
+
+    pub fn toggle_projection(&mut self) {
+        self.projection = match self.projection {
+            Projection::Perspective => Projection::Orthographic,
+            Projection::Orthographic => Projection::Perspective,
+        };
+    }
+
+    /// Move the camera so the (min, max) box fills the view, keeping the current angle
+    pub fn frame_bounds(&mut self, min: Vec3, max: Vec3) {
+        let center = (min + max) * 0.5;
+        let radius = ((max - min).length() * 0.5).max(0.5);
+        self.target = center.to_array();
+        self.distance = (radius / (FIELD_OF_VIEW.to_radians() * 0.5).sin() * 1.1).clamp(MIN_DISTANCE, MAX_DISTANCE);
+    }
+
+    /// Apply mouse and keyboard input. `hovered` is true while the cursor is over the
+    /// viewport and `active` while a drag that started on it is held, so input is
+    /// never taken from other panels.
+    pub fn handle_input(&mut self, ui: &Ui, viewport_height: f32, hovered: bool, active: bool) {
+        if !hovered && !active {
+            return;
+        }
+
+        let io = ui.io();
+        let delta = io.mouse_delta;
+        let dragging = |button| active && ui.is_mouse_down(button);
+
+        if hovered && io.mouse_wheel != 0.0 {
+            self.distance = (self.distance * 0.88f32.powf(io.mouse_wheel)).clamp(MIN_DISTANCE, MAX_DISTANCE);
+        }
+
+        if dragging(MouseButton::Right) {
+            match self.mode {
+                CameraMode::Orbit => self.orbit(delta),
+                CameraMode::Pan => self.pan(delta, viewport_height),
+                CameraMode::Fly => self.look(delta),
+            }
+        }
+        if dragging(MouseButton::Middle) {
+            self.pan(delta, viewport_height);
+        }
+        if io.key_alt && dragging(MouseButton::Left) {
+            self.orbit(delta);
+        }
+
+        // WASD moves in fly mode, or in any mode while the right button is held
+        let fly_keys = self.mode == CameraMode::Fly || dragging(MouseButton::Right);
+        if fly_keys && !io.want_text_input && !io.key_ctrl {
+            let forward = self.forward();
+            let right = self.right();
+            let mut direction = Vec3::ZERO;
+            for (key, axis) in [
+                (Key::W, forward),
+                (Key::S, -forward),
+                (Key::D, right),
+                (Key::A, -right),
+                (Key::E, Vec3::Y),
+                (Key::Q, -Vec3::Y),
+            ] {
+                if ui.is_key_down(key) {
+                    direction += axis;
+                }
+            }
+            if direction != Vec3::ZERO {
+                let speed = if io.key_shift { self.fly_speed * 4.0 } else { self.fly_speed };
+                let step = direction.normalize() * speed * io.delta_time;
+                self.target = (self.target() + step).to_array();
+            }
+        }
+    }
+
+    /// Rotate around the target
+    fn orbit(&mut self, delta: [f32; 2]) {
+        self.yaw -= delta[0] * LOOK_SPEED;
+        self.pitch = (self.pitch + delta[1] * LOOK_SPEED).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+
+    /// Rotate in place around the eye
+    fn look(&mut self, delta: [f32; 2]) {
+        let eye = self.eye();
+        self.yaw -= delta[0] * LOOK_SPEED;
+        self.pitch = (self.pitch + delta[1] * LOOK_SPEED).clamp(-MAX_PITCH, MAX_PITCH);
+        self.target = (eye + self.forward() * self.distance).to_array();
+    }
+
+    /// Slide the target in the view plane so the point under the cursor follows it
+    fn pan(&mut self, delta: [f32; 2], viewport_height: f32) {
+        let units_per_pixel =
+            2.0 * self.distance * (FIELD_OF_VIEW.to_radians() * 0.5).tan() / viewport_height.max(1.0);
+        let offset = (-self.right() * delta[0] + self.up() * delta[1]) * units_per_pixel;
+        self.target = (self.target() + offset).to_array();
+    }
+}
+
+/// Maps between world space and screen pixels for one viewport
+#[derive(Debug, Clone, Copy)]
+pub struct ScreenProjection {
+    origin: [f32; 2],
+    size: [f32; 2],
+    view_proj: Mat4,
+    inverse: Mat4,
+    camera_up: Vec3,
+}
+
+impl ScreenProjection {
+    pub fn new(origin: [f32; 2], size: [f32; 2], view_proj: Mat4, camera_up: Vec3) -> Self {
+        Self { origin, size, view_proj, inverse: view_proj.inverse(), camera_up }
+    }
+
+    pub fn origin(&self) -> [f32; 2] {
+        self.origin
+    }
+
+    pub fn size(&self) -> [f32; 2] {
+        self.size
+    }
+
+    pub fn contains(&self, point: [f32; 2]) -> bool {
+        point[0] >= self.origin[0]
+            && point[1] >= self.origin[1]
+            && point[0] < self.origin[0] + self.size[0]
+            && point[1] < self.origin[1] + self.size[1]
+    }
+
+    /// Screen position of a world point, or None if it is behind the camera
+    pub fn world_to_screen(&self, point: Vec3) -> Option<[f32; 2]> {
+        let clip = self.view_proj * point.extend(1.0);
+        if clip.w <= 1e-5 {
+            return None;
+        }
+        let ndc = clip.truncate() / clip.w;
+        Some([
+            self.origin[0] + (ndc.x + 1.0) * 0.5 * self.size[0],
+            self.origin[1] + (1.0 - ndc.y) * 0.5 * self.size[1],
+        ])
+    }
+
+    /// How many pixels one world unit covers at `point`, for drawing at a constant screen size
+    pub fn pixels_per_unit(&self, point: Vec3) -> Option<f32> {
+        let a = self.world_to_screen(point)?;
+        let b = self.world_to_screen(point + self.camera_up)?;
+        Some(((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2)).sqrt().max(1e-3))
+    }
+
+    /// World-space ray (origin, unit direction) through a screen position
+    pub fn ray(&self, point: [f32; 2]) -> (Vec3, Vec3) {
+        let x = (point[0] - self.origin[0]) / self.size[0] * 2.0 - 1.0;
+        let y = 1.0 - (point[1] - self.origin[1]) / self.size[1] * 2.0;
+        let near = self.inverse.project_point3(Vec3::new(x, y, 0.0));
+        let far = self.inverse.project_point3(Vec3::new(x, y, 1.0));
+        (near, (far - near).normalize_or_zero())
+    }
+}
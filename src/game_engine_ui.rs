@@ -1,18 +1,48 @@
 use imgui::*;
+use crate::session::Session;
 use crate::ui::SimpleGameUI;
+use crate::ui::detached_window::{DetachedContent, DetachedWindow};
 
 pub struct GameEngineUI {
     simple_ui: SimpleGameUI,
 }
 
 impl GameEngineUI {
-    pub fn new() -> Self {
+    pub fn new(session: &Session) -> Self {
         Self {
-            simple_ui: SimpleGameUI::new(),
+            simple_ui: SimpleGameUI::from_session(session),
         }
     }
 
     pub fn render(&mut self, ui: &Ui) {
         self.simple_ui.render(ui);
     }
+
+    pub fn request_exit(&mut self) {
+        self.simple_ui.request_exit();
+    }
+
+    pub fn should_exit(&self) -> bool {
+        self.simple_ui.should_exit()
+    }
+
+    pub fn session(&self) -> Session {
+        self.simple_ui.session()
+    }
+
+    pub fn detached_windows(&self) -> Vec<DetachedWindow> {
+        self.simple_ui.detached_windows()
+    }
+
+    pub fn render_detached(&mut self, ui: &Ui, content: DetachedContent, origin: [f32; 2]) {
+        self.simple_ui.render_detached(ui, content, origin);
+    }
+
+    pub fn move_detached(&mut self, content: DetachedContent, position: [f32; 2], size: [f32; 2]) {
+        self.simple_ui.move_detached(content, position, size);
+    }
+
+    pub fn close_detached(&mut self, content: DetachedContent) {
+        self.simple_ui.close_detached(content);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,610 @@
+use glam::{EulerRot, Mat4, Quat, Vec2, Vec3};
+use imgui::{Key, MouseButton, Ui};
+use crate::editor_camera::ScreenProjection;
+use crate::history::History;
+use crate::scene::{EntityId, FieldValue, Scene, SetField, Transform};
+
+const GIZMO_SIZE: f32 = 90.0; // axis length in pixels
+const HIT_DISTANCE: f32 = 8.0; // pixels
+const RING_SEGMENTS: usize = 48;
+
+const AXIS_COLORS: [[f32; 4]; 3] = [
+    [0.95, 0.30, 0.30, 1.0],
+    [0.35, 0.90, 0.35, 1.0],
+    [0.35, 0.50, 1.00, 1.0],
+];
+const HOT_COLOR: [f32; 4] = [1.0, 0.85, 0.2, 1.0];
+const CENTER_COLOR: [f32; 4] = [0.9, 0.9, 0.9, 1.0];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GizmoTool {
+    Select,
+    Move,
+    Rotate,
+    Scale,
+}
+
+impl GizmoTool {
+    pub const ALL: [GizmoTool; 4] = [GizmoTool::Select, GizmoTool::Move, GizmoTool::Rotate, GizmoTool::Scale];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Select => "🎩 Select",
+            Self::Move => "↔ Move",
+            Self::Rotate => "🔄 Rotate",
+            Self::Scale => "🔍 Scale",
+        }
+    }
+
+    fn field(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Self::Select => None,
+            Self::Move => Some(("position", "Position")),
+            Self::Rotate => Some(("rotation", "Rotation")),
+            Self::Scale => Some(("scale", "Scale")),
+        }
+    }
+}
+
+/// Orientation of the gizmo axes. Scaling always uses the entity's own axes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GizmoSpace {
+    World,
+    Local,
+}
+
+/// Increments used while snapping is on (or Ctrl is held with it off)
+#[derive(Debug, Clone, Copy)]
+pub struct SnapSettings {
+    pub translate: f32, // world units, matches the viewport grid
+    pub rotate: f32,    // degrees
+    pub scale: f32,     // scale factor
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        Self { translate: 1.0, rotate: 15.0, scale: 0.1 }
+    }
+}
+
+/// Part of the gizmo under the cursor or being dragged
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Handle {
+    Axis(usize),
+    Plane(usize), // index of the plane's normal axis
+    Center,
+}
+
+/// Where the gizmo is drawn this frame
+struct GizmoFrame {
+    origin: Vec3,
+    axes: [Vec3; 3],
+    length: f32, // world units for GIZMO_SIZE pixels at the origin
+}
+
+struct GizmoDrag {
+    entity: EntityId,
+    handle: Handle,
+    start: Transform,
+    parent: Mat4,
+    origin: Vec3,
+    axes: [Vec3; 3],
+    start_mouse: [f32; 2],
+    start_hit: Vec3, // axis/plane point under the cursor when the drag started
+    last_angle: f32, // screen angle around the origin, for rotation
+    angle: f32, // accumulated rotation in degrees
+    typed: String, // numeric value typed while dragging
+}
+
+/// Translate/rotate/scale handles for the selected entity
+pub struct Gizmo {
+    pub tool: GizmoTool,
+    pub space: GizmoSpace,
+    pub snap_enabled: bool,
+    pub snap: SnapSettings,
+    hot: Option<Handle>,
+    drag: Option<GizmoDrag>,
+}
+
+impl Gizmo {
+    pub fn new() -> Self {
+        Self {
+            tool: GizmoTool::Move,
+            space: GizmoSpace::World,
+            snap_enabled: false,
+            snap: SnapSettings::default(),
+            hot: None,
+            drag: None,
+        }
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// True while the cursor is over a handle, so viewport clicks there don't change the selection
+    pub fn is_hovered(&self) -> bool {
+        self.hot.is_some()
+    }
+
+    /// Handle input and draw the gizmo. Transform changes are applied to the scene live
+    /// while dragging and recorded as a single undo step when the drag ends.
+    pub fn render(
+        &mut self,
+        ui: &Ui,
+        projection: &ScreenProjection,
+        scene: &mut Scene,
+        history: &mut History<Scene>,
+        selected: Option<EntityId>,
+        hovered: bool,
+    ) {
+        let entity = selected.filter(|id| scene.contains(*id));
+        if self.tool == GizmoTool::Select || entity.is_none() || self.drag.as_ref().map(|d| d.entity) != entity {
+            self.cancel_drag(scene);
+        }
+        self.hot = None;
+        let Some(entity) = entity else { return };
+        if self.tool == GizmoTool::Select {
+            return;
+        }
+        let Some(frame) = self.frame(scene, entity, projection) else { return };
+        let mouse = ui.io().mouse_pos;
+
+        if self.drag.is_some() {
+            self.update_drag(ui, projection, scene, history);
+        } else if hovered && !ui.io().key_alt {
+            self.hot = self.hit_test(&frame, projection, mouse);
+            if let Some(handle) = self.hot {
+                if ui.is_mouse_clicked(MouseButton::Left) {
+                    self.begin_drag(scene, entity, handle, &frame, projection, mouse);
+                }
+            }
+        }
+
+        let active = self.drag.as_ref().map(|d| d.handle).or(self.hot);
+        self.draw(ui, &frame, projection, active);
+    }
+
+    fn snapping(&self, ui: &Ui) -> bool {
+        self.snap_enabled != ui.io().key_ctrl
+    }
+
+    fn frame(&self, scene: &Scene, entity: EntityId, projection: &ScreenProjection) -> Option<GizmoFrame> {
+        let (_, rotation, origin) = scene.world_matrix(entity).to_scale_rotation_translation();
+        let axes = if self.space == GizmoSpace::Local || self.tool == GizmoTool::Scale {
+            [rotation * Vec3::X, rotation * Vec3::Y, rotation * Vec3::Z]
+        } else {
+            [Vec3::X, Vec3::Y, Vec3::Z]
+        };
+
+        // Keep a constant size on screen
+        let length = GIZMO_SIZE / projection.pixels_per_unit(origin)?;
+        Some(GizmoFrame { origin, axes, length })
+    }
+
+    fn hit_test(&self, frame: &GizmoFrame, projection: &ScreenProjection, mouse: [f32; 2]) -> Option<Handle> {
+        let mouse = Vec2::from(mouse);
+        let center = Vec2::from(projection.world_to_screen(frame.origin)?);
+        let screen = |p: Vec3| projection.world_to_screen(p).map(Vec2::from);
+
+        match self.tool {
+            GizmoTool::Select => None,
+            GizmoTool::Move => {
+                for normal in 0..3 {
+                    if let Some(quad) = plane_quad(frame, normal).iter().map(|&p| screen(p)).collect::<Option<Vec<_>>>() {
+                        if point_in_quad(mouse, &quad) {
+                            return Some(Handle::Plane(normal));
+                        }
+                    }
+                }
+                nearest_axis(frame, center, mouse, screen)
+            }
+            GizmoTool::Scale => {
+                if mouse.distance(center) < HIT_DISTANCE {
+                    return Some(Handle::Center);
+                }
+                nearest_axis(frame, center, mouse, screen)
+            }
+            GizmoTool::Rotate => {
+                let mut best: Option<(f32, Handle)> = None;
+                for axis in 0..3 {
+                    let ring: Option<Vec<Vec2>> = ring_points(frame, axis).into_iter().map(screen).collect();
+                    let Some(ring) = ring else { continue };
+                    let distance = ring
+                        .windows(2)
+                        .map(|w| distance_to_segment(mouse, w[0], w[1]))
+                        .fold(f32::MAX, f32::min);
+                    if distance < HIT_DISTANCE && best.map_or(true, |(d, _)| distance < d) {
+                        best = Some((distance, Handle::Axis(axis)));
+                    }
+                }
+                best.map(|(_, handle)| handle)
+            }
+        }
+    }
+
+    fn begin_drag(
+        &mut self,
+        scene: &Scene,
+        entity: EntityId,
+        handle: Handle,
+        frame: &GizmoFrame,
+        projection: &ScreenProjection,
+        mouse: [f32; 2],
+    ) {
+        let Some(start) = scene.get(entity).and_then(|e| e.get_component::<Transform>()).cloned() else { return };
+        let parent = scene
+            .get(entity)
+            .and_then(|e| e.parent())
+            .map_or(Mat4::IDENTITY, |p| scene.world_matrix(p));
+
+        let ray = projection.ray(mouse);
+        let start_hit = match (self.tool, handle) {
+            (GizmoTool::Move, Handle::Axis(axis)) => {
+                let Some(t) = closest_on_axis(frame.origin, frame.axes[axis], ray) else { return };
+                frame.origin + frame.axes[axis] * t
+            }
+            (GizmoTool::Move, Handle::Plane(normal)) => {
+                let Some(hit) = intersect_plane(frame.origin, frame.axes[normal], ray) else { return };
+                hit
+            }
+            _ => frame.origin,
+        };
+
+        self.drag = Some(GizmoDrag {
+            entity,
+            handle,
+            start,
+            parent,
+            origin: frame.origin,
+            axes: frame.axes,
+            start_mouse: mouse,
+            start_hit,
+            last_angle: screen_angle(projection, frame.origin, mouse),
+            angle: 0.0,
+            typed: String::new(),
+        });
+    }
+
+    fn update_drag(&mut self, ui: &Ui, projection: &ScreenProjection, scene: &mut Scene, history: &mut History<Scene>) {
+        if ui.is_key_pressed(Key::Escape) {
+            self.cancel_drag(scene);
+            return;
+        }
+        let snapping = self.snapping(ui);
+        let snap = self.snap;
+        let tool = self.tool;
+        let Some(drag) = self.drag.as_mut() else { return };
+
+        // Numeric entry: typing a value replaces the dragged amount
+        for c in ui.io().input_queue_characters() {
+            if c.is_ascii_digit() || c == '.' || (c == '-' && drag.typed.is_empty()) {
+                drag.typed.push(c);
+            }
+        }
+        if ui.is_key_pressed(Key::Backspace) {
+            drag.typed.pop();
+        }
+        let typed: Option<f32> = drag.typed.parse().ok();
+
+        let mouse = ui.io().mouse_pos;
+        let ray = projection.ray(mouse);
+        let mut transform = drag.start.clone();
+
+        match (tool, drag.handle) {
+            (GizmoTool::Move, handle) => {
+                let offset = match handle {
+                    Handle::Axis(axis) => {
+                        let direction = drag.axes[axis];
+                        let amount = match typed {
+                            Some(value) => value,
+                            None => closest_on_axis(drag.origin, direction, ray)
+                                .map(|t| (drag.origin + direction * t - drag.start_hit).dot(direction))
+                                .map(|amount| snap_value(amount, snap.translate, snapping))
+                                .unwrap_or(0.0),
+                        };
+                        direction * amount
+                    }
+                    Handle::Plane(normal) => {
+                        let (u, v) = (drag.axes[(normal + 1) % 3], drag.axes[(normal + 2) % 3]);
+                        let (a, b) = match typed {
+                            Some(value) => (value, value),
+                            None => match intersect_plane(drag.origin, drag.axes[normal], ray) {
+                                Some(hit) => {
+                                    let delta = hit - drag.start_hit;
+                                    (
+                                        snap_value(delta.dot(u), snap.translate, snapping),
+                                        snap_value(delta.dot(v), snap.translate, snapping),
+                                    )
+                                }
+                                None => (0.0, 0.0),
+                            },
+                        };
+                        u * a + v * b
+                    }
+                    Handle::Center => Vec3::ZERO,
+                };
+                let world_position = drag.origin + offset;
+                transform.position = drag.parent.inverse().transform_point3(world_position).to_array();
+            }
+            (GizmoTool::Rotate, Handle::Axis(axis)) => {
+                // Accumulate screen-space angle changes so full turns work
+                let angle = screen_angle(projection, drag.origin, mouse);
+                let mut delta = angle - drag.last_angle;
+                if delta > std::f32::consts::PI {
+                    delta -= std::f32::consts::TAU;
+                } else if delta < -std::f32::consts::PI {
+                    delta += std::f32::consts::TAU;
+                }
+                drag.last_angle = angle;
+
+                // Screen y points down, so the sign depends on which way the axis faces
+                let direction = drag.axes[axis];
+                let facing = direction.dot(ray.1);
+                drag.angle += if facing > 0.0 { delta } else { -delta }.to_degrees();
+
+                let degrees = typed.unwrap_or_else(|| snap_value(drag.angle, snap.rotate, snapping));
+                let (_, parent_rotation, _) = drag.parent.to_scale_rotation_translation();
+                let world_rotation = Quat::from_axis_angle(direction, degrees.to_radians())
+                    * parent_rotation
+                    * drag.start.rotation_quat();
+                let local = parent_rotation.inverse() * world_rotation;
+                let (y, x, z) = local.to_euler(EulerRot::YXZ);
+                transform.rotation = [x.to_degrees(), y.to_degrees(), z.to_degrees()];
+            }
+            (GizmoTool::Scale, handle) => {
+                let moved = Vec2::from(mouse) - Vec2::from(drag.start_mouse);
+                let raw = match handle {
+                    Handle::Axis(axis) => {
+                        let center = projection.world_to_screen(drag.origin).map(Vec2::from);
+                        let tip = projection.world_to_screen(drag.origin + drag.axes[axis]).map(Vec2::from);
+                        match (center, tip) {
+                            (Some(center), Some(tip)) if center.distance(tip) > 1.0 => {
+                                let screen_axis = tip - center;
+                                1.0 + moved.dot(screen_axis.normalize()) / GIZMO_SIZE
+                            }
+                            _ => 1.0,
+                        }
+                    }
+                    _ => 1.0 + (moved.x - moved.y) / GIZMO_SIZE,
+                };
+                let factor = typed.unwrap_or_else(|| snap_value(raw, snap.scale, snapping).max(0.01));
+                match handle {
+                    Handle::Axis(axis) => transform.scale[axis] *= factor,
+                    _ => transform.scale = drag.start.scale.map(|s| s * factor),
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(current) = scene.get_mut(drag.entity).and_then(|e| e.get_component_mut::<Transform>()) {
+            *current = transform;
+        }
+
+        let finished = !ui.is_mouse_down(MouseButton::Left)
+            || ui.is_key_pressed(Key::Enter)
+            || ui.is_key_pressed(Key::KeypadEnter);
+        if finished {
+            self.commit_drag(scene, history);
+        }
+    }
+
+    /// Record the finished drag as one undoable field edit
+    fn commit_drag(&mut self, scene: &mut Scene, history: &mut History<Scene>) {
+        let Some(drag) = self.drag.take() else { return };
+        let Some((field, label)) = self.tool.field() else { return };
+        let Some(current) = scene.get(drag.entity).and_then(|e| e.get_component::<Transform>()).cloned() else { return };
+
+        let value = |t: &Transform| match field {
+            "position" => FieldValue::Vec3(t.position),
+            "rotation" => FieldValue::Vec3(t.rotation),
+            _ => FieldValue::Vec3(t.scale),
+        };
+        let (old, new) = (value(&drag.start), value(&current));
+        if old != new {
+            history.seal();
+            history.push(Box::new(SetField {
+                entity: drag.entity,
+                component: "Transform",
+                field,
+                label,
+                old,
+                new,
+            }));
+            history.seal();
+        }
+    }
+
+    /// Abort the current drag, putting the entity back where it started
+    pub fn cancel_drag(&mut self, scene: &mut Scene) {
+        let Some(drag) = self.drag.take() else { return };
+        if let Some(current) = scene.get_mut(drag.entity).and_then(|e| e.get_component_mut::<Transform>()) {
+            *current = drag.start;
+        }
+    }
+
+    fn draw(&self, ui: &Ui, frame: &GizmoFrame, projection: &ScreenProjection, active: Option<Handle>) {
+        let Some(center) = projection.world_to_screen(frame.origin) else { return };
+        let origin = projection.origin();
+        let size = projection.size();
+        let draw_list = ui.get_window_draw_list();
+        let color = |handle: Handle, base: [f32; 4]| if active == Some(handle) { HOT_COLOR } else { base };
+
+        draw_list.with_clip_rect_intersect(origin, [origin[0] + size[0], origin[1] + size[1]], || {
+            match self.tool {
+                GizmoTool::Select => {}
+                GizmoTool::Move | GizmoTool::Scale => {
+                    if self.tool == GizmoTool::Move {
+                        for normal in 0..3 {
+                            let quad: Option<Vec<[f32; 2]>> =
+                                plane_quad(frame, normal).iter().map(|&p| projection.world_to_screen(p)).collect();
+                            let Some(quad) = quad else { continue };
+                            let mut fill = color(Handle::Plane(normal), AXIS_COLORS[normal]);
+                            fill[3] = 0.35;
+                            draw_list.add_polyline(quad.clone(), fill).filled(true).build();
+                            draw_list.add_polyline(quad, AXIS_COLORS[normal]).thickness(1.0).build();
+                        }
+                    }
+
+                    for axis in 0..3 {
+                        let Some(tip) = projection.world_to_screen(frame.origin + frame.axes[axis] * frame.length) else { continue };
+                        let axis_color = color(Handle::Axis(axis), AXIS_COLORS[axis]);
+                        draw_list.add_line(center, tip, axis_color).thickness(3.0).build();
+
+                        if self.tool == GizmoTool::Move {
+                            let direction = (Vec2::from(tip) - Vec2::from(center)).normalize_or_zero();
+                            let side = direction.perp() * 5.0;
+                            let end = Vec2::from(tip) + direction * 12.0;
+                            draw_list
+                                .add_triangle((Vec2::from(tip) + side).to_array(), (Vec2::from(tip) - side).to_array(), end.to_array(), axis_color)
+                                .filled(true)
+                                .build();
+                        } else {
+                            draw_list
+                                .add_rect([tip[0] - 5.0, tip[1] - 5.0], [tip[0] + 5.0, tip[1] + 5.0], axis_color)
+                                .filled(true)
+                                .build();
+                        }
+                    }
+
+                    if self.tool == GizmoTool::Scale {
+                        draw_list
+                            .add_rect([center[0] - 6.0, center[1] - 6.0], [center[0] + 6.0, center[1] + 6.0], color(Handle::Center, CENTER_COLOR))
+                            .filled(true)
+                            .build();
+                    }
+                }
+                GizmoTool::Rotate => {
+                    for axis in 0..3 {
+                        let ring: Option<Vec<[f32; 2]>> =
+                            ring_points(frame, axis).into_iter().map(|p| projection.world_to_screen(p)).collect();
+                        let Some(ring) = ring else { continue };
+                        draw_list.add_polyline(ring, color(Handle::Axis(axis), AXIS_COLORS[axis])).thickness(2.5).build();
+                    }
+                }
+            }
+
+            draw_list.add_circle(center, 3.0, CENTER_COLOR).filled(true).build();
+
+            // Readout of the dragged amount, or the value being typed
+            if let Some(drag) = &self.drag {
+                let text = if !drag.typed.is_empty() {
+                    format!("= {}", drag.typed)
+                } else if self.tool == GizmoTool::Rotate {
+                    format!("{:.1}°", drag.angle)
+                } else {
+                    String::from("Type a value, Enter to apply, Esc to cancel")
+                };
+                draw_list.add_text([center[0] + 12.0, center[1] + 12.0], HOT_COLOR, text);
+            }
+        });
+    }
+}
+
+impl Default for Gizmo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn snap_value(value: f32, step: f32, enabled: bool) -> f32 {
+    if enabled && step > 0.0 {
+        (value / step).round() * step
+    } else {
+        value
+    }
+}
+
+/// Corners of the small square used to drag in the plane perpendicular to `normal`
+fn plane_quad(frame: &GizmoFrame, normal: usize) -> [Vec3; 4] {
+    let u = frame.axes[(normal + 1) % 3] * frame.length;
+    let v = frame.axes[(normal + 2) % 3] * frame.length;
+    let (near, far) = (0.2, 0.4);
+    [
+        frame.origin + u * near + v * near,
+        frame.origin + u * far + v * near,
+        frame.origin + u * far + v * far,
+        frame.origin + u * near + v * far,
+    ]
+}
+
+fn ring_points(frame: &GizmoFrame, axis: usize) -> Vec<Vec3> {
+    let u = frame.axes[(axis + 1) % 3];
+    let v = frame.axes[(axis + 2) % 3];
+    (0..=RING_SEGMENTS)
+        .map(|i| {
+            let theta = i as f32 / RING_SEGMENTS as f32 * std::f32::consts::TAU;
+            frame.origin + (u * theta.cos() + v * theta.sin()) * frame.length
+        })
+        .collect()
+}
+
+fn nearest_axis(
+    frame: &GizmoFrame,
+    center: Vec2,
+    mouse: Vec2,
+    screen: impl Fn(Vec3) -> Option<Vec2>,
+) -> Option<Handle> {
+    let mut best: Option<(f32, Handle)> = None;
+    for axis in 0..3 {
+        let Some(tip) = screen(frame.origin + frame.axes[axis] * frame.length) else { continue };
+        let distance = distance_to_segment(mouse, center, tip);
+        if distance < HIT_DISTANCE && best.map_or(true, |(d, _)| distance < d) {
+            best = Some((distance, Handle::Axis(axis)));
+        }
+    }
+    best.map(|(_, handle)| handle)
+}
+
+fn distance_to_segment(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let t = if ab.length_squared() > 0.0 {
+        ((point - a).dot(ab) / ab.length_squared()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    point.distance(a + ab * t)
+}
+
+fn point_in_quad(point: Vec2, quad: &[Vec2]) -> bool {
+    // Inside a convex polygon when on the same side of every edge
+    let mut sign = 0.0;
+    for i in 0..quad.len() {
+        let (a, b) = (quad[i], quad[(i + 1) % quad.len()]);
+        let cross = (b - a).perp_dot(point - a);
+        if cross.abs() < 1e-6 {
+            continue;
+        }
+        if sign == 0.0 {
+            sign = cross.signum();
+        } else if cross.signum() != sign {
+            return false;
+        }
+    }
+    sign != 0.0
+}
+
+/// Parameter along the axis line closest to the ray, or None when the axis points at the camera
+fn closest_on_axis(origin: Vec3, direction: Vec3, (ray_origin, ray_direction): (Vec3, Vec3)) -> Option<f32> {
+    let w = origin - ray_origin;
+    let b = direction.dot(ray_direction);
+    let denom = 1.0 - b * b;
+    if denom.abs() < 1e-4 {
+        return None;
+    }
+    Some((b * ray_direction.dot(w) - direction.dot(w)) / denom)
+}
+
+fn intersect_plane(origin: Vec3, normal: Vec3, (ray_origin, ray_direction): (Vec3, Vec3)) -> Option<Vec3> {
+    let denom = normal.dot(ray_direction);
+    if denom.abs() < 1e-4 {
+        return None;
+    }
+    let s = normal.dot(origin - ray_origin) / denom;
+    Some(ray_origin + ray_direction * s)
+}
+
+fn screen_angle(projection: &ScreenProjection, origin: Vec3, mouse: [f32; 2]) -> f32 {
+    let center = projection.world_to_screen(origin).unwrap_or(mouse);
+    (mouse[1] - center[1]).atan2(mouse[0] - center[0])
+}
@@ -0,0 +1,471 @@
+use std::any::Any;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Default memory budget for one undo history
+pub const DEFAULT_MEMORY_LIMIT: usize = 32 * 1024 * 1024;
+
+// Shared across all histories so the editor can tell which one holds the most recent edit
+static NEXT_SEQUENCE: AtomicU64 = AtomicU64::new(1);
+
+fn next_sequence() -> u64 {
+    NEXT_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A reversible edit of a `T`
+pub trait Command<T>: Any {
+    /// Short description shown in the Edit menu ("Undo Rename")
+    fn label(&self) -> String;
+    fn apply(&mut self, target: &mut T);
+    fn revert(&mut self, target: &mut T);
+
+    /// Fold `next` into this command. Used to turn a slider drag into a single undo step.
+    fn merge(&mut self, _next: &dyn Command<T>) -> bool {
+        false
+    }
+
+    /// Approximate heap + inline size, used to enforce the memory limit
+    fn memory_size(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+struct Entry<T> {
+    command: Box<dyn Command<T>>,
+    sequence: u64,
+    size: usize,
+}
+
+/// Several commands undone and redone as a single step
+pub struct CommandGroup<T> {
+    label: String,
+    commands: Vec<Box<dyn Command<T>>>,
+}
+
+impl<T> CommandGroup<T> {
+    pub fn new(label: impl Into<String>, commands: Vec<Box<dyn Command<T>>>) -> Self {
+        Self { label: label.into(), commands }
+    }
+}
+
+impl<T: 'static> Command<T> for CommandGroup<T> {
+    fn label(&self) -> String {
+        self.label.clone()
+    }
+
+    fn apply(&mut self, target: &mut T) {
+        for command in &mut self.commands {
+            command.apply(target);
+        }
+    }
+
+    fn revert(&mut self, target: &mut T) {
+        for command in self.commands.iter_mut().rev() {
+            command.revert(target);
+        }
+    }
+
+    fn memory_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.label.len() + self.commands.iter().map(|c| c.memory_size()).sum::<usize>()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Undo/redo stack for one document
+pub struct History<T> {
+    undo_stack: VecDeque<Entry<T>>,
+    redo_stack: Vec<Entry<T>>,
+    memory_used: usize,
+    memory_limit: usize,
+    // Sequence of the state below the oldest undo entry (0 until entries are evicted)
+    base_sequence: u64,
+    // Sequence of the state that was saved, `None` once that state can no longer be reached
+    saved_sequence: Option<u64>,
+    merge_open: bool,
+}
+
+impl<T: 'static> History<T> {
+    pub fn new(memory_limit: usize) -> Self {
+        Self {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            memory_used: 0,
+            memory_limit,
+            base_sequence: 0,
+            saved_sequence: Some(0),
+            merge_open: false,
+        }
+    }
+
+    /// Apply `command` to `target` and record it
+    pub fn execute(&mut self, target: &mut T, mut command: Box<dyn Command<T>>) {
+        command.apply(target);
+        self.push(command);
+    }
+
+    /// Record a command whose effect has already been applied
+    pub fn push(&mut self, command: Box<dyn Command<T>>) {
+        if self.redo_stack.iter().any(|e| Some(e.sequence) == self.saved_sequence) {
+            self.saved_sequence = None;
+        }
+        for entry in self.redo_stack.drain(..) {
+            self.memory_used -= entry.size;
+        }
+
+        if self.merge_open {
+            if let Some(top) = self.undo_stack.back_mut() {
+                if Some(top.sequence) != self.saved_sequence && top.command.merge(command.as_ref()) {
+                    self.memory_used -= top.size;
+                    top.size = top.command.memory_size();
+                    top.sequence = next_sequence();
+                    self.memory_used += top.size;
+                    return;
+                }
+            }
+        }
+
+        let size = command.memory_size();
+        self.memory_used += size;
+        self.undo_stack.push_back(Entry { command, sequence: next_sequence(), size });
+        self.merge_open = true;
+        self.enforce_limit();
+    }
+
+    /// Stop merging into the last command, e.g. when a drag ends
+    pub fn seal(&mut self) {
+        self.merge_open = false;
+    }
+
+    pub fn undo(&mut self, target: &mut T) -> bool {
+        let Some(mut entry) = self.undo_stack.pop_back() else { return false };
+        entry.command.revert(target);
+        self.resize(&mut entry);
+        self.redo_stack.push(entry);
+        self.merge_open = false;
+        self.enforce_limit();
+        true
+    }
+
+    pub fn redo(&mut self, target: &mut T) -> bool {
+        let Some(mut entry) = self.redo_stack.pop() else { return false };
+        entry.command.apply(target);
+        self.resize(&mut entry);
+        self.undo_stack.push_back(entry);
+        self.merge_open = false;
+        self.enforce_limit();
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn undo_label(&self) -> Option<String> {
+        self.undo_stack.back().map(|e| e.command.label())
+    }
+
+    pub fn redo_label(&self) -> Option<String> {
+        self.redo_stack.last().map(|e| e.command.label())
+    }
+
+    /// The command `undo` would revert
+    pub fn undo_command(&self) -> Option<&dyn Command<T>> {
+        self.undo_stack.back().map(|e| e.command.as_ref())
+    }
+
+    /// The command `redo` would re-apply
+    pub fn redo_command(&self) -> Option<&dyn Command<T>> {
+        self.redo_stack.last().map(|e| e.command.as_ref())
+    }
+
+    /// Global sequence of the command `undo` would revert
+    pub fn undo_sequence(&self) -> Option<u64> {
+        self.undo_stack.back().map(|e| e.sequence)
+    }
+
+    /// Global sequence of the command `redo` would re-apply
+    pub fn redo_sequence(&self) -> Option<u64> {
+        self.redo_stack.last().map(|e| e.sequence)
+    }
+
+    /// Remember the current state as the saved one
+    pub fn mark_saved(&mut self) {
+        self.saved_sequence = Some(self.current_sequence());
+        self.merge_open = false;
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.saved_sequence != Some(self.current_sequence())
+    }
+
+    /// Approximate memory held by the recorded commands
+    pub fn memory_used(&self) -> usize {
+        self.memory_used
+    }
+
+    /// Forget all commands, treating the current state as saved
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.memory_used = 0;
+        self.base_sequence = 0;
+        self.saved_sequence = Some(0);
+        self.merge_open = false;
+    }
+
+    fn current_sequence(&self) -> u64 {
+        self.undo_sequence().unwrap_or(self.base_sequence)
+    }
+
+    // Commands like closing a tab or deleting an entity take ownership of what they
+    // remove, so their size changes each time they are applied or reverted
+    fn resize(&mut self, entry: &mut Entry<T>) {
+        self.memory_used -= entry.size;
+        entry.size = entry.command.memory_size();
+        self.memory_used += entry.size;
+    }
+
+    fn enforce_limit(&mut self) {
+        // Always keep the newest command, even if it alone exceeds the budget
+        while self.memory_used > self.memory_limit && self.undo_stack.len() > 1 {
+            if let Some(entry) = self.undo_stack.pop_front() {
+                self.memory_used -= entry.size;
+                if self.saved_sequence == Some(self.base_sequence) {
+                    self.saved_sequence = None;
+                }
+                self.base_sequence = entry.sequence;
+            }
+        }
+    }
+}
+
+impl<T: 'static> Default for History<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_MEMORY_LIMIT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Adds to a number. Merges with other adds, like a slider drag.
+    struct Add(i32);
+
+    impl Command<i32> for Add {
+        fn label(&self) -> String {
+            format!("Add {}", self.0)
+        }
+
+        fn apply(&mut self, target: &mut i32) {
+            *target += self.0;
+        }
+
+        fn revert(&mut self, target: &mut i32) {
+            *target -= self.0;
+        }
+
+        fn merge(&mut self, next: &dyn Command<i32>) -> bool {
+            match next.as_any().downcast_ref::<Add>() {
+                Some(next) => {
+                    self.0 += next.0;
+                    true
+                }
+                None => false,
+            }
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    /// Takes the value when applied and gives it back on revert, so its size depends
+    /// on which way it was last run
+    struct Take(Option<Vec<u8>>);
+
+    impl Command<Vec<u8>> for Take {
+        fn label(&self) -> String {
+            "Take".to_string()
+        }
+
+        fn apply(&mut self, target: &mut Vec<u8>) {
+            self.0 = Some(std::mem::take(target));
+        }
+
+        fn revert(&mut self, target: &mut Vec<u8>) {
+            *target = self.0.take().unwrap_or_default();
+        }
+
+        fn memory_size(&self) -> usize {
+            std::mem::size_of::<Self>() + self.0.as_ref().map_or(0, |v| v.len())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    fn add(history: &mut History<i32>, value: &mut i32, amount: i32) {
+        history.execute(value, Box::new(Add(amount)));
+    }
+
+    #[test]
+    fn undo_and_redo_restore_the_value() {
+        let mut history = History::default();
+        let mut value = 0;
+        add(&mut history, &mut value, 1);
+        history.seal();
+        add(&mut history, &mut value, 2);
+
+        assert!(history.undo(&mut value));
+        assert_eq!(value, 1);
+        assert!(history.undo(&mut value));
+        assert_eq!(value, 0);
+        assert!(!history.undo(&mut value));
+
+        assert!(history.redo(&mut value));
+        assert!(history.redo(&mut value));
+        assert_eq!(value, 3);
+        assert!(!history.redo(&mut value));
+    }
+
+    #[test]
+    fn commands_merge_until_sealed() {
+        let mut history = History::default();
+        let mut value = 0;
+        add(&mut history, &mut value, 1);
+        add(&mut history, &mut value, 2);
+        assert_eq!(history.undo_label().as_deref(), Some("Add 3"));
+
+        history.seal();
+        add(&mut history, &mut value, 4);
+        history.undo(&mut value);
+        assert_eq!(value, 3);
+        history.undo(&mut value);
+        assert_eq!(value, 0);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn undo_seals_the_merged_command() {
+        let mut history = History::default();
+        let mut value = 0;
+        add(&mut history, &mut value, 1);
+        add(&mut history, &mut value, 1);
+        history.undo(&mut value);
+        add(&mut history, &mut value, 5);
+        assert_eq!(history.undo_label().as_deref(), Some("Add 5"));
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn dirty_follows_the_saved_state() {
+        let mut history = History::default();
+        let mut value = 0;
+        assert!(!history.is_dirty());
+
+        add(&mut history, &mut value, 1);
+        assert!(history.is_dirty());
+        history.mark_saved();
+        assert!(!history.is_dirty());
+
+        history.undo(&mut value);
+        assert!(history.is_dirty());
+        history.redo(&mut value);
+        assert!(!history.is_dirty());
+    }
+
+    #[test]
+    fn saved_command_is_not_merged_into() {
+        let mut history = History::default();
+        let mut value = 0;
+        add(&mut history, &mut value, 1);
+        history.mark_saved();
+        add(&mut history, &mut value, 1);
+        assert!(history.is_dirty());
+
+        history.undo(&mut value);
+        assert_eq!(value, 1);
+        assert!(!history.is_dirty());
+    }
+
+    #[test]
+    fn saved_state_is_lost_when_redo_is_discarded() {
+        let mut history = History::default();
+        let mut value = 0;
+        add(&mut history, &mut value, 1);
+        history.mark_saved();
+        history.undo(&mut value);
+        add(&mut history, &mut value, 2);
+
+        // The saved state was only reachable by redo
+        assert!(history.is_dirty());
+        history.undo(&mut value);
+        assert_eq!(value, 0);
+        assert!(history.is_dirty());
+    }
+
+    #[test]
+    fn memory_limit_evicts_the_oldest_commands() {
+        let size = std::mem::size_of::<Add>();
+        let mut history = History::new(size * 2);
+        let mut value = 0;
+        for amount in 1..=4 {
+            add(&mut history, &mut value, amount);
+            history.seal();
+        }
+        assert_eq!(history.memory_used(), size * 2);
+
+        assert!(history.undo(&mut value));
+        assert!(history.undo(&mut value));
+        assert!(!history.undo(&mut value));
+        assert_eq!(value, 3);
+    }
+
+    #[test]
+    fn evicting_the_saved_state_leaves_history_dirty() {
+        let size = std::mem::size_of::<Add>();
+        let mut history = History::new(size);
+        let mut value = 0;
+        add(&mut history, &mut value, 1);
+        history.mark_saved();
+        add(&mut history, &mut value, 2);
+        history.seal();
+        add(&mut history, &mut value, 3);
+
+        // Only the last command is kept, so the saved value of 1 can't be reached
+        history.undo(&mut value);
+        assert_eq!(value, 3);
+        assert!(!history.can_undo());
+        assert!(history.is_dirty());
+    }
+
+    #[test]
+    fn memory_used_tracks_commands_that_grow() {
+        let base = std::mem::size_of::<Take>();
+        let mut history = History::default();
+        let mut data = vec![0u8; 100];
+        history.execute(&mut data, Box::new(Take(None)));
+        assert_eq!(history.memory_used(), base + 100);
+
+        history.undo(&mut data);
+        assert_eq!(data.len(), 100);
+        assert_eq!(history.memory_used(), base);
+
+        history.redo(&mut data);
+        assert_eq!(history.memory_used(), base + 100);
+
+        history.clear();
+        assert_eq!(history.memory_used(), 0);
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::sync::Mutex;
+use imgui::Ui;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use crate::actions::{ActionRegistry, Shortcut};
+use crate::session::{config_dir, write_ron};
+
+const KEYBINDINGS_FILE: &str = "keybindings.ron";
+
+/// Where keyboard focus is, which decides the shortcuts that apply
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum KeyScope {
+    #[default]
+    Global,
+    Viewport, // the 3D viewport is hovered or being dragged in
+    Hierarchy, // the Hierarchy panel has focus
+    AssetBrowser, // the Asset Browser's contents have focus
+    TextEditing, // a text field has the keyboard
+}
+
+impl KeyScope {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Global => "Global",
+            Self::Viewport => "Viewport",
+            Self::Hierarchy => "Hierarchy",
+            Self::AssetBrowser => "Asset Browser",
+            Self::TextEditing => "Text Editing",
+        }
+    }
+
+    /// Scopes whose bindings work with focus here, most specific first
+    pub fn active_scopes(&self) -> &'static [KeyScope] {
+        match self {
+            Self::Global => &[KeyScope::Global],
+            Self::Viewport => &[KeyScope::Viewport, KeyScope::Global],
+            Self::Hierarchy => &[KeyScope::Hierarchy, KeyScope::Global],
+            Self::AssetBrowser => &[KeyScope::AssetBrowser, KeyScope::Global],
+            Self::TextEditing => &[KeyScope::TextEditing, KeyScope::Global],
+        }
+    }
+}
+
+/// User shortcuts, saved apart from the session so they apply to every project
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct KeybindingsFile {
+    overrides: BTreeMap<String, Vec<Shortcut>>,
+}
+
+lazy_static! {
+    // Scope UI code claimed during the frame, for shortcuts handled at the start of the next
+    static ref CLAIMED_SCOPE: Mutex<Option<KeyScope>> = Mutex::new(None);
+    // Shortcut text by action id, for tooltips drawn without access to the registry
+    static ref SHORTCUT_LABELS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+/// Apply the user's saved shortcuts to `actions`
+pub fn load(actions: &mut ActionRegistry) {
+    let path = config_dir().join(KEYBINDINGS_FILE);
+    let file: KeybindingsFile = match fs::read_to_string(&path) {
+        Ok(text) => ron::de::from_str(&text).unwrap_or_else(|e| {
+            log::warn!("Ignoring corrupt keybindings file {}: {}", path.display(), e);
+            KeybindingsFile::default()
+        }),
+        Err(_) => KeybindingsFile::default(),
+    };
+    actions.set_overrides(file.overrides);
+    publish(actions);
+}
+
+/// Write the user's shortcuts to disk
+pub fn save(actions: &ActionRegistry) {
+    let file = KeybindingsFile { overrides: actions.overrides().clone() };
+    let path = config_dir().join(KEYBINDINGS_FILE);
+    if let Err(e) = write_ron(&path, &file) {
+        log::error!("Failed to save keybindings to {}: {}", path.display(), e);
+    }
+    publish(actions);
+}
+
+/// Make the current shortcuts available to `shortcut_label`
+pub fn publish(actions: &ActionRegistry) {
+    let mut labels = SHORTCUT_LABELS.lock().unwrap();
+    labels.clear();
+    for action in actions.actions() {
+        if let Some(shortcut) = action.shortcut() {
+            labels.insert(action.id.clone(), shortcut.to_string());
+        }
+    }
+}
+
+/// The shortcut an action is bound to, as shown to the user
+pub fn shortcut_label(id: &str) -> Option<String> {
+    SHORTCUT_LABELS.lock().unwrap().get(id).cloned()
+}
+
+/// `text` followed by the action's shortcut in brackets, if it has one
+pub fn with_shortcut(text: &str, id: &str) -> String {
+    match shortcut_label(id) {
+        Some(label) => format!("{} ({})", text, label),
+        None => text.to_string(),
+    }
+}
+
+/// Mark keyboard focus as being in `scope` for this frame, e.g. while the viewport
+/// is hovered
+pub fn claim_scope(scope: KeyScope) {
+    *CLAIMED_SCOPE.lock().unwrap() = Some(scope);
+}
+
+/// Where keyboard focus was last frame: in a text field if one has the keyboard,
+/// else wherever UI code claimed. Call once at the start of a frame.
+pub fn take_focus(ui: &Ui) -> KeyScope {
+    let claimed = CLAIMED_SCOPE.lock().unwrap().take();
+    if ui.io().want_text_input {
+        KeyScope::TextEditing
+    } else {
+        claimed.unwrap_or_default()
+    }
+}
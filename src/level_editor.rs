@@ -1,49 +1,227 @@
 use imgui::*;
+use crate::history::{Command, History};
+use crate::project::{self, SceneFile};
+use crate::scene::{ComponentRegistry, Scene};
 use crate::tab_system::TabContent;
+use crate::textures::{self, TextureHandle};
+use crate::tile_map::{line_cells, tile_color, Tile, TileChange, TileMap, EMPTY_TILE, TILE_PALETTE};
+use std::any::Any;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 use rayon::prelude::*;
 use image::{RgbaImage, Rgba};
+use serde::{Deserialize, Serialize};
 
 pub struct LevelEditor {
     title: String,
-    is_dirty: bool,
 
     // Viewport state
-    viewport_texture: Option<TextureId>,
+    viewport_texture: TextureHandle,
     viewport_size: [u32; 2],
     animation_start: Instant,
 
     // Level data
-    level_data: String,
+    level: Level,
+    history: History<Level>,
+    file_path: Option<PathBuf>,
+    load_waiting: bool, // Load pressed with unsaved changes, waiting on the prompt
+    status: Option<(String, bool)>, // (message, is_error)
 
     // Tools
     selected_tool: Tool,
+    selected_tile: Tile,
     brush_size: f32,
+    stroke_last: Option<(i32, i32)>,
+    rect_start: Option<(i32, i32)>,
 }
 
-#[derive(PartialEq, Clone)]
+/// Everything in a level that edits can change, so undo commands have one target
+struct Level {
+    scene: Scene,
+    tiles: TileMap,
+}
+
+/// Cells changed by one tool stroke
+struct PaintTiles {
+    label: &'static str,
+    changes: Vec<TileChange>,
+}
+
+impl Command<Level> for PaintTiles {
+    fn label(&self) -> String {
+        self.label.to_string()
+    }
+
+    fn apply(&mut self, level: &mut Level) {
+        for change in &self.changes {
+            level.tiles.set(change.x, change.y, change.new);
+        }
+    }
+
+    fn revert(&mut self, level: &mut Level) {
+        // Reverse order so cells touched twice in one stroke end up at their first value
+        for change in self.changes.iter().rev() {
+            level.tiles.set(change.x, change.y, change.old);
+        }
+    }
+
+    fn merge(&mut self, next: &dyn Command<Level>) -> bool {
+        match next.as_any().downcast_ref::<Self>() {
+            Some(next) if next.label == self.label => {
+                self.changes.extend_from_slice(&next.changes);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn memory_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.changes.len() * std::mem::size_of::<TileChange>()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Tile layer and tool settings saved in the level file alongside the scene
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct LevelEditorState {
+    tiles: TileMap,
+    selected_tool: Tool,
+    selected_tile: Tile,
+    brush_size: f32,
+}
+
+impl Default for LevelEditorState {
+    fn default() -> Self {
+        Self {
+            tiles: TileMap::default(),
+            selected_tool: Tool::Brush,
+            selected_tile: 1,
+            brush_size: 3.0,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
 enum Tool {
     Brush,
     Pencil,
     Eraser,
+    Fill,
+    Rectangle,
+}
+
+impl Tool {
+    fn undo_label(&self) -> &'static str {
+        match self {
+            Self::Brush => "Brush Stroke",
+            Self::Pencil => "Pencil Stroke",
+            Self::Eraser => "Erase",
+            Self::Fill => "Flood Fill",
+            Self::Rectangle => "Rectangle Fill",
+        }
+    }
+}
+
+/// Screen-space placement of the tile map inside the viewport
+struct TileCanvas {
+    origin: [f32; 2],
+    cell_size: f32,
+}
+
+impl TileCanvas {
+    fn cell_at(&self, pos: [f32; 2]) -> (i32, i32) {
+        (
+            ((pos[0] - self.origin[0]) / self.cell_size).floor() as i32,
+            ((pos[1] - self.origin[1]) / self.cell_size).floor() as i32,
+        )
+    }
+
+    fn cell_min(&self, x: i32, y: i32) -> [f32; 2] {
+        [self.origin[0] + x as f32 * self.cell_size, self.origin[1] + y as f32 * self.cell_size]
+    }
 }
 
 impl LevelEditor {
     pub fn new(title: String) -> Self {
         Self {
+            level: Level {
+                scene: Scene::new(&title),
+                tiles: TileMap::default(),
+            },
+            history: History::default(),
             title,
-            is_dirty: false,
-            viewport_texture: None,
+            viewport_texture: textures::create_handle(),
             viewport_size: [800, 600],
             animation_start: Instant::now(),
-            level_data: "Level data goes here...".to_string(),
+            file_path: None,
+            load_waiting: false,
+            status: None,
             selected_tool: Tool::Brush,
-            brush_size: 10.0,
+            selected_tile: 1,
+            brush_size: 3.0,
+            stroke_last: None,
+            rect_start: None,
+        }
+    }
+
+    fn save_level(&mut self, save_as: bool) {
+        let path = match &self.file_path {
+            Some(path) if !save_as => Some(path.clone()),
+            _ => project::pick_save_path("Save Level", &self.title),
+        };
+        let Some(path) = path else { return };
+
+        let state = LevelEditorState {
+            tiles: self.level.tiles.clone(),
+            selected_tool: self.selected_tool.clone(),
+            selected_tile: self.selected_tile,
+            brush_size: self.brush_size,
+        };
+        match SceneFile::new(&self.level.scene, state).save(&path) {
+            Ok(()) => {
+                self.status = Some((format!("Saved {}", path.display()), false));
+                self.file_path = Some(path);
+                self.history.mark_saved();
+            }
+            Err(e) => self.status = Some((format!("Save failed: {}", e), true)),
+        }
+    }
+
+    fn load(&mut self) {
+        if let Some(path) = project::pick_open_path("Open Level") {
+            self.load_file(path);
+        }
+    }
+
+    fn load_file(&mut self, path: PathBuf) {
+        let result = SceneFile::<LevelEditorState>::load(&path)
+            .and_then(|file| Ok((file.build_scene(&path, &ComponentRegistry::default())?, file.editor)));
+
+        match result {
+            Ok((_, state)) if !state.tiles.is_valid() => {
+                self.status = Some(("Load failed: tile layer is too big or its data does not match its size".to_string(), true));
+            }
+            Ok((scene, state)) => {
+                self.level = Level { scene, tiles: state.tiles };
+                self.selected_tool = state.selected_tool;
+                self.selected_tile = state.selected_tile;
+                self.brush_size = state.brush_size;
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    self.title = stem.to_string();
+                }
+                self.status = Some((format!("Loaded {}", path.display()), false));
+                self.file_path = Some(path);
+                self.history.clear();
+            }
+            Err(e) => self.status = Some((format!("Load failed: {}", e), true)),
         }
     }
 
     fn render_animated_viewport(&mut self, ui: &Ui, size: [f32; 2]) -> bool {
-        // Create or update the texture for our animated content
         let width = size[0] as u32;
         let height = size[1] as u32;
 
@@ -105,47 +283,167 @@ impl LevelEditor {
             image.put_pixel(x, y, *pixel);
         }
 
-        // For now, we'll just draw a colored rectangle since imgui-rs texture management
-        // is more complex and would require additional setup
-        let draw_list = ui.get_window_draw_list();
+        // Hand the frame to the renderer; the GPU texture is reallocated only on resize
+        self.viewport_size = [width, height];
+        textures::upload(self.viewport_texture, width, height, image.into_raw());
+
         let canvas_pos = ui.cursor_screen_pos();
-        let canvas_size = size;
-
-        // Draw animated background
-        let hue = (elapsed * 0.5) % 1.0;
-        let color = [
-            hue,
-            1.0 - hue,
-            (elapsed * 2.0) % 1.0,
-            0.5
-        ];
+        let canvas_max = [canvas_pos[0] + size[0], canvas_pos[1] + size[1]];
+        let draw_list = ui.get_window_draw_list();
+
+        match textures::texture_id(self.viewport_texture) {
+            Some(texture_id) => {
+                draw_list.add_image(texture_id, canvas_pos, canvas_max).build();
+            }
+            None => {
+                // First frame: the texture is created when this frame's upload is flushed
+                draw_list.add_rect(canvas_pos, canvas_max, [0.0, 0.0, 0.0, 1.0]).filled(true).build();
+            }
+        }
+
+        true
+    }
+
+    fn brush_radius(&self) -> f32 {
+        (self.brush_size - 1.0).max(0.0) * 0.5
+    }
+
+    /// Draw the tile layer over the viewport and let the active tool paint into it
+    fn render_tile_layer(&mut self, ui: &Ui, canvas_pos: [f32; 2], canvas_size: [f32; 2]) {
+        let (width, height) = (self.level.tiles.width(), self.level.tiles.height());
+        if width == 0 || height == 0 {
+            ui.dummy(canvas_size);
+            return;
+        }
+
+        // Fit the whole map, centred
+        let cell_size = (canvas_size[0] / width as f32).min(canvas_size[1] / height as f32).floor().max(1.0);
+        let map_size = [cell_size * width as f32, cell_size * height as f32];
+        let canvas = TileCanvas {
+            origin: [
+                canvas_pos[0] + ((canvas_size[0] - map_size[0]) * 0.5).floor(),
+                canvas_pos[1] + ((canvas_size[1] - map_size[1]) * 0.5).floor(),
+            ],
+            cell_size,
+        };
 
+        ui.set_cursor_screen_pos(canvas_pos);
+        ui.invisible_button("##tile_canvas", canvas_size);
+        let hovered = ui.is_item_hovered();
+        let mouse_cell = canvas.cell_at(ui.io().mouse_pos);
+
+        self.handle_tool_input(ui, mouse_cell);
+
+        let draw_list = ui.get_window_draw_list();
+        let map_max = [canvas.origin[0] + map_size[0], canvas.origin[1] + map_size[1]];
         draw_list
-            .add_rect(
-                canvas_pos,
-                [canvas_pos[0] + canvas_size[0], canvas_pos[1] + canvas_size[1]],
-                color,
-            )
+            .add_rect(canvas.origin, map_max, [0.0, 0.0, 0.0, 0.55])
             .filled(true)
             .build();
 
-        // Add some animated elements
-        let center_x = canvas_pos[0] + canvas_size[0] * 0.5;
-        let center_y = canvas_pos[1] + canvas_size[1] * 0.5;
-        let radius = 50.0 + 20.0 * (elapsed * 3.0).sin();
+        for y in 0..height {
+            for x in 0..width {
+                if let Some(color) = self.level.tiles.get(x, y).and_then(tile_color) {
+                    let min = canvas.cell_min(x as i32, y as i32);
+                    draw_list
+                        .add_rect(min, [min[0] + cell_size, min[1] + cell_size], color)
+                        .filled(true)
+                        .build();
+                }
+            }
+        }
 
-        draw_list
-            .add_circle(
-                [center_x, center_y],
-                radius,
-                (255.0, 255.0, 255.0, 200.0),
-            )
-            .num_segments(32)
-            .build();
+        // Grid lines once cells are large enough to tell apart
+        if cell_size >= 6.0 {
+            let grid_color = [1.0, 1.0, 1.0, 0.06];
+            for x in 0..=width {
+                let px = canvas.origin[0] + x as f32 * cell_size;
+                draw_list.add_line([px, canvas.origin[1]], [px, map_max[1]], grid_color).build();
+            }
+            for y in 0..=height {
+                let py = canvas.origin[1] + y as f32 * cell_size;
+                draw_list.add_line([canvas.origin[0], py], [map_max[0], py], grid_color).build();
+            }
+        }
+        draw_list.add_rect(canvas.origin, map_max, [1.0, 1.0, 1.0, 0.3]).build();
+
+        // Tool preview
+        let preview_color = [1.0, 1.0, 1.0, 0.8];
+        if let Some(start) = self.rect_start {
+            let min = canvas.cell_min(start.0.min(mouse_cell.0), start.1.min(mouse_cell.1));
+            let max = canvas.cell_min(start.0.max(mouse_cell.0) + 1, start.1.max(mouse_cell.1) + 1);
+            draw_list.add_rect(min, max, preview_color).thickness(2.0).build();
+        } else if hovered && self.level.tiles.in_bounds(mouse_cell.0, mouse_cell.1) {
+            let min = canvas.cell_min(mouse_cell.0, mouse_cell.1);
+            match self.selected_tool {
+                Tool::Brush | Tool::Eraser if self.brush_radius() > 0.0 => {
+                    let center = [min[0] + cell_size * 0.5, min[1] + cell_size * 0.5];
+                    draw_list
+                        .add_circle(center, (self.brush_radius() + 0.5) * cell_size, preview_color)
+                        .num_segments(32)
+                        .build();
+                }
+                _ => {
+                    draw_list
+                        .add_rect(min, [min[0] + cell_size, min[1] + cell_size], preview_color)
+                        .build();
+                }
+            }
+        }
+    }
 
-        ui.dummy(canvas_size);
+    fn handle_tool_input(&mut self, ui: &Ui, cell: (i32, i32)) {
+        let tile = match self.selected_tool {
+            Tool::Eraser => EMPTY_TILE,
+            _ => self.selected_tile,
+        };
+        let mut changes = Vec::new();
+
+        match self.selected_tool {
+            Tool::Brush | Tool::Pencil | Tool::Eraser => {
+                if ui.is_item_active() && ui.is_mouse_down(MouseButton::Left) {
+                    let radius = if self.selected_tool == Tool::Pencil { 0.0 } else { self.brush_radius() };
+                    // Fill in the cells between this frame and the last so fast strokes stay connected
+                    let from = self.stroke_last.unwrap_or(cell);
+                    let cells: Vec<(u32, u32)> = line_cells(from, cell)
+                        .into_iter()
+                        .flat_map(|(x, y)| self.level.tiles.circle_cells(x, y, radius))
+                        .collect();
+                    changes = self.level.tiles.paint(cells, tile);
+                    self.stroke_last = Some(cell);
+                } else {
+                    self.stroke_last = None;
+                }
+            }
+            Tool::Fill => {
+                if ui.is_item_clicked() && self.level.tiles.in_bounds(cell.0, cell.1) {
+                    let cells = self.level.tiles.flood_cells(cell.0 as u32, cell.1 as u32);
+                    changes = self.level.tiles.paint(cells, tile);
+                }
+            }
+            Tool::Rectangle => {
+                if ui.is_item_activated() {
+                    self.rect_start = Some(cell);
+                }
+                if ui.is_item_deactivated() {
+                    if let Some(start) = self.rect_start.take() {
+                        let cells = self.level.tiles.rect_cells(start, cell);
+                        changes = self.level.tiles.paint(cells, tile);
+                    }
+                }
+            }
+        }
 
-        true
+        if !changes.is_empty() {
+            let label = self.selected_tool.undo_label();
+            self.history.push(Box::new(PaintTiles { label, changes }));
+        }
+    }
+}
+
+impl Drop for LevelEditor {
+    fn drop(&mut self) {
+        textures::release(self.viewport_texture);
     }
 }
 
@@ -161,15 +459,24 @@ impl TabContent for LevelEditor {
 
         // Action buttons
         if ui.button("Save") {
-            // Handle save
-            self.is_dirty = false;
+            self.save_level(false);
         }
         ui.same_line();
         if ui.button("Load") {
-            // Handle load
+            // Loading replaces the level, so unsaved changes are asked about first
+            if self.is_dirty() {
+                self.load_waiting = true;
+            } else {
+                self.load();
+            }
         }
 
         ui.columns(1, "", false);
+
+        if let Some((message, is_error)) = &self.status {
+            let color = if *is_error { [1.0, 0.5, 0.5, 1.0] } else { [0.5, 1.0, 0.5, 1.0] };
+            ui.text_colored(color, message);
+        }
         ui.separator();
 
         // Main layout: tools | viewport | properties
@@ -181,20 +488,45 @@ impl TabContent for LevelEditor {
         ui.text("Tools");
         ui.separator();
 
-        let tools = [(Tool::Brush, "🖌️ Brush"), (Tool::Pencil, "✏️ Pencil"), (Tool::Eraser, "🧹 Eraser")];
+        let tools = [
+            (Tool::Brush, "🖌️ Brush"),
+            (Tool::Pencil, "✏️ Pencil"),
+            (Tool::Eraser, "🧹 Eraser"),
+            (Tool::Fill, "🪣 Fill"),
+            (Tool::Rectangle, "⬛ Rectangle"),
+        ];
 
         for (tool, label) in &tools {
             let selected = self.selected_tool == *tool;
             if ui.radio_button_bool(label, selected) {
                 self.selected_tool = tool.clone();
+                self.rect_start = None;
             }
         }
 
         ui.separator();
         ui.text("Brush Settings");
-        ui.slider_config("Size", 1.0, 100.0)
+        ui.slider_config("Size", 1.0, 16.0)
+            .display_format("%.0f cells")
             .build(&mut self.brush_size);
 
+        ui.separator();
+        ui.text("Tiles");
+        for (index, (name, color)) in TILE_PALETTE.iter().enumerate() {
+            let tile = index as Tile + 1;
+            let pos = ui.cursor_screen_pos();
+            let swatch = ui.text_line_height();
+            ui.get_window_draw_list()
+                .add_rect(pos, [pos[0] + swatch, pos[1] + swatch], *color)
+                .filled(true)
+                .build();
+            ui.dummy([swatch, swatch]);
+            ui.same_line();
+            if ui.selectable_config(name).selected(self.selected_tile == tile).build() {
+                self.selected_tile = tile;
+            }
+        }
+
         ui.next_column();
 
         // Middle: Animated Viewport
@@ -205,7 +537,9 @@ impl TabContent for LevelEditor {
         let viewport_size = [viewport_size[0], viewport_size[1] - 20.0]; // Reserve some space
 
         if viewport_size[0] > 0.0 && viewport_size[1] > 0.0 {
+            let canvas_pos = ui.cursor_screen_pos();
             self.render_animated_viewport(ui, viewport_size);
+            self.render_tile_layer(ui, canvas_pos, viewport_size);
         }
 
         ui.next_column();
@@ -216,18 +550,16 @@ impl TabContent for LevelEditor {
 
         ui.text("Level Information");
         ui.text(&format!("Size: {}x{}", self.viewport_size[0], self.viewport_size[1]));
+        ui.text(&format!("Tiles: {}x{}", self.level.tiles.width(), self.level.tiles.height()));
+        ui.text(&format!("Painted: {}", self.level.tiles.painted_count()));
 
         ui.separator();
         ui.text("Selected Object");
         ui.text("No object selected");
 
-        // Level data editor
-        ui.separator();
-        ui.text("Level Data");
-        if ui.input_text_multiline("##leveldata", &mut self.level_data, [0.0, 100.0])
-            .build()
-        {
-            self.is_dirty = true;
+        if let Some(label) = self.history.undo_label() {
+            ui.separator();
+            ui.text_colored([0.6, 0.6, 0.6, 1.0], &format!("Last edit: {}", label));
         }
 
         ui.columns(1, "", false);
@@ -238,10 +570,71 @@ impl TabContent for LevelEditor {
     }
 
     fn is_dirty(&self) -> bool {
-        self.is_dirty
+        self.history.is_dirty()
     }
 
     fn get_icon(&self) -> Option<&str> {
         Some("🎮")
     }
+
+    fn memory_size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.level.scene.memory_size()
+            + self.level.tiles.memory_size()
+            + self.history.memory_used()
+    }
+
+    fn can_save(&self) -> bool {
+        true
+    }
+
+    fn save(&mut self, save_as: bool) {
+        self.save_level(save_as);
+    }
+
+    fn file_path(&self) -> Option<&Path> {
+        self.file_path.as_deref()
+    }
+
+    fn open_file(&mut self, path: PathBuf) {
+        self.load_file(path);
+    }
+
+    fn wants_discard(&self) -> bool {
+        self.load_waiting
+    }
+
+    fn resume_discard(&mut self, proceed: bool) {
+        if std::mem::take(&mut self.load_waiting) && proceed {
+            self.load();
+        }
+    }
+
+    fn undo_sequence(&self) -> Option<u64> {
+        self.history.undo_sequence()
+    }
+
+    fn redo_sequence(&self) -> Option<u64> {
+        self.history.redo_sequence()
+    }
+
+    fn undo_label(&self) -> Option<String> {
+        self.history.undo_label()
+    }
+
+    fn redo_label(&self) -> Option<String> {
+        self.history.redo_label()
+    }
+
+    fn seal_history(&mut self) {
+        self.history.seal();
+    }
+
+    fn undo(&mut self) {
+        self.history.undo(&mut self.level);
+    }
+
+    fn redo(&mut self) {
+        self.history.redo(&mut self.level);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use crate::session::config_dir;
+
+/// How many records are kept for the Console
+const CAPACITY: usize = 10_000;
+
+/// A log record as kept for the Console
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub sequence: u64, // increases by one per record
+    pub time: Duration, // since the editor started
+    pub level: Level,
+    pub category: String, // module the record came from, e.g. "ui::layout_manager"
+    pub message: String,
+}
+
+impl LogEntry {
+    /// Time since the editor started, as "mm:ss.mmm"
+    pub fn timestamp(&self) -> String {
+        let millis = self.time.as_millis();
+        format!("{:02}:{:02}.{:03}", millis / 60_000, millis / 1000 % 60, millis % 1000)
+    }
+
+    /// The record as one line of text, as written to stderr and the log file
+    pub fn format_line(&self) -> String {
+        format!("[{}] {:<5} {}: {}", self.timestamp(), self.level, self.category, self.message)
+    }
+}
+
+/// Recent records, plus the file they are mirrored to
+struct LogSink {
+    entries: VecDeque<LogEntry>,
+    next_sequence: u64,
+    file: Option<(PathBuf, LineWriter<File>)>,
+}
+
+lazy_static! {
+    static ref START: Instant = Instant::now();
+    static ref SINK: Mutex<LogSink> = Mutex::new(LogSink {
+        entries: VecDeque::new(),
+        next_sequence: 1,
+        file: None,
+    });
+}
+
+/// Sends `log` records to stderr, the Console's ring buffer and, optionally, a file
+struct Logger;
+
+static LOGGER: Logger = Logger;
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        // Other crates are kept to warnings; wgpu logs every resource it creates
+        let max = if is_own(metadata.target()) { Level::Debug } else { Level::Warn };
+        metadata.level() <= max
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let mut sink = SINK.lock().unwrap();
+        let entry = LogEntry {
+            sequence: sink.next_sequence,
+            time: START.elapsed(),
+            level: record.level(),
+            category: category(record.target()),
+            message: record.args().to_string(),
+        };
+        sink.next_sequence += 1;
+
+        let line = entry.format_line();
+        if entry.level <= Level::Info {
+            eprintln!("{}", line);
+        }
+        if let Some((_, file)) = &mut sink.file {
+            let _ = writeln!(file, "{}", line);
+        }
+        if sink.entries.len() == CAPACITY {
+            sink.entries.pop_front();
+        }
+        sink.entries.push_back(entry);
+    }
+
+    fn flush(&self) {
+        if let Some((_, file)) = &mut SINK.lock().unwrap().file {
+            let _ = file.flush();
+        }
+    }
+}
+
+/// Route `log` records to the Console. Call once, before anything logs.
+pub fn init() {
+    lazy_static::initialize(&START);
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(LevelFilter::Debug);
+    }
+}
+
+/// Records logged after `sequence`, oldest first. Records that have already left
+/// the ring buffer are skipped.
+pub fn entries_since(sequence: u64) -> Vec<LogEntry> {
+    let sink = SINK.lock().unwrap();
+    // Sequences are consecutive, so the first new record's index follows from the oldest one's
+    let skip = sink.entries.front().map_or(0, |oldest| sequence.saturating_sub(oldest.sequence - 1) as usize);
+    sink.entries.iter().skip(skip).cloned().collect()
+}
+
+/// Start or stop copying records to a file, appending to it if it exists
+pub fn set_log_file(path: Option<&Path>) -> Result<(), String> {
+    let file = match path {
+        Some(path) => {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            let file = OpenOptions::new().create(true).append(true).open(path).map_err(|e| e.to_string())?;
+            Some((path.to_path_buf(), LineWriter::new(file)))
+        }
+        None => None,
+    };
+    SINK.lock().unwrap().file = file;
+    Ok(())
+}
+
+/// The file records are being copied to, if any
+pub fn log_file() -> Option<PathBuf> {
+    SINK.lock().unwrap().file.as_ref().map(|(path, _)| path.clone())
+}
+
+/// Where the Console mirrors the log when asked to
+pub fn default_log_file() -> PathBuf {
+    config_dir().join("logs").join("pulsar.log")
+}
+
+fn is_own(target: &str) -> bool {
+    target.split("::").next() == Some(module_path!().split("::").next().unwrap_or_default())
+}
+
+/// The target without this crate's name, or just the crate for other crates
+fn category(target: &str) -> String {
+    match target.split_once("::") {
+        Some((_, module)) if is_own(target) => module.to_string(),
+        Some((krate, _)) => krate.to_string(),
+        None => target.to_string(),
+    }
+}
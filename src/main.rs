@@ -1,27 +1,55 @@
+use std::collections::HashMap;
 use std::time::Instant;
-use imgui::*;
 use imgui_wgpu::{Renderer, RendererConfig};
-use imgui_winit_support::WinitPlatform;
 use winit::{
+    dpi::PhysicalPosition,
     event::{Event, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
+    event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget},
     window::WindowBuilder,
 };
 
 #[cfg(windows)]
 use windows_sys::Win32::System::Threading::{SetPriorityClass, GetCurrentProcess, HIGH_PRIORITY_CLASS};
 
+mod actions;
 mod app;
+mod assets;
+mod console;
+mod editor_camera;
 mod frame_counter;
+mod gizmo;
+mod history;
+mod keybindings;
+mod picking;
+mod play_mode;
 mod tab_system;
+mod textures;
+mod thumbnails;
 mod level_editor;
+mod logging;
+mod tile_map;
 mod game_engine_ui;
+mod project;
+mod scene;
+mod scene_renderer;
+mod session;
+mod simulation;
 mod ui;
+mod viewport;
 
 use app::App;
+use scene_renderer::SceneRenderer;
+use ui::detached_window::DetachedContent;
+use viewport::{Gpu, Viewport};
 
 #[tokio::main]
 async fn main() {
+    // Capture log records for the Console before anything can log
+    logging::init();
+    console::register_builtin_commands();
+    simulation::register_cvars();
+    viewport::register_cvars();
+
     // Set high process priority on Windows
     #[cfg(windows)]
     unsafe {
@@ -29,125 +57,128 @@ async fn main() {
         SetPriorityClass(handle, HIGH_PRIORITY_CLASS);
     }
 
+    // Reopen the window, tabs and panels the way the last session left them
+    let session = session::load();
+
     let event_loop = EventLoop::new();
     let window = WindowBuilder::new()
         .with_title("Pulsar Engine")
-        .with_inner_size(winit::dpi::LogicalSize::new(1600, 1000))
+        .with_inner_size(winit::dpi::LogicalSize::new(session.window.width, session.window.height))
         .with_resizable(true)
-        .with_maximized(false)
+        .with_maximized(session.window.maximized)
         .build(&event_loop)
         .unwrap();
 
     // Set up wgpu
-    let size = window.inner_size();
     let instance = wgpu::Instance::default();
     let surface = unsafe { instance.create_surface(&window).unwrap() };
-    let adapter = instance
-        .request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::HighPerformance,
-            force_fallback_adapter: false,
-            compatible_surface: Some(&surface),
-        })
-        .await
-        .expect("Failed to find an appropriate adapter");
+    let mut adapter_options = wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        force_fallback_adapter: false,
+        compatible_surface: Some(&surface),
+    };
+    let adapter = match instance.request_adapter(&adapter_options).await {
+        Some(adapter) => adapter,
+        None => {
+            // No hardware adapter (VMs, CI, remote desktops) - fall back to software rendering
+            adapter_options.force_fallback_adapter = true;
+            instance
+                .request_adapter(&adapter_options)
+                .await
+                .expect("Failed to find an appropriate adapter")
+        }
+    };
 
     let (device, queue) = adapter
         .request_device(
             &wgpu::DeviceDescriptor {
                 label: None,
                 features: wgpu::Features::empty(),
-                limits: wgpu::Limits::default(),
+                // Downlevel limits so the viewport also runs on fallback adapters
+                limits: wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
             },
             None,
         )
         .await
         .expect("Failed to create device");
-
-    let mut config = surface
-        .get_default_config(&adapter, size.width, size.height)
-        .unwrap();
-    config.format = wgpu::TextureFormat::Bgra8UnormSrgb;
-    config.usage = wgpu::TextureUsages::RENDER_ATTACHMENT;
-    surface.configure(&device, &config);
+    // Shared by the main window and every detached one
+    let gpu = Gpu { instance, adapter, device, queue };
 
     // Set up imgui
-    let mut imgui = imgui::Context::create();
-    imgui.set_ini_filename(None);
-
-    // Apply complete AMOLED black theme
-    ui::PulsarTheme::apply_theme(&mut imgui);
-
-    let mut platform = WinitPlatform::init(&mut imgui);
-    platform.attach_window(imgui.io_mut(), &window, imgui_winit_support::HiDpiMode::Rounded);
-
-    // High-quality font rendering with better scaling
-    let hidpi_factor = window.scale_factor();
-    let base_font_size = 15.0; // Larger base font for better readability
-    let font_size = (base_font_size * hidpi_factor) as f32;
-
-    imgui.fonts().clear();
-    imgui.fonts().add_font(&[
-        FontSource::DefaultFontData {
-            config: Some(imgui::FontConfig {
-                oversample_h: 3,
-                oversample_v: 1,
-                pixel_snap_h: false,
-                size_pixels: font_size,
-                rasterizer_multiply: 1.5,
-                ..Default::default()
-            }),
-        },
-    ]);
+    let mut imgui = viewport::create_context(&window);
+    imgui.load_ini_settings(&session.imgui_ini);
 
     let renderer_config = RendererConfig {
-        texture_format: config.format,
+        texture_format: viewport::SURFACE_FORMAT,
         ..Default::default()
     };
-    let mut renderer = Renderer::new(&mut imgui, &device, &queue, renderer_config);
+    let mut renderer = Renderer::new(&mut imgui, &gpu.device, &gpu.queue, renderer_config);
+    let mut scene_renderer = SceneRenderer::new(&gpu.device);
+    let mut main_view = Viewport::new(window, surface, &gpu, imgui);
 
-    let mut app = App::new();
+    let mut app = App::new(&session);
+    let mut window_size = [session.window.width, session.window.height];
+    // Panels and tabs in windows of their own
+    let mut detached: HashMap<DetachedContent, DetachedViewport> = HashMap::new();
 
     // Frame timing setup
-    let mut last_frame = Instant::now();
     let mut frame_count = 0u64;
     let mut fps_counter = Instant::now();
 
-    event_loop.run(move |event, _target, control_flow| {
+    event_loop.run(move |event, target, control_flow| {
             *control_flow = ControlFlow::Poll;
-            platform.handle_event(imgui.io_mut(), &window, &event);
 
-            match event {
-                Event::WindowEvent {
-                    event: WindowEvent::Resized(size),
-                    ..
-                } => {
-                    config.width = size.width.max(1);
-                    config.height = size.height.max(1);
-                    surface.configure(&device, &config);
+            // Window events go to their own window's imgui context, the rest to all
+            match &event {
+                Event::WindowEvent { window_id, .. } if *window_id != main_view.window.id() => {
+                    if let Some(window) = detached.values_mut().find(|w| w.viewport.window.id() == *window_id) {
+                        window.viewport.handle_event(&event);
+                    }
                 }
-                Event::WindowEvent {
-                    event: WindowEvent::CloseRequested,
-                    ..
-                } => *control_flow = ControlFlow::Exit,
-                Event::RedrawRequested(_) => {
-                    let now = Instant::now();
-                    imgui.io_mut().update_delta_time(now - last_frame);
-                    last_frame = now;
-
-                    let frame = match surface.get_current_texture() {
-                        Ok(frame) => frame,
-                        Err(e) => {
-                            eprintln!("dropped frame: {e:?}");
-                            return;
-                        }
-                    };
-
-                    platform
-                        .prepare_frame(imgui.io_mut(), &window)
-                        .expect("Failed to prepare frame");
+                Event::WindowEvent { .. } => main_view.handle_event(&event),
+                _ => {
+                    main_view.handle_event(&event);
+                    for window in detached.values_mut() {
+                        window.viewport.handle_event(&event);
+                    }
+                }
+            }
 
-                    let ui = imgui.frame();
+            match event {
+                Event::WindowEvent { window_id, event } if window_id == main_view.window.id() => match event {
+                    WindowEvent::Resized(size) => {
+                        main_view.resize(&gpu.device, size);
 
+                        // Remember the restored size, not the maximized or minimized one
+                        let window = &main_view.window;
+                        if size.width > 0 && size.height > 0 && !window.is_maximized() {
+                            let logical = size.to_logical::<f64>(window.scale_factor());
+                            window_size = [logical.width, logical.height];
+                        }
+                    }
+                    WindowEvent::CloseRequested => app.request_exit(),
+                    _ => {}
+                },
+                Event::WindowEvent { window_id, event } => {
+                    let Some((&content, window)) = detached.iter_mut().find(|(_, w)| w.viewport.window.id() == window_id) else { return };
+                    let view = &mut window.viewport;
+                    match event {
+                        WindowEvent::Resized(_) | WindowEvent::Moved(_) => {
+                            if let WindowEvent::Resized(size) = event {
+                                view.resize(&gpu.device, size);
+                            }
+                            // Moved or resized by the OS: keep the UI's idea of the window in step
+                            if let Some(position) = view.position_relative_to(&main_view.window) {
+                                let size = view.window.inner_size().to_logical::<f32>(view.window.scale_factor());
+                                window.position = position;
+                                app.move_detached(content, position, [size.width, size.height]);
+                            }
+                        }
+                        WindowEvent::CloseRequested => app.close_detached(content),
+                        _ => {}
+                    }
+                }
+                Event::RedrawRequested(window_id) if window_id == main_view.window.id() => {
                     // Update frame counter
                     frame_count += 1;
                     if fps_counter.elapsed().as_secs() >= 1 {
@@ -156,49 +187,96 @@ async fn main() {
                         fps_counter = Instant::now();
                     }
 
-                    // Run the app - check for valid display size to avoid ClipRect assertion
-                    let io = ui.io();
-                    if io.display_size[0] > 10.0 && io.display_size[1] > 10.0 {
-                        app.run(&ui);
-                    }
+                    main_view.render(&gpu, &mut renderer, &mut scene_renderer, |ui| app.run(ui));
 
-                    let mut encoder: wgpu::CommandEncoder = device.create_command_encoder(
-                        &wgpu::CommandEncoderDescriptor { label: None }
-                    );
-
-                    let view = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
-                    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                        label: None,
-                        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                            view: &view,
-                            resolve_target: None,
-                            ops: wgpu::Operations {
-                                load: wgpu::LoadOp::Clear(wgpu::Color {
-                                    r: 0.06,
-                                    g: 0.06,
-                                    b: 0.06,
-                                    a: 1.0,
-                                }),
-                                store: true,
-                            },
-                        })],
-                        depth_stencil_attachment: None,
-                    });
-
-                    let draw_data = imgui.render();
-                    renderer
-                        .render(&draw_data, &queue, &device, &mut rpass)
-                        .expect("Rendering failed");
-
-                    drop(rpass);
-
-                    queue.submit(Some(encoder.finish()));
-                    frame.present();
+                    // Exit once any unsaved changes have been dealt with, saving the session
+                    if app.should_exit() {
+                        let mut session = app.session();
+                        session.window = session::WindowState {
+                            width: window_size[0],
+                            height: window_size[1],
+                            maximized: main_view.window.is_maximized(),
+                        };
+                        main_view.with_context(|imgui| imgui.save_ini_settings(&mut session.imgui_ini));
+                        session::save(session);
+                        *control_flow = ControlFlow::Exit;
+                    }
+                }
+                Event::RedrawRequested(window_id) => {
+                    let Some((&content, window)) = detached.iter_mut().find(|(_, w)| w.viewport.window.id() == window_id) else { return };
+                    let origin = window.viewport.position_relative_to(&main_view.window).unwrap_or(window.position);
+                    window.viewport.render(&gpu, &mut renderer, &mut scene_renderer, |ui| app.run_detached(ui, content, origin));
                 }
                 Event::MainEventsCleared => {
-                    window.request_redraw();
+                    sync_detached_windows(&app, &mut detached, &main_view, target, &gpu, &mut renderer);
+
+                    main_view.window.request_redraw();
+                    for window in detached.values() {
+                        window.viewport.window.request_redraw();
+                    }
                 }
                 _ => {}
             }
         })
-}
\ No newline at end of file
+}
+
+/// OS window showing a detached panel or tab
+struct DetachedViewport {
+    viewport: Viewport,
+    position: [f32; 2], // where the UI last placed it, relative to the main window
+    title: String,
+}
+
+/// Open and close OS windows to match the panels and tabs the UI has detached, and
+/// move those the UI has dragged
+fn sync_detached_windows(
+    app: &App,
+    detached: &mut HashMap<DetachedContent, DetachedViewport>,
+    main_view: &Viewport,
+    target: &EventLoopWindowTarget<()>,
+    gpu: &Gpu,
+    renderer: &mut Renderer,
+) {
+    let wanted = app.detached_windows();
+
+    let closed: Vec<DetachedContent> = detached
+        .keys()
+        .filter(|content| !wanted.iter().any(|w| w.content == **content))
+        .copied()
+        .collect();
+    for content in closed {
+        if let Some(window) = detached.remove(&content) {
+            window.viewport.close(renderer);
+        }
+    }
+
+    for window in wanted {
+        match detached.get_mut(&window.content) {
+            Some(open) => {
+                let moved = (window.position[0] - open.position[0]).abs() > 0.5
+                    || (window.position[1] - open.position[1]).abs() > 0.5;
+                if moved {
+                    open.viewport.move_relative_to(&main_view.window, window.position);
+                    open.position = window.position;
+                }
+                if open.title != window.title {
+                    open.viewport.window.set_title(&window.title);
+                    open.title = window.title;
+                }
+            }
+            None => {
+                // Left to the OS where windows can't be placed, e.g. on Wayland
+                let scale = main_view.window.scale_factor();
+                let position = main_view.window.inner_position().ok().map(|origin| {
+                    PhysicalPosition::new(
+                        origin.x + (window.position[0] as f64 * scale) as i32,
+                        origin.y + (window.position[1] as f64 * scale) as i32,
+                    )
+                });
+                if let Some(viewport) = Viewport::open(target, &window.title, position, window.size, gpu, renderer) {
+                    detached.insert(window.content, DetachedViewport { viewport, position: window.position, title: window.title });
+                }
+            }
+        }
+    }
+}
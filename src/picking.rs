@@ -0,0 +1,66 @@
+use glam::{Vec2, Vec3};
+use crate::editor_camera::ScreenProjection;
+use crate::scene::{Entity, EntityId, MeshRenderer, Scene};
+
+/// Local-space box used for picking. Entities without a mesh (lights, cameras,
+/// empty groups) get a small box around their origin so they can still be clicked.
+fn local_bounds(entity: &Entity) -> (Vec3, Vec3) {
+    match entity.get_component::<MeshRenderer>() {
+        Some(mesh) if mesh.mesh == "Plane" => (Vec3::new(-0.5, -0.01, -0.5), Vec3::new(0.5, 0.01, 0.5)),
+        Some(_) => (Vec3::splat(-0.5), Vec3::splat(0.5)),
+        None => (Vec3::splat(-0.25), Vec3::splat(0.25)),
+    }
+}
+
+/// Closest entity hit by a world-space ray
+pub fn pick(scene: &Scene, (ray_origin, ray_direction): (Vec3, Vec3)) -> Option<EntityId> {
+    let mut best: Option<(f32, EntityId)> = None;
+    for entity in scene.iter() {
+        let world = scene.world_matrix(entity.id());
+        if world.determinant().abs() < 1e-8 {
+            continue; // zero scale
+        }
+
+        // Test against the box in the entity's local space, then measure the hit in world space
+        let inverse = world.inverse();
+        let origin = inverse.transform_point3(ray_origin);
+        let direction = inverse.transform_vector3(ray_direction);
+        let (min, max) = local_bounds(entity);
+        let Some(t) = intersect_box(origin, direction, min, max) else { continue };
+        let distance = world.transform_point3(origin + direction * t).distance(ray_origin);
+        if best.map_or(true, |(d, _)| distance < d) {
+            best = Some((distance, entity.id()));
+        }
+    }
+    best.map(|(_, id)| id)
+}
+
+/// Entities whose origin is inside the screen rectangle spanned by `a` and `b`
+pub fn marquee(scene: &Scene, projection: &ScreenProjection, a: [f32; 2], b: [f32; 2]) -> Vec<EntityId> {
+    let min = Vec2::from(a).min(Vec2::from(b));
+    let max = Vec2::from(a).max(Vec2::from(b));
+    scene
+        .iter()
+        .filter(|entity| {
+            let origin = scene.world_matrix(entity.id()).transform_point3(Vec3::ZERO);
+            projection
+                .world_to_screen(origin)
+                .map_or(false, |p| p[0] >= min.x && p[0] <= max.x && p[1] >= min.y && p[1] <= max.y)
+        })
+        .map(|entity| entity.id())
+        .collect()
+}
+
+/// Ray parameter of the first hit with an axis-aligned box (slab test)
+fn intersect_box(origin: Vec3, direction: Vec3, min: Vec3, max: Vec3) -> Option<f32> {
+    let inverse = direction.recip();
+    let t1 = (min - origin) * inverse;
+    let t2 = (max - origin) * inverse;
+    let near = t1.min(t2).max_element();
+    let far = t1.max(t2).min_element();
+    if near > far || far < 0.0 {
+        None
+    } else {
+        Some(near.max(0.0))
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashSet;
+use crate::history::{Command, CommandGroup, History};
+use crate::scene::{AddComponent, Entity, EntityId, RemoveComponent, RenameEntity, Scene, SetField};
+use crate::simulation::Simulation;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlayState {
+    Playing,
+    Paused,
+}
+
+/// A running play-in-editor session. The edited scene and its undo history are set
+/// aside untouched while the game runs on a copy, and swapped back on stop.
+pub struct PlaySession {
+    pub state: PlayState,
+    pub simulation: Simulation,
+    scene: Scene,
+    history: History<Scene>,
+    kept: HashSet<EntityId>,
+}
+
+impl PlaySession {
+    /// Begin playing. `scene` becomes a copy of itself and `history` a fresh history,
+    /// so edits made during play can be undone without touching the editor's.
+    pub fn start(scene: &mut Scene, history: &mut History<Scene>) -> Self {
+        let play_scene = scene.clone();
+        Self {
+            state: PlayState::Playing,
+            simulation: Simulation::new(),
+            scene: std::mem::replace(scene, play_scene),
+            history: std::mem::take(history),
+            kept: HashSet::new(),
+        }
+    }
+
+    /// Restore the pre-play scene and history. Changes to entities marked with `set_kept`
+    /// are carried over as a single undo step.
+    pub fn stop(self, scene: &mut Scene, history: &mut History<Scene>) {
+        let play_scene = std::mem::replace(scene, self.scene);
+        *history = self.history;
+
+        let mut kept: Vec<EntityId> = self.kept.into_iter().collect();
+        kept.sort();
+        let mut commands: Vec<Box<dyn Command<Scene>>> = Vec::new();
+        for id in kept {
+            if let Some(played) = play_scene.get(id) {
+                keep_changes(scene, played, &mut commands);
+            }
+        }
+        if !commands.is_empty() {
+            history.seal();
+            history.push(Box::new(CommandGroup::new("Keep Play Changes", commands)));
+            history.seal();
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state == PlayState::Paused
+    }
+
+    /// The edited scene and its history, set aside while the game runs
+    pub fn edited_mut(&mut self) -> (&mut Scene, &mut History<Scene>) {
+        (&mut self.scene, &mut self.history)
+    }
+
+    /// Whether the edited scene has unsaved changes
+    pub fn is_dirty(&self) -> bool {
+        self.history.is_dirty()
+    }
+
+    /// Approximate memory held by the edited scene and its history
+    pub fn memory_size(&self) -> usize {
+        self.scene.memory_size() + self.history.memory_used()
+    }
+
+    /// Only entities that existed before play can have their changes kept
+    pub fn can_keep(&self, id: EntityId) -> bool {
+        self.scene.contains(id)
+    }
+
+    pub fn is_kept(&self, id: EntityId) -> bool {
+        self.kept.contains(&id)
+    }
+
+    pub fn set_kept(&mut self, id: EntityId, kept: bool) {
+        if !kept {
+            self.kept.remove(&id);
+        } else if self.can_keep(id) {
+            self.kept.insert(id);
+        }
+    }
+
+    /// Entities of `play_scene` that were spawned during play or whose name or
+    /// components no longer match the edited scene
+    pub fn changed_entities(&self, play_scene: &Scene) -> HashSet<EntityId> {
+        play_scene
+            .iter()
+            .filter(|e| self.scene.get(e.id()).map_or(true, |original| differs(original, e)))
+            .map(|e| e.id())
+            .collect()
+    }
+}
+
+fn differs(a: &Entity, b: &Entity) -> bool {
+    a.name != b.name
+        || a.components.len() != b.components.len()
+        || a.components.iter().zip(&b.components).any(|(a, b)| {
+            a.type_name() != b.type_name() || a.fields().iter().any(|f| a.get_field(f.name) != b.get_field(f.name))
+        })
+}
+
+/// Apply the name and components `played` ended up with to the same entity in `scene`,
+/// recording the commands. Hierarchy changes made during play are not carried over.
+fn keep_changes(scene: &mut Scene, played: &Entity, commands: &mut Vec<Box<dyn Command<Scene>>>) {
+    let id = played.id();
+    let Some(original) = scene.get(id) else { return };
+
+    // Each command is applied before the next is built, as component indices shift
+    let mut pending: Vec<Box<dyn Command<Scene>>> = Vec::new();
+    if let Some(command) = RenameEntity::new(scene, id, &played.name) {
+        pending.push(Box::new(command));
+    }
+    for component in &played.components {
+        match original.components.iter().find(|c| c.type_name() == component.type_name()) {
+            Some(current) => {
+                for field in component.fields() {
+                    let (Some(old), Some(new)) = (current.get_field(field.name), component.get_field(field.name)) else { continue };
+                    if old != new {
+                        pending.push(Box::new(SetField {
+                            entity: id,
+                            component: component.type_name(),
+                            field: field.name,
+                            label: field.label,
+                            old,
+                            new,
+                        }));
+                    }
+                }
+            }
+            None => pending.push(Box::new(AddComponent::new(id, component.clone()))),
+        }
+    }
+    for mut command in pending {
+        command.apply(scene);
+        commands.push(command);
+    }
+
+    // Components removed during play
+    loop {
+        let removed = scene
+            .get(id)
+            .and_then(|e| e.components.iter().position(|c| !played.components.iter().any(|p| p.type_name() == c.type_name())));
+        let Some(mut command) = removed.and_then(|index| RemoveComponent::new(scene, id, index)) else { break };
+        command.apply(scene);
+        commands.push(Box::new(command));
+    }
+}
@@ -0,0 +1,139 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use crate::scene::{ComponentRegistry, Scene, SceneData, SceneError};
+
+/// Current version of the scene/project file format
+pub const FORMAT_VERSION: u32 = 1;
+
+/// File extension used for project and level files
+pub const PROJECT_EXTENSION: &str = "pulsar";
+
+/// Top-level contents of a project or level file. `E` is the editor state the
+/// owner wants restored alongside the scene (open tabs, tool settings, ...).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneFile<E> {
+    pub version: u32,
+    pub scene: SceneData,
+    #[serde(default)]
+    pub editor: E,
+}
+
+/// Only the version field, read first so newer files fail with a clear message
+#[derive(Deserialize)]
+struct VersionHeader {
+    version: u32,
+}
+
+#[derive(Debug)]
+pub enum ProjectError {
+    Io { path: PathBuf, error: std::io::Error },
+    Parse { path: PathBuf, line: usize, column: usize, message: String },
+    UnsupportedVersion { path: PathBuf, found: u32 },
+    Scene { path: PathBuf, error: SceneError },
+    Serialize(String),
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            Self::Parse { path, line, column, message } => {
+                write!(f, "{}:{}:{}: {}", path.display(), line, column, message)
+            }
+            Self::UnsupportedVersion { path, found } => write!(
+                f,
+                "{}: file format version {} is newer than supported version {}",
+                path.display(),
+                found,
+                FORMAT_VERSION
+            ),
+            Self::Scene { path, error } => write!(f, "{}: {}", path.display(), error),
+            Self::Serialize(message) => write!(f, "failed to serialize scene: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+impl<E: Serialize + DeserializeOwned + Default> SceneFile<E> {
+    pub fn new(scene: &Scene, editor: E) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            scene: scene.to_data(),
+            editor,
+        }
+    }
+
+    /// Rebuild the scene, resolving component types through `registry`
+    pub fn build_scene(&self, path: &Path, registry: &ComponentRegistry) -> Result<Scene, ProjectError> {
+        Scene::from_data(&self.scene, registry).map_err(|error| ProjectError::Scene {
+            path: path.to_path_buf(),
+            error,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ProjectError> {
+        let config = ron::ser::PrettyConfig::new()
+            .struct_names(false)
+            .indentor("    ".to_string());
+        let text = ron::ser::to_string_pretty(self, config)
+            .map_err(|e| ProjectError::Serialize(e.to_string()))?;
+        write_atomic(path, &text).map_err(|error| ProjectError::Io { path: path.to_path_buf(), error })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ProjectError> {
+        let text = fs::read_to_string(path)
+            .map_err(|error| ProjectError::Io { path: path.to_path_buf(), error })?;
+
+        let header: VersionHeader = ron::de::from_str(&text).map_err(|e| parse_error(path, e))?;
+        if header.version > FORMAT_VERSION {
+            return Err(ProjectError::UnsupportedVersion { path: path.to_path_buf(), found: header.version });
+        }
+
+        ron::de::from_str(&text).map_err(|e| parse_error(path, e))
+    }
+}
+
+/// Replace the file at `path` with `contents`. The text goes to a sibling `.tmp`
+/// file first so a failed write never truncates the old file.
+pub fn write_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    fs::write(&temp_path, contents).and_then(|_| fs::rename(&temp_path, path))
+}
+
+fn parse_error(path: &Path, error: ron::error::SpannedError) -> ProjectError {
+    ProjectError::Parse {
+        path: path.to_path_buf(),
+        line: error.position.line,
+        column: error.position.col,
+        message: error.code.to_string(),
+    }
+}
+
+/// Native "save as" dialog for project/level files
+pub fn pick_save_path(title: &str, default_name: &str) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .set_title(title)
+        .add_filter("Pulsar Project", &[PROJECT_EXTENSION])
+        .set_file_name(&format!("{}.{}", default_name, PROJECT_EXTENSION))
+        .save_file()
+        .map(|path| {
+            if path.extension().is_none() {
+                path.with_extension(PROJECT_EXTENSION)
+            } else {
+                path
+            }
+        })
+}
+
+/// Native "open" dialog for project/level files
+pub fn pick_open_path(title: &str) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .set_title(title)
+        .add_filter("Pulsar Project", &[PROJECT_EXTENSION])
+        .pick_file()
+}
@@ -0,0 +1,439 @@
+use std::any::Any;
+use crate::history::Command;
+use crate::scene::{Component, EntityId, FieldValue, RemovedSubtree, Scene};
+
+fn find_component<'a>(scene: &'a mut Scene, entity: EntityId, type_name: &str) -> Option<&'a mut Box<dyn Component>> {
+    scene
+        .get_mut(entity)?
+        .components
+        .iter_mut()
+        .find(|c| c.type_name() == type_name)
+}
+
+/// Change one reflected field of a component
+pub struct SetField {
+    pub entity: EntityId,
+    pub component: &'static str,
+    pub field: &'static str,
+    pub label: &'static str,
+    pub old: FieldValue,
+    pub new: FieldValue,
+}
+
+impl Command<Scene> for SetField {
+    fn label(&self) -> String {
+        format!("Edit {}", self.label)
+    }
+
+    fn apply(&mut self, scene: &mut Scene) {
+        if let Some(component) = find_component(scene, self.entity, self.component) {
+            component.set_field(self.field, self.new.clone());
+        }
+    }
+
+    fn revert(&mut self, scene: &mut Scene) {
+        if let Some(component) = find_component(scene, self.entity, self.component) {
+            component.set_field(self.field, self.old.clone());
+        }
+    }
+
+    fn merge(&mut self, next: &dyn Command<Scene>) -> bool {
+        match next.as_any().downcast_ref::<Self>() {
+            Some(next) if next.entity == self.entity && next.component == self.component && next.field == self.field => {
+                self.new = next.new.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Change the same reflected field on several entities, e.g. from the Inspector
+/// with a multi-selection. Each entity keeps its own old and new value.
+pub struct SetFields {
+    pub component: &'static str,
+    pub field: &'static str,
+    pub label: &'static str,
+    pub values: Vec<(EntityId, FieldValue, FieldValue)>, // (entity, old, new)
+}
+
+impl Command<Scene> for SetFields {
+    fn label(&self) -> String {
+        format!("Edit {} ({} entities)", self.label, self.values.len())
+    }
+
+    fn apply(&mut self, scene: &mut Scene) {
+        for (entity, _, new) in &self.values {
+            if let Some(component) = find_component(scene, *entity, self.component) {
+                component.set_field(self.field, new.clone());
+            }
+        }
+    }
+
+    fn revert(&mut self, scene: &mut Scene) {
+        for (entity, old, _) in &self.values {
+            if let Some(component) = find_component(scene, *entity, self.component) {
+                component.set_field(self.field, old.clone());
+            }
+        }
+    }
+
+    fn merge(&mut self, next: &dyn Command<Scene>) -> bool {
+        let Some(next) = next.as_any().downcast_ref::<Self>() else { return false };
+        let same_entities = next.values.len() == self.values.len()
+            && next.values.iter().zip(&self.values).all(|(a, b)| a.0 == b.0);
+        if next.component != self.component || next.field != self.field || !same_entities {
+            return false;
+        }
+        for (value, next_value) in self.values.iter_mut().zip(&next.values) {
+            value.2 = next_value.2.clone();
+        }
+        true
+    }
+
+    fn memory_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.values.len() * std::mem::size_of::<(EntityId, FieldValue, FieldValue)>()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct RenameEntity {
+    entity: EntityId,
+    old: String,
+    new: String,
+}
+
+impl RenameEntity {
+    /// None if the new name is empty or unchanged
+    pub fn new(scene: &Scene, entity: EntityId, name: &str) -> Option<Self> {
+        let old = scene.get(entity)?.name.clone();
+        let new = name.trim().to_string();
+        (!new.is_empty() && new != old).then(|| Self { entity, old, new })
+    }
+}
+
+impl Command<Scene> for RenameEntity {
+    fn label(&self) -> String {
+        format!("Rename {}", self.old)
+    }
+
+    fn apply(&mut self, scene: &mut Scene) {
+        scene.rename(self.entity, &self.new);
+    }
+
+    fn revert(&mut self, scene: &mut Scene) {
+        scene.rename(self.entity, &self.old);
+    }
+
+    fn memory_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.old.len() + self.new.len()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct ReparentEntity {
+    entity: EntityId,
+    old_parent: Option<EntityId>,
+    old_index: usize,
+    new_parent: Option<EntityId>,
+}
+
+impl ReparentEntity {
+    /// None if the move would create a cycle or not change anything
+    pub fn new(scene: &Scene, entity: EntityId, new_parent: Option<EntityId>) -> Option<Self> {
+        let old_parent = scene.get(entity)?.parent();
+        if old_parent == new_parent {
+            return None;
+        }
+        if let Some(p) = new_parent {
+            if !scene.contains(p) || scene.is_ancestor(entity, p) {
+                return None;
+            }
+        }
+        Some(Self {
+            entity,
+            old_parent,
+            old_index: scene.sibling_index(entity)?,
+            new_parent,
+        })
+    }
+}
+
+impl Command<Scene> for ReparentEntity {
+    fn label(&self) -> String {
+        "Reparent".to_string()
+    }
+
+    fn apply(&mut self, scene: &mut Scene) {
+        scene.set_parent(self.entity, self.new_parent, None);
+    }
+
+    fn revert(&mut self, scene: &mut Scene) {
+        scene.set_parent(self.entity, self.old_parent, Some(self.old_index));
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct AddComponent {
+    entity: EntityId,
+    component: Box<dyn Component>,
+}
+
+impl AddComponent {
+    pub fn new(entity: EntityId, component: Box<dyn Component>) -> Self {
+        Self { entity, component }
+    }
+}
+
+impl Command<Scene> for AddComponent {
+    fn label(&self) -> String {
+        format!("Add {}", self.component.type_name())
+    }
+
+    fn apply(&mut self, scene: &mut Scene) {
+        if let Some(entity) = scene.get_mut(self.entity) {
+            entity.components.push(self.component.clone());
+        }
+    }
+
+    fn revert(&mut self, scene: &mut Scene) {
+        if let Some(entity) = scene.get_mut(self.entity) {
+            let type_name = self.component.type_name();
+            if let Some(index) = entity.components.iter().rposition(|c| c.type_name() == type_name) {
+                entity.components.remove(index);
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct RemoveComponent {
+    entity: EntityId,
+    index: usize,
+    component: Box<dyn Component>,
+}
+
+impl RemoveComponent {
+    pub fn new(scene: &Scene, entity: EntityId, index: usize) -> Option<Self> {
+        let component = scene.get(entity)?.components.get(index)?.clone();
+        Some(Self { entity, index, component })
+    }
+}
+
+impl Command<Scene> for RemoveComponent {
+    fn label(&self) -> String {
+        format!("Remove {}", self.component.type_name())
+    }
+
+    fn apply(&mut self, scene: &mut Scene) {
+        if let Some(entity) = scene.get_mut(self.entity) {
+            if self.index < entity.components.len() {
+                entity.components.remove(self.index);
+            }
+        }
+    }
+
+    fn revert(&mut self, scene: &mut Scene) {
+        if let Some(entity) = scene.get_mut(self.entity) {
+            let index = self.index.min(entity.components.len());
+            entity.components.insert(index, self.component.clone());
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Records an entity that was just spawned; undo removes it again
+pub struct SpawnEntity {
+    entity: EntityId,
+    removed: Option<RemovedSubtree>,
+}
+
+impl SpawnEntity {
+    pub fn new(entity: EntityId) -> Self {
+        Self { entity, removed: None }
+    }
+}
+
+impl Command<Scene> for SpawnEntity {
+    fn label(&self) -> String {
+        "Create Entity".to_string()
+    }
+
+    fn apply(&mut self, scene: &mut Scene) {
+        if let Some(removed) = self.removed.take() {
+            scene.restore_subtree(removed);
+        }
+    }
+
+    fn revert(&mut self, scene: &mut Scene) {
+        self.removed = scene.remove_subtree(self.entity);
+    }
+
+    fn memory_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.removed.as_ref().map_or(0, |r| r.memory_size())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Delete an entity with all of its children
+pub struct DeleteEntity {
+    entity: EntityId,
+    name: String,
+    removed: Option<RemovedSubtree>,
+}
+
+impl DeleteEntity {
+    pub fn new(scene: &Scene, entity: EntityId) -> Option<Self> {
+        let name = scene.get(entity)?.name.clone();
+        Some(Self { entity, name, removed: None })
+    }
+}
+
+impl Command<Scene> for DeleteEntity {
+    fn label(&self) -> String {
+        format!("Delete {}", self.name)
+    }
+
+    fn apply(&mut self, scene: &mut Scene) {
+        self.removed = scene.remove_subtree(self.entity);
+    }
+
+    fn revert(&mut self, scene: &mut Scene) {
+        if let Some(removed) = self.removed.take() {
+            scene.restore_subtree(removed);
+        }
+    }
+
+    fn memory_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.name.len() + self.removed.as_ref().map_or(0, |r| r.memory_size())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::History;
+    use crate::scene::Transform;
+
+    fn position(scene: &Scene, entity: EntityId) -> [f32; 3] {
+        scene.get(entity).and_then(|e| e.get_component::<Transform>()).unwrap().position
+    }
+
+    fn move_to(entity: EntityId, old: [f32; 3], new: [f32; 3]) -> Box<SetField> {
+        Box::new(SetField {
+            entity,
+            component: "Transform",
+            field: "position",
+            label: "Position",
+            old: FieldValue::Vec3(old),
+            new: FieldValue::Vec3(new),
+        })
+    }
+
+    #[test]
+    fn field_edits_of_one_drag_merge() {
+        let mut scene = Scene::new("Test");
+        let entity = scene.spawn("Box", None);
+        scene.add_component(entity, Transform::default());
+        let mut history = History::default();
+
+        history.execute(&mut scene, move_to(entity, [0.0; 3], [1.0; 3]));
+        history.execute(&mut scene, move_to(entity, [1.0; 3], [2.0; 3]));
+        assert_eq!(position(&scene, entity), [2.0; 3]);
+
+        history.undo(&mut scene);
+        assert_eq!(position(&scene, entity), [0.0; 3]);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn delete_round_trip_restores_the_subtree() {
+        let mut scene = Scene::with_default_content();
+        let entities = scene.len();
+        let environment = scene.roots()[1];
+        let children = scene.get(environment).unwrap().children().to_vec();
+        let mut history = History::default();
+
+        let command = DeleteEntity::new(&scene, environment).unwrap();
+        history.execute(&mut scene, Box::new(command));
+        assert!(!scene.contains(environment));
+        assert!(children.iter().all(|&child| !scene.contains(child)));
+        assert_eq!(scene.len(), entities - 1 - children.len());
+
+        history.undo(&mut scene);
+        assert_eq!(scene.len(), entities);
+        assert_eq!(scene.roots()[1], environment);
+        assert_eq!(scene.get(environment).unwrap().children(), children.as_slice());
+
+        history.redo(&mut scene);
+        assert!(!scene.contains(environment));
+    }
+
+    #[test]
+    fn spawn_round_trip_keeps_the_entity_id() {
+        let mut scene = Scene::new("Test");
+        let parent = scene.spawn("Parent", None);
+        let mut history = History::default();
+
+        let entity = scene.spawn("Child", Some(parent));
+        history.push(Box::new(SpawnEntity::new(entity)));
+        let spawned = history.memory_used();
+
+        history.undo(&mut scene);
+        assert!(!scene.contains(entity));
+        assert!(history.memory_used() > spawned);
+
+        history.redo(&mut scene);
+        assert_eq!(scene.get(entity).and_then(|e| e.parent()), Some(parent));
+    }
+
+    #[test]
+    fn rename_and_reparent_round_trip() {
+        let mut scene = Scene::new("Test");
+        let a = scene.spawn("A", None);
+        let b = scene.spawn("B", None);
+        let mut history = History::default();
+
+        assert!(RenameEntity::new(&scene, a, "A").is_none());
+        assert!(ReparentEntity::new(&scene, a, None).is_none());
+
+        let rename = RenameEntity::new(&scene, a, " Renamed ").unwrap();
+        history.execute(&mut scene, Box::new(rename));
+        let reparent = ReparentEntity::new(&scene, a, Some(b)).unwrap();
+        history.execute(&mut scene, Box::new(reparent));
+        assert_eq!(scene.get(a).unwrap().name, "Renamed");
+        assert_eq!(scene.get(a).unwrap().parent(), Some(b));
+        assert!(ReparentEntity::new(&scene, b, Some(a)).is_none());
+
+        history.undo(&mut scene);
+        history.undo(&mut scene);
+        assert_eq!(scene.get(a).unwrap().name, "A");
+        assert_eq!(scene.roots(), [a, b]);
+    }
+}
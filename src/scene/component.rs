@@ -0,0 +1,251 @@
+use std::any::Any;
+use glam::{EulerRot, Mat4, Quat, Vec3};
+use crate::scene::reflect::{FieldInfo, FieldKind, FieldValue, Reflect};
+
+/// Data attached to an entity. Concrete components are stored as trait objects
+/// so new component types can be added without touching the scene model.
+pub trait Component: Reflect + Any {
+    fn type_name(&self) -> &'static str;
+    fn icon(&self) -> &'static str { "🧩" }
+    fn removable(&self) -> bool { true }
+    fn clone_box(&self) -> Box<dyn Component>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl Clone for Box<dyn Component> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Implements `Component` and `Reflect` for a struct whose fields map directly
+/// onto `FieldValue` variants
+macro_rules! impl_component {
+    ($ty:ty, $name:expr, $icon:expr, removable: $removable:expr, { $($field:ident: $kind:ident = $label:expr),* $(,)? }) => {
+        impl Component for $ty {
+            fn type_name(&self) -> &'static str { $name }
+            fn icon(&self) -> &'static str { $icon }
+            fn removable(&self) -> bool { $removable }
+            fn clone_box(&self) -> Box<dyn Component> { Box::new(self.clone()) }
+            fn as_any(&self) -> &dyn Any { self }
+            fn as_any_mut(&mut self) -> &mut dyn Any { self }
+        }
+
+        impl Reflect for $ty {
+            fn fields(&self) -> &'static [FieldInfo] {
+                const FIELDS: &[FieldInfo] = &[
+                    $(FieldInfo { name: stringify!($field), label: $label, kind: FieldKind::$kind }),*
+                ];
+                FIELDS
+            }
+
+            fn get_field(&self, name: &str) -> Option<FieldValue> {
+                match name {
+                    $(stringify!($field) => Some(FieldValue::$kind(self.$field.clone())),)*
+                    _ => None,
+                }
+            }
+
+            fn set_field(&mut self, name: &str, value: FieldValue) -> bool {
+                match (name, value) {
+                    $((stringify!($field), FieldValue::$kind(v)) => {
+                        self.$field = v;
+                        true
+                    })*
+                    _ => false,
+                }
+            }
+        }
+    };
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transform {
+    pub position: [f32; 3],
+    pub rotation: [f32; 3], // Euler angles in degrees
+    pub scale: [f32; 3],
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            position: [0.0, 0.0, 0.0],
+            rotation: [0.0, 0.0, 0.0],
+            scale: [1.0, 1.0, 1.0],
+        }
+    }
+}
+
+impl Transform {
+    pub fn at(position: [f32; 3]) -> Self {
+        Self { position, ..Default::default() }
+    }
+
+    /// Rotation as a quaternion (yaw around Y, then pitch around X, then roll around Z)
+    pub fn rotation_quat(&self) -> Quat {
+        let [x, y, z] = self.rotation;
+        Quat::from_euler(EulerRot::YXZ, y.to_radians(), x.to_radians(), z.to_radians())
+    }
+
+    /// Local-to-parent matrix
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(
+            Vec3::from(self.scale),
+            self.rotation_quat(),
+            Vec3::from(self.position),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshRenderer {
+    pub mesh: String,
+    pub material: String,
+    pub cast_shadows: bool,
+}
+
+impl Default for MeshRenderer {
+    fn default() -> Self {
+        Self {
+            mesh: "Cube".to_string(),
+            material: "Default".to_string(),
+            cast_shadows: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Camera {
+    pub fov: f32,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self { fov: 60.0, near: 0.1, far: 1000.0 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Light {
+    pub color: [f32; 3],
+    pub intensity: f32,
+}
+
+impl Default for Light {
+    fn default() -> Self {
+        Self { color: [1.0, 0.95, 0.8], intensity: 1.0 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Script {
+    pub script: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RigidBody {
+    pub mass: f32,
+    pub use_gravity: bool,
+    pub is_kinematic: bool,
+}
+
+impl Default for RigidBody {
+    fn default() -> Self {
+        Self { mass: 1.0, use_gravity: true, is_kinematic: false }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxCollider {
+    pub size: [f32; 3],
+    pub is_trigger: bool,
+}
+
+impl Default for BoxCollider {
+    fn default() -> Self {
+        Self { size: [1.0, 1.0, 1.0], is_trigger: false }
+    }
+}
+
+impl_component!(Transform, "Transform", "📐", removable: false, {
+    position: Vec3 = "Position",
+    rotation: Vec3 = "Rotation",
+    scale: Vec3 = "Scale",
+});
+
+impl_component!(MeshRenderer, "Mesh Renderer", "🎭", removable: true, {
+    mesh: Text = "Mesh",
+    material: Text = "Material",
+    cast_shadows: Bool = "Cast Shadows",
+});
+
+impl_component!(Camera, "Camera", "📷", removable: true, {
+    fov: Float = "Field of View",
+    near: Float = "Near Clip",
+    far: Float = "Far Clip",
+});
+
+impl_component!(Light, "Light", "☀️", removable: true, {
+    color: Color = "Color",
+    intensity: Float = "Intensity",
+});
+
+impl_component!(Script, "Script", "🎯", removable: true, {
+    script: Text = "Script",
+});
+
+impl_component!(RigidBody, "Rigid Body", "⚡", removable: true, {
+    mass: Float = "Mass",
+    use_gravity: Bool = "Use Gravity",
+    is_kinematic: Bool = "Is Kinematic",
+});
+
+impl_component!(BoxCollider, "Box Collider", "📦", removable: true, {
+    size: Vec3 = "Size",
+    is_trigger: Bool = "Is Trigger",
+});
+
+/// Component types that can be added from the editor, keyed by type name
+pub struct ComponentRegistry {
+    types: Vec<(&'static str, &'static str, Box<dyn Fn() -> Box<dyn Component>>)>, // (name, icon, factory)
+}
+
+impl ComponentRegistry {
+    pub fn new() -> Self {
+        Self { types: Vec::new() }
+    }
+
+    pub fn register<C: Component + Default>(&mut self) {
+        let sample = C::default();
+        self.types.push((sample.type_name(), sample.icon(), Box::new(|| Box::new(C::default()) as Box<dyn Component>)));
+    }
+
+    pub fn create(&self, type_name: &str) -> Option<Box<dyn Component>> {
+        self.types
+            .iter()
+            .find(|(name, _, _)| *name == type_name)
+            .map(|(_, _, factory)| factory())
+    }
+
+    /// (name, icon) of every registered component type
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &'static str)> + '_ {
+        self.types.iter().map(|(name, icon, _)| (*name, *icon))
+    }
+}
+
+impl Default for ComponentRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register::<Transform>();
+        registry.register::<MeshRenderer>();
+        registry.register::<Camera>();
+        registry.register::<Light>();
+        registry.register::<Script>();
+        registry.register::<RigidBody>();
+        registry.register::<BoxCollider>();
+        registry
+    }
+}
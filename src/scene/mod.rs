@@ -0,0 +1,377 @@
+pub mod commands;
+pub mod component;
+pub mod reflect;
+pub mod selection;
+pub mod serialization;
+
+pub use commands::*;
+pub use component::*;
+pub use reflect::*;
+pub use selection::{SelectMode, Selection};
+pub use serialization::{SceneData, SceneError};
+
+use std::collections::HashMap;
+use glam::{Mat4, Vec3};
+
+/// Stable identifier for an entity. IDs are never reused within a scene.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntityId(pub u64);
+
+pub struct Entity {
+    id: EntityId,
+    pub name: String,
+    parent: Option<EntityId>,
+    children: Vec<EntityId>,
+    pub components: Vec<Box<dyn Component>>,
+}
+
+impl Clone for Entity {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            name: self.name.clone(),
+            parent: self.parent,
+            children: self.children.clone(),
+            components: self.components.clone(),
+        }
+    }
+}
+
+impl Entity {
+    pub fn id(&self) -> EntityId {
+        self.id
+    }
+
+    pub fn parent(&self) -> Option<EntityId> {
+        self.parent
+    }
+
+    pub fn children(&self) -> &[EntityId] {
+        &self.children
+    }
+
+    pub fn get_component<T: Component>(&self) -> Option<&T> {
+        self.components.iter().find_map(|c| c.as_any().downcast_ref::<T>())
+    }
+
+    pub fn get_component_mut<T: Component>(&mut self) -> Option<&mut T> {
+        self.components.iter_mut().find_map(|c| c.as_any_mut().downcast_mut::<T>())
+    }
+
+    /// Icon shown in the hierarchy, derived from the most descriptive component
+    pub fn icon(&self) -> &'static str {
+        self.components
+            .iter()
+            .find(|c| c.type_name() != "Transform")
+            .map(|c| c.icon())
+            .unwrap_or(if self.children.is_empty() { "⬜" } else { "📁" })
+    }
+
+    /// Rough memory footprint for undo memory budgets
+    fn memory_size(&self) -> usize {
+        std::mem::size_of::<Entity>() + self.name.len() + self.components.len() * 64
+    }
+}
+
+/// Scene graph: a forest of named entities with parent/child links
+#[derive(Clone)]
+pub struct Scene {
+    pub name: String,
+    entities: HashMap<EntityId, Entity>,
+    roots: Vec<EntityId>,
+    next_id: u64,
+}
+
+impl Scene {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            entities: HashMap::new(),
+            roots: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Starter scene used for new levels
+    pub fn with_default_content() -> Self {
+        let mut scene = Self::new("Untitled");
+
+        let player = scene.spawn("Player", None);
+        scene.add_component(player, Transform::default());
+        scene.add_component(player, MeshRenderer { mesh: "Capsule".to_string(), ..Default::default() });
+        scene.add_component(player, Script { script: "PlayerController".to_string() });
+
+        let camera = scene.spawn("Camera", Some(player));
+        scene.add_component(camera, Transform::at([0.0, 1.6, -4.0]));
+        scene.add_component(camera, Camera::default());
+
+        let environment = scene.spawn("Environment", None);
+        scene.add_component(environment, Transform::default());
+
+        let sun = scene.spawn("DirectionalLight", Some(environment));
+        scene.add_component(sun, Transform { rotation: [50.0, -30.0, 0.0], ..Default::default() });
+        scene.add_component(sun, Light::default());
+
+        let terrain = scene.spawn("Terrain", Some(environment));
+        scene.add_component(terrain, Transform { scale: [50.0, 1.0, 50.0], ..Default::default() });
+        scene.add_component(terrain, MeshRenderer { mesh: "Plane".to_string(), material: "Grass".to_string(), ..Default::default() });
+
+        let water = scene.spawn("Water", Some(environment));
+        scene.add_component(water, Transform { position: [10.0, -0.5, 10.0], scale: [20.0, 1.0, 20.0], ..Default::default() });
+        scene.add_component(water, MeshRenderer { mesh: "Plane".to_string(), material: "Water".to_string(), ..Default::default() });
+
+        scene
+    }
+
+    /// Create a new empty entity, optionally as the last child of `parent`
+    pub fn spawn(&mut self, name: &str, parent: Option<EntityId>) -> EntityId {
+        let id = EntityId(self.next_id);
+        self.next_id += 1;
+
+        let parent = parent.filter(|p| self.entities.contains_key(p));
+        self.entities.insert(id, Entity {
+            id,
+            name: name.to_string(),
+            parent,
+            children: Vec::new(),
+            components: Vec::new(),
+        });
+
+        match parent {
+            Some(p) => self.entities.get_mut(&p).unwrap().children.push(id),
+            None => self.roots.push(id),
+        }
+
+        id
+    }
+
+    pub fn add_component(&mut self, id: EntityId, component: impl Component) {
+        if let Some(entity) = self.entities.get_mut(&id) {
+            entity.components.push(Box::new(component));
+        }
+    }
+
+    pub fn get(&self, id: EntityId) -> Option<&Entity> {
+        self.entities.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: EntityId) -> Option<&mut Entity> {
+        self.entities.get_mut(&id)
+    }
+
+    pub fn contains(&self, id: EntityId) -> bool {
+        self.entities.contains_key(&id)
+    }
+
+    pub fn roots(&self) -> &[EntityId] {
+        &self.roots
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Rough memory footprint, e.g. for a closed tab kept by undo history
+    pub fn memory_size(&self) -> usize {
+        self.entities.values().map(Entity::memory_size).sum::<usize>() + self.roots.len() * std::mem::size_of::<EntityId>()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Entity> {
+        self.entities.values()
+    }
+
+    pub fn rename(&mut self, id: EntityId, name: &str) -> bool {
+        match self.entities.get_mut(&id) {
+            Some(entity) if !name.trim().is_empty() => {
+                entity.name = name.trim().to_string();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if `ancestor` is `id` itself or one of its parents
+    pub fn is_ancestor(&self, ancestor: EntityId, id: EntityId) -> bool {
+        let mut current = Some(id);
+        while let Some(c) = current {
+            if c == ancestor {
+                return true;
+            }
+            current = self.entities.get(&c).and_then(|e| e.parent);
+        }
+        false
+    }
+
+    /// Move `id` under `new_parent` (or to the root level) at `index` among its siblings.
+    /// Fails if that would create a cycle.
+    pub fn set_parent(&mut self, id: EntityId, new_parent: Option<EntityId>, index: Option<usize>) -> bool {
+        if !self.entities.contains_key(&id) {
+            return false;
+        }
+        if let Some(p) = new_parent {
+            if !self.entities.contains_key(&p) || self.is_ancestor(id, p) {
+                return false;
+            }
+        }
+
+        self.detach(id);
+        self.entities.get_mut(&id).unwrap().parent = new_parent;
+        let siblings = match new_parent {
+            Some(p) => &mut self.entities.get_mut(&p).unwrap().children,
+            None => &mut self.roots,
+        };
+        let index = index.unwrap_or(siblings.len()).min(siblings.len());
+        siblings.insert(index, id);
+        true
+    }
+
+    /// Position of `id` among its siblings
+    pub fn sibling_index(&self, id: EntityId) -> Option<usize> {
+        let siblings = match self.entities.get(&id)?.parent {
+            Some(p) => &self.entities.get(&p)?.children,
+            None => &self.roots,
+        };
+        siblings.iter().position(|&c| c == id)
+    }
+
+    /// Remove an entity together with all of its descendants
+    pub fn despawn(&mut self, id: EntityId) -> bool {
+        if !self.entities.contains_key(&id) {
+            return false;
+        }
+        self.detach(id);
+
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            if let Some(entity) = self.entities.remove(&current) {
+                stack.extend(entity.children);
+            }
+        }
+        true
+    }
+
+    /// Local-to-world matrix of an entity, combining the transforms of all its parents
+    pub fn world_matrix(&self, id: EntityId) -> Mat4 {
+        let mut matrix = Mat4::IDENTITY;
+        let mut current = self.entities.get(&id);
+        while let Some(entity) = current {
+            if let Some(transform) = entity.get_component::<Transform>() {
+                matrix = transform.matrix() * matrix;
+            }
+            current = entity.parent.and_then(|p| self.entities.get(&p));
+        }
+        matrix
+    }
+
+    /// World-space (min, max) box around an entity and its descendants. Entities with a
+    /// `MeshRenderer` contribute their unit mesh bounds, everything else just its origin.
+    pub fn world_bounds(&self, id: EntityId) -> Option<(Vec3, Vec3)> {
+        let mut bounds: Option<(Vec3, Vec3)> = None;
+        for entity_id in self.subtree(id) {
+            let matrix = self.world_matrix(entity_id);
+            let has_mesh = self
+                .entities
+                .get(&entity_id)
+                .map_or(false, |e| e.get_component::<MeshRenderer>().is_some());
+            let corners: Vec<Vec3> = if has_mesh {
+                (0..8)
+                    .map(|i| Vec3::new(
+                        if i & 1 == 0 { -0.5 } else { 0.5 },
+                        if i & 2 == 0 { -0.5 } else { 0.5 },
+                        if i & 4 == 0 { -0.5 } else { 0.5 },
+                    ))
+                    .collect()
+            } else {
+                vec![Vec3::ZERO]
+            };
+            for corner in corners {
+                let point = matrix.transform_point3(corner);
+                bounds = Some(match bounds {
+                    Some((min, max)) => (min.min(point), max.max(point)),
+                    None => (point, point),
+                });
+            }
+        }
+        bounds
+    }
+
+    /// Detach an entity and its descendants so they can be put back later with the same ids
+    pub fn remove_subtree(&mut self, id: EntityId) -> Option<RemovedSubtree> {
+        let parent = self.entities.get(&id)?.parent;
+        let index = self.sibling_index(id)?;
+        let entities = self
+            .subtree(id)
+            .into_iter()
+            .filter_map(|e| self.entities.get(&e).cloned())
+            .collect();
+        self.despawn(id);
+        Some(RemovedSubtree { parent, index, entities })
+    }
+
+    /// Reinsert a subtree taken out by `remove_subtree` at its original position
+    pub fn restore_subtree(&mut self, removed: RemovedSubtree) {
+        let Some(root) = removed.entities.first().map(|e| e.id) else { return };
+        for entity in removed.entities {
+            self.next_id = self.next_id.max(entity.id.0 + 1);
+            self.entities.insert(entity.id, entity);
+        }
+
+        let parent = removed.parent.filter(|p| self.entities.contains_key(p));
+        self.entities.get_mut(&root).unwrap().parent = parent;
+        let siblings = match parent {
+            Some(p) => &mut self.entities.get_mut(&p).unwrap().children,
+            None => &mut self.roots,
+        };
+        siblings.insert(removed.index.min(siblings.len()), root);
+    }
+
+    /// All entities of the subtree rooted at `id`, parents before children
+    pub fn subtree(&self, id: EntityId) -> Vec<EntityId> {
+        let mut result = Vec::new();
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            if let Some(entity) = self.entities.get(&current) {
+                result.push(current);
+                stack.extend(entity.children.iter().rev());
+            }
+        }
+        result
+    }
+
+    fn detach(&mut self, id: EntityId) {
+        let parent = self.entities.get(&id).and_then(|e| e.parent);
+        match parent {
+            Some(p) => {
+                if let Some(parent) = self.entities.get_mut(&p) {
+                    parent.children.retain(|&c| c != id);
+                }
+            }
+            None => self.roots.retain(|&c| c != id),
+        }
+    }
+}
+
+/// Entities removed from a scene, kept by undo history so deletes can be reverted
+#[derive(Clone)]
+pub struct RemovedSubtree {
+    parent: Option<EntityId>,
+    index: usize,
+    entities: Vec<Entity>, // parents first
+}
+
+impl RemovedSubtree {
+    /// Rough memory footprint for the undo memory budget
+    pub fn memory_size(&self) -> usize {
+        self.entities.iter().map(Entity::memory_size).sum()
+    }
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::with_default_content()
+    }
+}
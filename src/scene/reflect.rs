@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// Editable value of a reflected component field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FieldValue {
+    Bool(bool),
+    Int(i32),
+    Float(f32),
+    Vec3([f32; 3]),
+    Color([f32; 3]),
+    Text(String),
+}
+
+impl FieldValue {
+    pub fn kind(&self) -> FieldKind {
+        match self {
+            Self::Bool(_) => FieldKind::Bool,
+            Self::Int(_) => FieldKind::Int,
+            Self::Float(_) => FieldKind::Float,
+            Self::Vec3(_) => FieldKind::Vec3,
+            Self::Color(_) => FieldKind::Color,
+            Self::Text(_) => FieldKind::Text,
+        }
+    }
+}
+
+/// Type of a reflected field, used to pick the Inspector widget
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldKind {
+    Bool,
+    Int,
+    Float,
+    Vec3,
+    Color,
+    Text,
+}
+
+/// Static description of one component field
+#[derive(Debug, Clone, Copy)]
+pub struct FieldInfo {
+    pub name: &'static str,
+    pub label: &'static str,
+    pub kind: FieldKind,
+}
+
+/// Runtime description of a type's fields so editors can display and edit it generically
+pub trait Reflect {
+    fn fields(&self) -> &'static [FieldInfo];
+    fn get_field(&self, name: &str) -> Option<FieldValue>;
+    /// Returns false if the field does not exist or the value has the wrong kind
+    fn set_field(&mut self, name: &str, value: FieldValue) -> bool;
+}
@@ -0,0 +1,121 @@
+use crate::scene::{EntityId, Scene};
+
+/// How a click or box selection combines with the current selection
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectMode {
+    Replace,
+    Add,    // Shift
+    Toggle, // Ctrl
+}
+
+impl SelectMode {
+    pub fn from_modifiers(shift: bool, ctrl: bool) -> Self {
+        if ctrl {
+            Self::Toggle
+        } else if shift {
+            Self::Add
+        } else {
+            Self::Replace
+        }
+    }
+}
+
+/// Selected entities, shared by the viewport, Hierarchy and Inspector. The most
+/// recently selected entity is the primary one, used for the gizmo and renaming.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Selection {
+    entities: Vec<EntityId>, // in selection order, primary last
+}
+
+impl Selection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn contains(&self, id: EntityId) -> bool {
+        self.entities.contains(&id)
+    }
+
+    pub fn primary(&self) -> Option<EntityId> {
+        self.entities.last().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = EntityId> + '_ {
+        self.entities.iter().copied()
+    }
+
+    pub fn clear(&mut self) {
+        self.entities.clear();
+    }
+
+    /// Select only `id`
+    pub fn select(&mut self, id: EntityId) {
+        self.entities.clear();
+        self.entities.push(id);
+    }
+
+    /// Add `id`, making it the primary entity
+    pub fn add(&mut self, id: EntityId) {
+        self.entities.retain(|&e| e != id);
+        self.entities.push(id);
+    }
+
+    pub fn remove(&mut self, id: EntityId) {
+        self.entities.retain(|&e| e != id);
+    }
+
+    pub fn toggle(&mut self, id: EntityId) {
+        if self.contains(id) {
+            self.remove(id);
+        } else {
+            self.add(id);
+        }
+    }
+
+    /// Apply a click on `id` (or on empty space for None)
+    pub fn click(&mut self, id: Option<EntityId>, mode: SelectMode) {
+        match (id, mode) {
+            (Some(id), SelectMode::Replace) => self.select(id),
+            (Some(id), SelectMode::Add) => self.add(id),
+            (Some(id), SelectMode::Toggle) => self.toggle(id),
+            (None, SelectMode::Replace) => self.clear(),
+            (None, _) => {}
+        }
+    }
+
+    /// Apply a box selection covering `ids`
+    pub fn select_many(&mut self, ids: impl IntoIterator<Item = EntityId>, mode: SelectMode) {
+        if mode == SelectMode::Replace {
+            self.entities.clear();
+        }
+        for id in ids {
+            match mode {
+                SelectMode::Toggle => self.toggle(id),
+                _ => self.add(id),
+            }
+        }
+    }
+
+    /// Drop entities that no longer exist (after undo, delete or loading a file)
+    pub fn retain_existing(&mut self, scene: &Scene) {
+        self.entities.retain(|&id| scene.contains(id));
+    }
+
+    /// Selected entities that have no selected ancestor, so subtree operations
+    /// such as delete touch each entity once
+    pub fn roots(&self, scene: &Scene) -> Vec<EntityId> {
+        self.entities
+            .iter()
+            .copied()
+            .filter(|&id| !self.entities.iter().any(|&other| other != id && scene.is_ancestor(other, id)))
+            .collect()
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::scene::{ComponentRegistry, Entity, EntityId, FieldValue, Scene};
+
+/// On-disk representation of a scene. Entities are stored parents-first in
+/// hierarchy order so files diff cleanly and load without fix-ups.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SceneData {
+    pub name: String,
+    #[serde(default)]
+    pub entities: Vec<EntityData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityData {
+    pub id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub parent: Option<u64>,
+    #[serde(default)]
+    pub components: Vec<ComponentData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentData {
+    #[serde(rename = "type")]
+    pub type_name: String,
+    #[serde(default)]
+    pub fields: BTreeMap<String, FieldValue>,
+}
+
+/// Problems found while turning `SceneData` back into a `Scene`
+#[derive(Debug, Clone, PartialEq)]
+pub enum SceneError {
+    DuplicateEntity(u64),
+    MissingParent { entity: u64, parent: u64 },
+    UnknownComponent { entity: u64, type_name: String },
+    InvalidField { entity: u64, component: String, field: String },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateEntity(id) => write!(f, "entity id {} is used more than once", id),
+            Self::MissingParent { entity, parent } => {
+                write!(f, "entity {} refers to parent {} which is not defined before it", entity, parent)
+            }
+            Self::UnknownComponent { entity, type_name } => {
+                write!(f, "entity {} has unknown component type '{}'", entity, type_name)
+            }
+            Self::InvalidField { entity, component, field } => {
+                write!(f, "entity {}: component '{}' has no field '{}' of that type", entity, component, field)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl Scene {
+    pub fn to_data(&self) -> SceneData {
+        let mut entities = Vec::with_capacity(self.entities.len());
+        for &root in &self.roots {
+            for id in self.subtree(root) {
+                entities.push(Self::entity_to_data(&self.entities[&id]));
+            }
+        }
+
+        SceneData {
+            name: self.name.clone(),
+            entities,
+        }
+    }
+
+    pub fn from_data(data: &SceneData, registry: &ComponentRegistry) -> Result<Self, SceneError> {
+        let mut scene = Scene::new(&data.name);
+        let mut max_id = 0;
+
+        for entity_data in &data.entities {
+            let id = EntityId(entity_data.id);
+            if scene.entities.contains_key(&id) {
+                return Err(SceneError::DuplicateEntity(entity_data.id));
+            }
+            let parent = match entity_data.parent {
+                Some(p) if scene.entities.contains_key(&EntityId(p)) => Some(EntityId(p)),
+                Some(p) => return Err(SceneError::MissingParent { entity: entity_data.id, parent: p }),
+                None => None,
+            };
+
+            let mut components = Vec::with_capacity(entity_data.components.len());
+            for component_data in &entity_data.components {
+                let mut component = registry.create(&component_data.type_name).ok_or_else(|| {
+                    SceneError::UnknownComponent {
+                        entity: entity_data.id,
+                        type_name: component_data.type_name.clone(),
+                    }
+                })?;
+                for (field, value) in &component_data.fields {
+                    if !component.set_field(field, value.clone()) {
+                        return Err(SceneError::InvalidField {
+                            entity: entity_data.id,
+                            component: component_data.type_name.clone(),
+                            field: field.clone(),
+                        });
+                    }
+                }
+                components.push(component);
+            }
+
+            scene.entities.insert(id, Entity {
+                id,
+                name: entity_data.name.clone(),
+                parent,
+                children: Vec::new(),
+                components,
+            });
+            match parent {
+                Some(p) => scene.entities.get_mut(&p).unwrap().children.push(id),
+                None => scene.roots.push(id),
+            }
+            max_id = max_id.max(entity_data.id);
+        }
+
+        scene.next_id = max_id + 1;
+        Ok(scene)
+    }
+
+    fn entity_to_data(entity: &Entity) -> EntityData {
+        EntityData {
+            id: entity.id.0,
+            name: entity.name.clone(),
+            parent: entity.parent.map(|p| p.0),
+            components: entity
+                .components
+                .iter()
+                .map(|component| ComponentData {
+                    type_name: component.type_name().to_string(),
+                    fields: component
+                        .fields()
+                        .iter()
+                        .filter_map(|f| component.get_field(f.name).map(|v| (f.name.to_string(), v)))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
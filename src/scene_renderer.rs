@@ -0,0 +1,461 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use bytemuck::{Pod, Zeroable};
+use glam::{Mat4, Vec3};
+use imgui::TextureId;
+use imgui_wgpu::{Renderer, Texture, TextureConfig};
+use lazy_static::lazy_static;
+use wgpu::util::DeviceExt;
+use crate::scene::{MeshRenderer, Scene, Selection};
+use crate::textures::{self, TextureHandle};
+
+/// Colour format of viewport targets; matches what imgui-wgpu samples by default
+const COLOR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+const GRID_EXTENT: i32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MeshKind {
+    Cube,
+    Plane,
+    Lines, // world-space grid
+}
+
+#[derive(Debug, Clone)]
+pub struct DrawItem {
+    pub mesh: MeshKind,
+    pub model: Mat4,
+    pub color: [f32; 4],
+}
+
+/// Everything needed to draw one viewport, submitted by UI code each frame
+#[derive(Debug, Clone)]
+pub struct ViewportFrame {
+    pub target: TextureHandle,
+    pub size: [u32; 2],
+    pub view_proj: Mat4,
+    pub clear_color: [f64; 4],
+    pub items: Vec<DrawItem>,
+}
+
+lazy_static! {
+    static ref PENDING_FRAMES: Mutex<Vec<ViewportFrame>> = Mutex::new(Vec::new());
+}
+
+/// Queue a viewport to be rendered before this frame's imgui pass.
+/// The result is available through `textures::texture_id(frame.target)`.
+pub fn submit(frame: ViewportFrame) {
+    if frame.size[0] == 0 || frame.size[1] == 0 {
+        return;
+    }
+    let mut pending = PENDING_FRAMES.lock().unwrap();
+    pending.retain(|f| f.target != frame.target);
+    pending.push(frame);
+}
+
+/// Draw items for every entity with a `MeshRenderer`, plus the ground grid
+pub fn collect_draw_items(scene: &Scene, selection: &Selection) -> Vec<DrawItem> {
+    let mut items = vec![DrawItem {
+        mesh: MeshKind::Lines,
+        model: Mat4::IDENTITY,
+        color: [0.15, 0.25, 0.35, 1.0],
+    }];
+
+    for entity in scene.iter() {
+        let Some(mesh_renderer) = entity.get_component::<MeshRenderer>() else { continue };
+        let mesh = match mesh_renderer.mesh.as_str() {
+            "Plane" => MeshKind::Plane,
+            _ => MeshKind::Cube, // stand-in until real mesh assets are loaded
+        };
+        let mut color = material_color(&mesh_renderer.material);
+        if selection.contains(entity.id()) {
+            color = [
+                color[0] * 0.5 + 0.5,
+                color[1] * 0.5 + 0.35,
+                color[2] * 0.5,
+                color[3],
+            ];
+        }
+        items.push(DrawItem {
+            mesh,
+            model: scene.world_matrix(entity.id()),
+            color,
+        });
+    }
+
+    items
+}
+
+fn material_color(material: &str) -> [f32; 4] {
+    match material {
+        "Grass" => [0.25, 0.55, 0.2, 1.0],
+        "Water" => [0.15, 0.35, 0.75, 1.0],
+        "Sand" => [0.8, 0.72, 0.45, 1.0],
+        "Stone" => [0.45, 0.45, 0.5, 1.0],
+        _ => [0.7, 0.7, 0.72, 1.0],
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct Vertex {
+    position: [f32; 3],
+    normal: [f32; 3],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct Instance {
+    model: [[f32; 4]; 4],
+    color: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct Globals {
+    view_proj: [[f32; 4]; 4],
+    light_dir: [f32; 4],
+}
+
+const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3];
+const INSTANCE_ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+    2 => Float32x4, 3 => Float32x4, 4 => Float32x4, 5 => Float32x4, 6 => Float32x4
+];
+
+struct GpuMesh {
+    vertices: wgpu::Buffer,
+    indices: wgpu::Buffer,
+    index_count: u32,
+}
+
+impl GpuMesh {
+    fn new(device: &wgpu::Device, label: &str, vertices: &[Vertex], indices: &[u16]) -> Self {
+        Self {
+            vertices: device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents: bytemuck::cast_slice(vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            }),
+            indices: device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents: bytemuck::cast_slice(indices),
+                usage: wgpu::BufferUsages::INDEX,
+            }),
+            index_count: indices.len() as u32,
+        }
+    }
+}
+
+/// Offscreen colour + depth target shown in imgui as a texture
+struct RenderTarget {
+    size: [u32; 2],
+    texture_id: TextureId,
+    depth_view: wgpu::TextureView,
+    globals: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Renders scenes into offscreen textures. Uses only downlevel features so it
+/// also runs on software/fallback adapters.
+pub struct SceneRenderer {
+    mesh_pipeline: wgpu::RenderPipeline,
+    line_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    cube: GpuMesh,
+    plane: GpuMesh,
+    grid: GpuMesh,
+    targets: HashMap<TextureHandle, RenderTarget>,
+}
+
+impl SceneRenderer {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("scene shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("scene_renderer.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("scene globals"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("scene pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let create_pipeline = |label: &str, topology: wgpu::PrimitiveTopology| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[
+                        wgpu::VertexBufferLayout {
+                            array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
+                            step_mode: wgpu::VertexStepMode::Vertex,
+                            attributes: &VERTEX_ATTRIBUTES,
+                        },
+                        wgpu::VertexBufferLayout {
+                            array_stride: std::mem::size_of::<Instance>() as wgpu::BufferAddress,
+                            step_mode: wgpu::VertexStepMode::Instance,
+                            attributes: &INSTANCE_ATTRIBUTES,
+                        },
+                    ],
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology,
+                    cull_mode: None,
+                    ..Default::default()
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: DEPTH_FORMAT,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::Less,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: COLOR_FORMAT,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                multiview: None,
+            })
+        };
+
+        let mesh_pipeline = create_pipeline("scene mesh pipeline", wgpu::PrimitiveTopology::TriangleList);
+        let line_pipeline = create_pipeline("scene line pipeline", wgpu::PrimitiveTopology::LineList);
+
+        let (cube_vertices, cube_indices) = cube_mesh();
+        let (plane_vertices, plane_indices) = plane_mesh();
+        let (grid_vertices, grid_indices) = grid_mesh();
+
+        Self {
+            cube: GpuMesh::new(device, "cube", &cube_vertices, &cube_indices),
+            plane: GpuMesh::new(device, "plane", &plane_vertices, &plane_indices),
+            grid: GpuMesh::new(device, "grid", &grid_vertices, &grid_indices),
+            mesh_pipeline,
+            line_pipeline,
+            bind_group_layout,
+            targets: HashMap::new(),
+        }
+    }
+
+    /// Render every viewport submitted this frame into its target.
+    /// Must run before the imgui pass so the textures are up to date.
+    pub fn render_pending(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        renderer: &mut Renderer,
+    ) {
+        // Drop targets whose texture was released by the UI
+        self.targets.retain(|handle, _| textures::texture_id(*handle).is_some());
+
+        let frames = std::mem::take(&mut *PENDING_FRAMES.lock().unwrap());
+        for frame in frames {
+            self.prepare_target(device, renderer, frame.target, frame.size);
+            let Some(target) = self.targets.get(&frame.target) else { continue };
+            let Some(color_view) = renderer.textures.get(target.texture_id).map(|t| t.view()) else { continue };
+
+            let globals = Globals {
+                view_proj: frame.view_proj.to_cols_array_2d(),
+                light_dir: Vec3::new(-0.4, -1.0, 0.3).normalize().extend(0.0).to_array(),
+            };
+            queue.write_buffer(&target.globals, 0, bytemuck::bytes_of(&globals));
+
+            // Group instances by mesh so each mesh is one instanced draw
+            let batches: Vec<(MeshKind, Vec<Instance>)> = [MeshKind::Lines, MeshKind::Plane, MeshKind::Cube]
+                .into_iter()
+                .map(|kind| {
+                    let instances = frame
+                        .items
+                        .iter()
+                        .filter(|item| item.mesh == kind)
+                        .map(|item| Instance { model: item.model.to_cols_array_2d(), color: item.color })
+                        .collect();
+                    (kind, instances)
+                })
+                .collect();
+            let instance_buffers: Vec<Option<wgpu::Buffer>> = batches
+                .iter()
+                .map(|(_, instances)| {
+                    (!instances.is_empty()).then(|| {
+                        device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                            label: Some("scene instances"),
+                            contents: bytemuck::cast_slice(instances),
+                            usage: wgpu::BufferUsages::VERTEX,
+                        })
+                    })
+                })
+                .collect();
+
+            let [r, g, b, a] = frame.clear_color;
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("scene viewport pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: color_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color { r, g, b, a }),
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &target.depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: false,
+                    }),
+                    stencil_ops: None,
+                }),
+            });
+            pass.set_bind_group(0, &target.bind_group, &[]);
+
+            for ((kind, instances), buffer) in batches.iter().zip(&instance_buffers) {
+                let Some(buffer) = buffer else { continue };
+                let (pipeline, mesh) = match kind {
+                    MeshKind::Lines => (&self.line_pipeline, &self.grid),
+                    MeshKind::Plane => (&self.mesh_pipeline, &self.plane),
+                    MeshKind::Cube => (&self.mesh_pipeline, &self.cube),
+                };
+                pass.set_pipeline(pipeline);
+                pass.set_vertex_buffer(0, mesh.vertices.slice(..));
+                pass.set_vertex_buffer(1, buffer.slice(..));
+                pass.set_index_buffer(mesh.indices.slice(..), wgpu::IndexFormat::Uint16);
+                pass.draw_indexed(0..mesh.index_count, 0, 0..instances.len() as u32);
+            }
+        }
+    }
+
+    /// Create the target on first use and recreate it only when the size changes
+    fn prepare_target(&mut self, device: &wgpu::Device, renderer: &mut Renderer, handle: TextureHandle, size: [u32; 2]) {
+        if self.targets.get(&handle).map_or(false, |t| t.size == size) {
+            return;
+        }
+
+        let extent = wgpu::Extent3d {
+            width: size[0],
+            height: size[1],
+            depth_or_array_layers: 1,
+        };
+        let color = Texture::new(device, renderer, TextureConfig {
+            size: extent,
+            label: Some("scene viewport color"),
+            format: Some(COLOR_FORMAT),
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            ..Default::default()
+        });
+        let texture_id = match self.targets.get(&handle) {
+            Some(existing) => {
+                renderer.textures.replace(existing.texture_id, color);
+                existing.texture_id
+            }
+            None => renderer.textures.insert(color),
+        };
+        textures::register(handle, texture_id, size);
+
+        let depth = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("scene viewport depth"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: DEPTH_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+
+        let globals = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("scene globals"),
+            size: std::mem::size_of::<Globals>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("scene globals"),
+            layout: &self.bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: globals.as_entire_binding(),
+            }],
+        });
+
+        self.targets.insert(handle, RenderTarget {
+            size,
+            texture_id,
+            depth_view: depth.create_view(&wgpu::TextureViewDescriptor::default()),
+            globals,
+            bind_group,
+        });
+    }
+}
+
+fn cube_mesh() -> (Vec<Vertex>, Vec<u16>) {
+    // (normal, tangent u, tangent v) for each face of a unit cube centred on the origin
+    let faces: [([f32; 3], [f32; 3], [f32; 3]); 6] = [
+        ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]),
+        ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+        ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+        ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+        ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+        ([0.0, 0.0, -1.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+    ];
+
+    let mut vertices = Vec::with_capacity(24);
+    let mut indices = Vec::with_capacity(36);
+    for (normal, u, v) in faces {
+        let (n, u, v) = (Vec3::from(normal), Vec3::from(u), Vec3::from(v));
+        let base = vertices.len() as u16;
+        for (su, sv) in [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)] {
+            vertices.push(Vertex {
+                position: (n * 0.5 + u * su + v * sv).to_array(),
+                normal,
+            });
+        }
+        indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+    (vertices, indices)
+}
+
+fn plane_mesh() -> (Vec<Vertex>, Vec<u16>) {
+    let normal = [0.0, 1.0, 0.0];
+    let vertices = [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)]
+        .into_iter()
+        .map(|(x, z)| Vertex { position: [x, 0.0, z], normal })
+        .collect();
+    (vertices, vec![0, 1, 2, 0, 2, 3])
+}
+
+fn grid_mesh() -> (Vec<Vertex>, Vec<u16>) {
+    let extent = GRID_EXTENT as f32;
+    let mut vertices = Vec::new();
+    for i in -GRID_EXTENT..=GRID_EXTENT {
+        let t = i as f32;
+        for (a, b) in [([t, 0.0, -extent], [t, 0.0, extent]), ([-extent, 0.0, t], [extent, 0.0, t])] {
+            vertices.push(Vertex { position: a, normal: [0.0; 3] });
+            vertices.push(Vertex { position: b, normal: [0.0; 3] });
+        }
+    }
+    let indices = (0..vertices.len() as u16).collect();
+    (vertices, indices)
+}
@@ -0,0 +1,44 @@
+struct Globals {
+    view_proj: mat4x4<f32>,
+    light_dir: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> globals: Globals;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) model_0: vec4<f32>,
+    @location(3) model_1: vec4<f32>,
+    @location(4) model_2: vec4<f32>,
+    @location(5) model_3: vec4<f32>,
+    @location(6) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) normal: vec3<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    let model = mat4x4<f32>(in.model_0, in.model_1, in.model_2, in.model_3);
+    var out: VertexOutput;
+    out.clip_position = globals.view_proj * model * vec4<f32>(in.position, 1.0);
+    out.normal = (model * vec4<f32>(in.normal, 0.0)).xyz;
+    out.color = in.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Lines have no normal and are drawn unlit
+    var shade = 1.0;
+    if (length(in.normal) > 0.0001) {
+        let diffuse = max(dot(normalize(in.normal), -globals.light_dir.xyz), 0.0);
+        shade = 0.35 + 0.65 * diffuse;
+    }
+    return vec4<f32>(in.color.rgb * shade, in.color.a);
+}
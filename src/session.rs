@@ -0,0 +1,182 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::project::write_atomic;
+use crate::ui::panel_system::DockLayout;
+
+const SESSIONS_FILE: &str = "sessions.ron";
+
+/// Editor state restored on the next launch: window geometry, open tabs and panel layout.
+/// One is kept per project, keyed by the project file open when the editor exited.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub project: Option<PathBuf>,
+    pub window: WindowState,
+    pub tabs: Vec<TabState>,
+    pub active_tab: Option<usize>, // index into `tabs`
+    pub panels: DockLayout,
+    pub imgui_ini: String, // imgui's own window positions and sizes
+    #[serde(skip)]
+    pub preferences: Preferences, // saved once for every project, see `SessionsFile`
+}
+
+/// Editor-wide state that stays the same when switching projects
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub recent_actions: Vec<String>, // most recently run first, for the command palette
+    pub log_to_file: bool, // the Console mirrors the log to a file
+    pub console_history: Vec<String>, // commands typed into the Console, oldest first
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowState {
+    // Logical size of the window when not maximized
+    pub width: f64,
+    pub height: f64,
+    pub maximized: bool,
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        Self { width: 1600.0, height: 1000.0, maximized: false }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TabState {
+    pub type_name: String,
+    pub file: Option<PathBuf>,
+    pub pinned: bool,
+    pub floating: Option<[f32; 2]>,
+    pub detached: bool, // `floating` is the position of an OS window of its own
+}
+
+/// Every saved session plus the project to reopen on launch
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SessionsFile {
+    last_project: Option<PathBuf>,
+    preferences: Preferences,
+    sessions: Vec<Session>,
+}
+
+/// Where editor settings live: `%APPDATA%\Pulsar` on Windows, `~/.config/pulsar` elsewhere
+pub fn config_dir() -> PathBuf {
+    if cfg!(windows) {
+        if let Some(app_data) = env::var_os("APPDATA") {
+            return PathBuf::from(app_data).join("Pulsar");
+        }
+    }
+    if let Some(config) = env::var_os("XDG_CONFIG_HOME") {
+        return PathBuf::from(config).join("pulsar");
+    }
+    match env::var_os("HOME") {
+        Some(home) => PathBuf::from(home).join(".config").join("pulsar"),
+        None => PathBuf::from(".pulsar"),
+    }
+}
+
+/// A missing or unreadable file counts as no saved sessions
+fn read_sessions(path: &Path) -> SessionsFile {
+    let Ok(text) = fs::read_to_string(path) else { return SessionsFile::default() };
+    match ron::de::from_str(&text) {
+        Ok(file) => file,
+        Err(e) => {
+            log::warn!("Ignoring corrupt session file {}: {}", path.display(), e);
+            SessionsFile::default()
+        }
+    }
+}
+
+/// The session of the project open when the editor last exited, or defaults
+pub fn load() -> Session {
+    let file = read_sessions(&config_dir().join(SESSIONS_FILE));
+    let mut session = file.sessions
+        .into_iter()
+        .find(|s| s.project == file.last_project)
+        .unwrap_or_default();
+    session.preferences = file.preferences;
+    session
+}
+
+/// The session saved for `project`, e.g. when it is opened while the editor runs
+pub fn load_project(project: &Path) -> Option<Session> {
+    let file = read_sessions(&config_dir().join(SESSIONS_FILE));
+    file.sessions.into_iter().find(|s| s.project.as_deref() == Some(project))
+}
+
+/// Store `session` under its project, replacing the one saved before, and its
+/// preferences for every project
+pub fn save(session: Session) {
+    let path = config_dir().join(SESSIONS_FILE);
+    let mut file = read_sessions(&path);
+    file.last_project = session.project.clone();
+    file.preferences = session.preferences.clone();
+    file.sessions.retain(|s| s.project != session.project);
+    file.sessions.push(session);
+
+    if let Err(e) = write_ron(&path, &file) {
+        log::error!("Failed to save session to {}: {}", path.display(), e);
+    }
+}
+
+/// Write `value` as pretty RON, creating the directory if needed
+pub fn write_ron<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let config = ron::ser::PrettyConfig::new()
+        .struct_names(false)
+        .indentor("    ".to_string());
+    let text = ron::ser::to_string_pretty(value, config).map_err(|e| e.to_string())?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    write_atomic(path, &text).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("pulsar-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn sessions_round_trip() {
+        let path = temp_file("sessions.ron");
+        let file = SessionsFile {
+            last_project: Some(PathBuf::from("game.pulsar")),
+            preferences: Preferences { log_to_file: true, ..Default::default() },
+            sessions: vec![Session { project: Some(PathBuf::from("game.pulsar")), active_tab: Some(1), ..Default::default() }],
+        };
+        write_ron(&path, &file).unwrap();
+
+        let read = read_sessions(&path);
+        fs::remove_file(&path).ok();
+        assert_eq!(read.last_project, file.last_project);
+        assert!(read.preferences.log_to_file);
+        assert_eq!(read.sessions.len(), 1);
+        assert_eq!(read.sessions[0].active_tab, Some(1));
+    }
+
+    #[test]
+    fn corrupt_sessions_file_falls_back_to_defaults() {
+        let path = temp_file("corrupt-sessions.ron");
+        fs::write(&path, "(sessions: [(project: ").unwrap();
+
+        let read = read_sessions(&path);
+        fs::remove_file(&path).ok();
+        assert!(read.last_project.is_none());
+        assert!(read.sessions.is_empty());
+    }
+
+    #[test]
+    fn missing_sessions_file_falls_back_to_defaults() {
+        let read = read_sessions(&temp_file("missing-sessions.ron"));
+        assert!(read.sessions.is_empty());
+    }
+}
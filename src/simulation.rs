@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use glam::Vec3;
+use crate::console;
+use crate::scene::{BoxCollider, EntityId, RigidBody, Scene, Transform};
+
+/// Length of one simulation step in seconds
+pub const FIXED_STEP: f32 = 1.0 / 60.0;
+
+// Frame time beyond this is dropped so a long hitch doesn't trigger hundreds of steps
+const MAX_FRAME_TIME: f32 = 0.25;
+
+const GRAVITY: Vec3 = Vec3::new(0.0, -9.81, 0.0);
+
+/// Console variable scaling how fast play mode runs
+pub const TIME_SCALE: &str = "time_scale";
+
+/// Add the simulation's console variables
+pub fn register_cvars() {
+    console::register_ranged_cvar(TIME_SCALE, "Speed of play mode, 1 being real time", 1.0, 0.0, 10.0);
+}
+
+/// Game loop run by play mode. Advances in fixed steps: rigid bodies fall under
+/// gravity and are stopped by the box colliders of static entities.
+#[derive(Debug, Default)]
+pub struct Simulation {
+    velocities: HashMap<EntityId, Vec3>,
+    accumulator: f32,
+    time: f32,
+    steps: u64,
+}
+
+impl Simulation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Simulated time in seconds
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Run as many fixed steps as fit into `delta` seconds of frame time, sped up
+    /// or slowed down by the `time_scale` console variable
+    pub fn update(&mut self, scene: &mut Scene, delta: f32) {
+        let time_scale = console::cvar(TIME_SCALE).map_or(1.0, |value| value.as_f32());
+        self.accumulator = (self.accumulator + delta * time_scale).min(MAX_FRAME_TIME);
+        while self.accumulator >= FIXED_STEP {
+            self.step(scene);
+            self.accumulator -= FIXED_STEP;
+        }
+    }
+
+    /// Advance the scene by exactly one fixed step
+    pub fn step(&mut self, scene: &mut Scene) {
+        // Entities removed during play take their velocity with them
+        self.velocities.retain(|id, _| scene.contains(*id));
+
+        let is_dynamic = |id: EntityId| {
+            scene
+                .get(id)
+                .and_then(|e| e.get_component::<RigidBody>())
+                .map_or(false, |body| !body.is_kinematic)
+        };
+        let bodies: Vec<EntityId> = scene.iter().map(|e| e.id()).filter(|&id| is_dynamic(id)).collect();
+        let obstacles: Vec<(EntityId, Vec3, Vec3)> = scene
+            .iter()
+            .filter(|e| !is_dynamic(e.id()))
+            .filter_map(|e| {
+                let collider = e.get_component::<BoxCollider>().filter(|c| !c.is_trigger)?;
+                let (min, max) = collider_bounds(scene, e.id(), collider);
+                Some((e.id(), min, max))
+            })
+            .collect();
+
+        for id in bodies {
+            let Some(body) = scene.get(id).and_then(|e| e.get_component::<RigidBody>()).cloned() else { continue };
+            let velocity = self.velocities.entry(id).or_insert(Vec3::ZERO);
+            if body.use_gravity {
+                *velocity += GRAVITY * FIXED_STEP;
+            }
+
+            let world = scene.world_matrix(id);
+            let mut position = world.transform_point3(Vec3::ZERO) + *velocity * FIXED_STEP;
+
+            // Push the body out of anything it now overlaps, along the shallowest axis
+            let collider = scene
+                .get(id)
+                .and_then(|e| e.get_component::<BoxCollider>())
+                .cloned()
+                .unwrap_or_default();
+            let (min, max) = collider_bounds(scene, id, &collider);
+            let offset = position - world.transform_point3(Vec3::ZERO);
+            let (mut min, mut max) = (min + offset, max + offset);
+            for &(other, other_min, other_max) in &obstacles {
+                if scene.is_ancestor(id, other) || scene.is_ancestor(other, id) {
+                    continue;
+                }
+                let overlap = max.min(other_max) - min.max(other_min);
+                if overlap.min_element() <= 0.0 {
+                    continue;
+                }
+                let axis = if overlap.x < overlap.y && overlap.x < overlap.z {
+                    0
+                } else if overlap.y < overlap.z {
+                    1
+                } else {
+                    2
+                };
+                let direction = if min[axis] + max[axis] < other_min[axis] + other_max[axis] { -1.0 } else { 1.0 };
+                let push = overlap[axis] * direction;
+                position[axis] += push;
+                min[axis] += push;
+                max[axis] += push;
+                velocity[axis] = 0.0;
+            }
+
+            set_world_position(scene, id, position);
+        }
+
+        self.time += FIXED_STEP;
+        self.steps += 1;
+    }
+}
+
+/// World-space box around an entity's collider
+fn collider_bounds(scene: &Scene, id: EntityId, collider: &BoxCollider) -> (Vec3, Vec3) {
+    let matrix = scene.world_matrix(id);
+    let half = Vec3::from(collider.size) * 0.5;
+    let mut min = Vec3::splat(f32::MAX);
+    let mut max = Vec3::splat(f32::MIN);
+    for i in 0..8 {
+        let corner = Vec3::new(
+            if i & 1 == 0 { -half.x } else { half.x },
+            if i & 2 == 0 { -half.y } else { half.y },
+            if i & 4 == 0 { -half.z } else { half.z },
+        );
+        let point = matrix.transform_point3(corner);
+        min = min.min(point);
+        max = max.max(point);
+    }
+    (min, max)
+}
+
+fn set_world_position(scene: &mut Scene, id: EntityId, position: Vec3) {
+    let parent = scene.get(id).and_then(|e| e.parent());
+    let local = match parent {
+        Some(parent) => scene.world_matrix(parent).inverse().transform_point3(position),
+        None => position,
+    };
+    if let Some(transform) = scene.get_mut(id).and_then(|e| e.get_component_mut::<Transform>()) {
+        transform.position = local.into();
+    }
+}
@@ -1,5 +1,15 @@
 use imgui::*;
-use std::collections::HashMap;
+use std::any::Any;
+use std::path::{Path, PathBuf};
+use crate::console;
+use crate::history::{Command, History};
+use crate::keybindings;
+use crate::play_mode::PlaySession;
+use crate::scene::{Scene, Selection};
+use crate::session::TabState;
+use crate::ui::editor_actions;
+use crate::ui::theme::PulsarTheme;
+use crate::viewport;
 
 pub trait TabContent {
     fn render(&mut self, ui: &Ui);
@@ -7,206 +17,834 @@ pub trait TabContent {
     fn is_dirty(&self) -> bool;
     fn can_close(&self) -> bool { true }
     fn get_icon(&self) -> Option<&str> { None }
+
+    /// Called every frame for every open tab, including ones in the background
+    fn update(&mut self, _ui: &Ui) {}
+
+    /// The scene this tab edits, shown in the Hierarchy and Inspector panels
+    fn scene_document(&mut self) -> Option<SceneDocument<'_>> { None }
+
+    /// Approximate memory held by the tab's document and undo history, so the tab
+    /// bar's history can count closed tabs against its limit
+    fn memory_size(&self) -> usize { std::mem::size_of_val(self) }
+
+    /// Whether File > Save applies to this tab
+    fn can_save(&self) -> bool { false }
+    /// Save the document. A tab that is still dirty afterwards is treated as not
+    /// saved, e.g. because the file dialog was cancelled or the write failed.
+    fn save(&mut self, _save_as: bool) {}
+
+    /// File the tab's document was loaded from or last saved to
+    fn file_path(&self) -> Option<&Path> { None }
+    /// Load a file into a freshly created tab, e.g. when restoring a session
+    fn open_file(&mut self, _path: PathBuf) {}
+
+    /// Whether an action from inside the tab, such as loading another file, is
+    /// waiting because it would throw away unsaved changes. The owner asks the
+    /// user, then calls `resume_discard`.
+    fn wants_discard(&self) -> bool { false }
+    /// Carry out the waiting action, or drop it if `proceed` is false
+    fn resume_discard(&mut self, _proceed: bool) {}
+
+    // Undo history of the tab's document. The sequence numbers let Edit > Undo pick
+    // between this and the tab bar's own history.
+    fn undo_sequence(&self) -> Option<u64> { None }
+    fn redo_sequence(&self) -> Option<u64> { None }
+    fn undo_label(&self) -> Option<String> { None }
+    fn redo_label(&self) -> Option<String> { None }
+    fn undo(&mut self) {}
+    fn redo(&mut self) {}
+    /// End the undo step edits are merging into, see `History::seal`
+    fn seal_history(&mut self) {}
+
+    /// Run a registered action aimed at the tab, such as framing the viewport's selection
+    fn run_action(&mut self, _id: &str) {}
+}
+
+/// Scene state a tab shares with the Hierarchy and Inspector panels
+pub struct SceneDocument<'a> {
+    pub scene: &'a mut Scene,
+    pub history: &'a mut History<Scene>,
+    pub selection: &'a mut Selection,
+    pub play_session: Option<&'a mut PlaySession>,
+}
+
+/// Identifies an open tab. IDs are never reused, so undo can find a tab again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TabId(pub u64);
+
+const TAB_PAYLOAD: &str = "EDITOR_TAB";
+
+pub struct Tab {
+    id: TabId,
+    type_name: String, // registered type the tab was created from
+    content: Box<dyn TabContent>,
+    pinned: bool, // kept at the left of the bar, without a close button
+    floating: Option<[f32; 2]>, // torn off into its own window, opened at this position
+    detached: bool, // the floating window is an OS window of its own, see `DetachedWindow`
+}
+
+impl Tab {
+    fn new(id: TabId, type_name: &str, content: Box<dyn TabContent>) -> Self {
+        Self { id, type_name: type_name.to_string(), content, pinned: false, floating: None, detached: false }
+    }
+
+    pub fn id(&self) -> TabId {
+        self.id
+    }
+
+    pub fn content(&self) -> &dyn TabContent {
+        self.content.as_ref()
+    }
+
+    pub fn content_mut(&mut self) -> &mut dyn TabContent {
+        self.content.as_mut()
+    }
+
+    /// Icon and title, with a "*" while there are unsaved changes
+    pub fn label(&self) -> String {
+        let dirty_marker = if self.content.is_dirty() { "*" } else { "" };
+        format!("{} {}{}", self.content.get_icon().unwrap_or(""), self.content.get_title(), dirty_marker)
+    }
+}
+
+struct TabType {
+    name: String,
+    icon: String,
+    description: String,
+    factory: Box<dyn Fn() -> Box<dyn TabContent>>,
+}
+
+/// Tab bar requests, applied by the owner after the bar has been drawn
+#[derive(Debug, Clone, PartialEq)]
+pub enum TabAction {
+    Activate(TabId),
+    Open(String), // tab type name
+    Close(TabId),
+    Move { id: TabId, to: usize }, // index in the tab list
+    SetPinned(TabId, bool),
+    Float(TabId, [f32; 2]), // window position
+    Detach(TabId, [f32; 2]), // OS window position, relative to the main window
+    Dock(TabId),
 }
 
+/// Open editor tabs and the registry of tab types they are created from
 pub struct TabSystem {
-    tabs: Vec<Box<dyn TabContent>>,
-    tab_types: HashMap<String, (String, Box<dyn Fn() -> Box<dyn TabContent>>)>, // name -> (icon, factory)
-    selected_tab: usize,
-    next_tab_id: usize,
+    tabs: Vec<Tab>, // pinned tabs first
+    tab_types: Vec<TabType>, // in registration order
+    active: Option<TabId>, // the tab panels, undo and saving act on, possibly floating
+    selected: Option<TabId>, // the docked tab shown in the editor area
+    next_tab_id: u64,
     pub show_new_tab_popup: bool,
-    tab_to_close: Option<usize>,
-    context_menu_tab: Option<usize>,
-    context_menu_pos: [f32; 2],
+    search_query: String,
+    dragging: Option<TabId>,
+    overflowing: bool, // more tabs than fit in the bar last frame
+    scroll_to_selected: bool,
 }
 
 impl TabSystem {
     pub fn new() -> Self {
         Self {
             tabs: Vec::new(),
-            tab_types: HashMap::new(),
-            selected_tab: 0,
-            next_tab_id: 0,
+            tab_types: Vec::new(),
+            active: None,
+            selected: None,
+            next_tab_id: 1,
             show_new_tab_popup: false,
-            tab_to_close: None,
-            context_menu_tab: None,
-            context_menu_pos: [0.0, 0.0],
+            search_query: String::new(),
+            dragging: None,
+            overflowing: false,
+            scroll_to_selected: false,
         }
     }
 
-    pub fn register_tab_type<F>(&mut self, name: &str, icon: &str, factory: F)
+    pub fn register_tab_type<F>(&mut self, name: &str, icon: &str, description: &str, factory: F)
     where
         F: Fn() -> Box<dyn TabContent> + 'static
     {
-        self.tab_types.insert(name.to_string(), (icon.to_string(), Box::new(factory)));
+        self.tab_types.retain(|t| t.name != name);
+        self.tab_types.push(TabType {
+            name: name.to_string(),
+            icon: icon.to_string(),
+            description: description.to_string(),
+            factory: Box::new(factory),
+        });
     }
 
-    pub fn add_tab<T>(&mut self, _tab_type: &str, content: T)
-    where
-        T: TabContent + 'static
-    {
-        self.tabs.push(Box::new(content));
-        self.selected_tab = self.tabs.len().saturating_sub(1);
+    /// Open a new tab of a registered type. Returns None for unknown types.
+    pub fn add_tab(&mut self, tab_type: &str) -> Option<TabId> {
+        let content = (self.tab_types.iter().find(|t| t.name == tab_type)?.factory)();
+        Some(self.insert_tab(tab_type, content))
+    }
+
+    /// Open a tab for content created elsewhere, e.g. an editor for a file
+    pub fn insert_tab(&mut self, tab_type: &str, content: Box<dyn TabContent>) -> TabId {
+        let id = TabId(self.next_tab_id);
         self.next_tab_id += 1;
+        self.insert_at(self.tabs.len(), Tab::new(id, tab_type, content));
+        self.set_active(id);
+        id
     }
 
-    pub fn close_tab(&mut self, index: usize) {
-        if index < self.tabs.len() && self.tabs[index].can_close() {
-            self.tabs.remove(index);
-            if self.selected_tab >= self.tabs.len() && !self.tabs.is_empty() {
-                self.selected_tab = self.tabs.len() - 1;
-            } else if self.tabs.is_empty() {
-                self.selected_tab = 0;
-            } else if self.selected_tab > index {
-                self.selected_tab -= 1;
-            }
+    /// Remove a tab and hand it back, activating a neighbour if it was active.
+    /// Unsaved changes are not checked here; the owner asks before closing.
+    pub fn close_tab(&mut self, id: TabId) -> Option<Tab> {
+        let index = self.index_of(id)?;
+        if !self.tabs[index].content.can_close() {
+            return None;
         }
+        Some(self.remove_at(index))
     }
 
-    pub fn render(&mut self, ui: &Ui) {
-        let window_size = ui.content_region_avail();
+    pub fn get(&self, id: TabId) -> Option<&Tab> {
+        self.tabs.iter().find(|t| t.id == id)
+    }
 
-        // Tab bar
-        let tab_bar_height = ui.frame_height();
+    pub fn get_mut(&mut self, id: TabId) -> Option<&mut Tab> {
+        self.tabs.iter_mut().find(|t| t.id == id)
+    }
 
-        ui.child_window("TabBar")
-            .size([0.0, tab_bar_height])
-            .flags(WindowFlags::NO_SCROLLBAR | WindowFlags::NO_SCROLL_WITH_MOUSE)
-            .build(|| {
-                // Left side - tabs (using simple same_line instead of columns)
-                for (i, tab) in self.tabs.iter().enumerate() {
-                    let is_selected = i == self.selected_tab;
-
-                    if i > 0 { ui.same_line(); }
-
-                    let mut button_color = if is_selected {
-                        [0.2, 0.4, 0.8, 1.0]
-                    } else {
-                        [0.2, 0.2, 0.2, 1.0]
-                    };
-
-                    let _style_color = ui.push_style_color(StyleColor::Button, button_color);
-                    let _style_color2 = ui.push_style_color(
-                        StyleColor::ButtonHovered,
-                        [button_color[0] + 0.1, button_color[1] + 0.1, button_color[2] + 0.1, 1.0]
-                    );
-
-                    let tab_text = if tab.is_dirty() {
-                        format!("{}* {}", tab.get_icon().unwrap_or(""), tab.get_title())
-                    } else {
-                        format!("{} {}", tab.get_icon().unwrap_or(""), tab.get_title())
-                    };
-
-                    if ui.button(&tab_text) {
-                        self.selected_tab = i;
-                    }
+    /// The first tab with an action waiting on the unsaved changes prompt
+    pub fn discard_request(&self) -> Option<TabId> {
+        self.tabs.iter().find(|t| t.content.wants_discard()).map(|t| t.id)
+    }
 
-                    // Right-click context menu
-                    if ui.is_item_clicked_with_button(MouseButton::Right) {
-                        self.context_menu_tab = Some(i);
-                        self.context_menu_pos = ui.io().mouse_pos;
-                        ui.open_popup("tab_context_menu");
-                    }
+    /// Open tabs with unsaved changes, in tab bar order
+    pub fn dirty_tabs(&self) -> Vec<TabId> {
+        self.tabs.iter().filter(|t| t.content.is_dirty()).map(|t| t.id).collect()
+    }
+
+    /// Make a tab active. A docked tab is also brought into view in the editor area.
+    pub fn set_active(&mut self, id: TabId) {
+        let Some(index) = self.index_of(id) else { return };
+        self.active = Some(id);
+        if self.tabs[index].floating.is_none() {
+            self.selected = Some(id);
+            self.scroll_to_selected = true;
+        }
+    }
+
+    /// Names and icons of the registered tab types, in registration order
+    pub fn tab_types(&self) -> Vec<(&str, &str)> {
+        self.tab_types.iter().map(|t| (t.name.as_str(), t.icon.as_str())).collect()
+    }
+
+    pub fn active(&self) -> Option<&Tab> {
+        self.active.and_then(|id| self.get(id))
+    }
+
+    pub fn active_mut(&mut self) -> Option<&mut Tab> {
+        let id = self.active?;
+        self.get_mut(id)
+    }
+
+    /// The docked tab shown in the editor area
+    pub fn selected(&self) -> Option<&Tab> {
+        self.selected.and_then(|id| self.get(id))
+    }
+
+    /// Move a tab to `to` in the tab list, staying within its pinned or unpinned group
+    pub fn move_tab(&mut self, id: TabId, to: usize) {
+        let Some(index) = self.index_of(id) else { return };
+        let tab = self.tabs.remove(index);
+        self.insert_at(to, tab);
+    }
+
+    /// Pinned tabs go to the end of the pinned group, unpinned ones to the start of the rest
+    pub fn set_pinned(&mut self, id: TabId, pinned: bool) {
+        let Some(index) = self.index_of(id) else { return };
+        let mut tab = self.tabs.remove(index);
+        tab.pinned = pinned;
+        let boundary = self.pinned_count();
+        self.insert_at(boundary, tab);
+    }
+
+    /// Tear a tab off the bar into a floating window at `position`
+    pub fn float_tab(&mut self, id: TabId, position: [f32; 2]) {
+        let Some(index) = self.index_of(id) else { return };
+        self.tabs[index].floating = Some(position);
+        if self.selected == Some(id) {
+            self.selected = self.nearest_docked(index);
+        }
+        self.active = Some(id);
+    }
 
-                    // Close button
-                    if tab.can_close() {
+    /// Float a tab in an OS window of its own at `position`, relative to the main window
+    pub fn detach_tab(&mut self, id: TabId, position: [f32; 2]) {
+        self.float_tab(id, position);
+        if let Some(tab) = self.get_mut(id) {
+            tab.detached = true;
+        }
+    }
+
+    /// Put a floating tab back into the bar
+    pub fn dock_tab(&mut self, id: TabId) {
+        let Some(index) = self.index_of(id) else { return };
+        self.tabs[index].floating = None;
+        self.tabs[index].detached = false;
+        self.set_active(id);
+    }
+
+    /// Tabs shown in OS windows of their own, with their labels and window positions
+    pub fn detached_tabs(&self) -> Vec<(TabId, String, [f32; 2])> {
+        self.tabs
+            .iter()
+            .filter(|t| t.detached)
+            .filter_map(|t| Some((t.id, t.label(), t.floating?)))
+            .collect()
+    }
+
+    /// Remember where a detached tab's window was moved to
+    pub fn move_detached_tab(&mut self, id: TabId, position: [f32; 2]) {
+        if let Some(tab) = self.get_mut(id).filter(|t| t.detached) {
+            tab.floating = Some(position);
+        }
+    }
+
+    /// File of the project being worked on: the active tab's, or else the first
+    /// open tab with one
+    pub fn project_file(&self) -> Option<&Path> {
+        self.active()
+            .and_then(|tab| tab.content().file_path())
+            .or_else(|| self.tabs.iter().find_map(|tab| tab.content().file_path()))
+    }
+
+    /// Open tabs in bar order and the index of the active one, for saving the session
+    pub fn tab_states(&self) -> (Vec<TabState>, Option<usize>) {
+        let states = self
+            .tabs
+            .iter()
+            .map(|t| TabState {
+                type_name: t.type_name.clone(),
+                file: t.content.file_path().map(Path::to_path_buf),
+                pinned: t.pinned,
+                floating: t.floating,
+                detached: t.detached,
+            })
+            .collect();
+        (states, self.active.and_then(|id| self.index_of(id)))
+    }
+
+    /// Reopen a tab saved by `tab_states`. Returns None if its type is no longer registered.
+    pub fn restore_tab(&mut self, state: &TabState) -> Option<TabId> {
+        let id = self.add_tab(&state.type_name)?;
+        if let Some(path) = &state.file {
+            self.get_mut(id)?.content.open_file(path.clone());
+        }
+        if state.pinned {
+            self.set_pinned(id, true);
+        }
+        match state.floating {
+            Some(position) if state.detached => self.detach_tab(id, position),
+            Some(position) => self.float_tab(id, position),
+            None => {}
+        }
+        Some(id)
+    }
+
+    fn index_of(&self, id: TabId) -> Option<usize> {
+        self.tabs.iter().position(|t| t.id == id)
+    }
+
+    fn pinned_count(&self) -> usize {
+        self.tabs.iter().take_while(|t| t.pinned).count()
+    }
+
+    /// Insert at `index`, clamped so pinned tabs stay in front of the others
+    fn insert_at(&mut self, index: usize, tab: Tab) {
+        let pinned = self.pinned_count();
+        let index = if tab.pinned { index.min(pinned) } else { index.clamp(pinned, self.tabs.len()) };
+        self.tabs.insert(index, tab);
+    }
+
+    fn remove_at(&mut self, index: usize) -> Tab {
+        let tab = self.tabs.remove(index);
+        if self.selected == Some(tab.id) {
+            self.selected = self.nearest_docked(index);
+        }
+        if self.active == Some(tab.id) {
+            self.active = self.selected;
+        }
+        tab
+    }
+
+    /// The docked tab closest to the left of `index`, or else to its right
+    fn nearest_docked(&self, index: usize) -> Option<TabId> {
+        let index = index.min(self.tabs.len());
+        let docked = |t: &&Tab| t.floating.is_none();
+        self.tabs[..index]
+            .iter()
+            .rev()
+            .find(docked)
+            .or_else(|| self.tabs[index..].iter().find(docked))
+            .map(|t| t.id)
+    }
+
+    /// Give every tab its per-frame update
+    pub fn update(&mut self, ui: &Ui) {
+        for tab in &mut self.tabs {
+            tab.content.update(ui);
+        }
+    }
+
+    /// End the undo step each tab's edits are merging into
+    pub fn seal_histories(&mut self) {
+        for tab in &mut self.tabs {
+            tab.content.seal_history();
+        }
+    }
+
+    /// Draw the docked tabs plus the overflow and "Add Tab" buttons. Tabs are dragged
+    /// onto each other to reorder them, or out of the bar to float them.
+    pub fn render_tab_bar(&mut self, ui: &Ui) -> Option<TabAction> {
+        let mut action = None;
+        let bar_min = ui.window_pos();
+        let bar_size = ui.window_size();
+
+        // Leave room on the right for the dropdown and "Add Tab" buttons
+        let buttons_width = if self.overflowing { 144.0 } else { 108.0 };
+        let strip_width = (ui.content_region_avail()[0] - buttons_width).max(50.0);
+        ui.child_window("TabStrip")
+            .size([strip_width, 30.0])
+            .scroll_bar(false)
+            .horizontal_scrollbar(true)
+            .build(|| {
+                let docked: Vec<usize> = (0..self.tabs.len()).filter(|&i| self.tabs[i].floating.is_none()).collect();
+                for (n, &index) in docked.iter().enumerate() {
+                    if n > 0 {
                         ui.same_line();
-                        let _id = ui.push_id(&format!("close_{}", i));
-                        if ui.small_button("×") {
-                            self.tab_to_close = Some(i);
-                        }
                     }
+                    if let Some(tab_action) = self.render_tab(ui, index) {
+                        action = Some(tab_action);
+                    }
+                }
+
+                // The mouse wheel scrolls sideways through tabs that don't fit
+                let wheel = ui.io().mouse_wheel;
+                if wheel != 0.0 && ui.is_window_hovered() {
+                    ui.set_scroll_x(ui.scroll_x() - wheel * 40.0);
                 }
+                self.overflowing = ui.scroll_max_x() > 0.0;
+            });
 
-                // Right side - new tab button (push to far right)
-                ui.same_line();
-                let window_width = ui.content_region_avail()[0];
-                let button_width = ui.calc_text_size("+")[0] + 20.0;
-                if window_width > button_width {
-                    ui.set_cursor_pos([ui.cursor_pos()[0] + window_width - button_width, ui.cursor_pos()[1]]);
+        // A tab dropped outside the bar tears off into its own window, or into an
+        // OS window when dropped outside the editor
+        if let Some(id) = self.dragging {
+            if !ui.is_mouse_down(MouseButton::Left) {
+                self.dragging = None;
+                let mouse = ui.io().mouse_pos;
+                let display_size = ui.io().display_size;
+                let inside = |min: [f32; 2], size: [f32; 2]| {
+                    mouse[0] >= min[0] && mouse[0] <= min[0] + size[0] && mouse[1] >= min[1] && mouse[1] <= min[1] + size[1]
+                };
+                let position = [mouse[0] - 60.0, mouse[1] - 10.0];
+                if !inside([0.0, 0.0], display_size) {
+                    action = Some(TabAction::Detach(id, position));
+                } else if !inside(bar_min, bar_size) {
+                    action = Some(TabAction::Float(id, position));
                 }
-                if ui.button("+") {
-                    self.show_new_tab_popup = true;
+            }
+        }
+
+        if self.overflowing {
+            ui.same_line();
+            if ui.button_with_size("▾", [28.0, 28.0]) {
+                ui.open_popup("tab_overflow_menu");
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip_text("All tabs");
+            }
+            ui.popup("tab_overflow_menu", || {
+                for tab in self.tabs.iter().filter(|t| t.floating.is_none()) {
+                    let _id = ui.push_id(&format!("overflow{}", tab.id.0));
+                    if ui.menu_item_config(&tab.label()).selected(self.selected == Some(tab.id)).build() {
+                        action = Some(TabAction::Activate(tab.id));
+                    }
                 }
             });
+        }
 
-        // Handle tab closing
-        if let Some(index) = self.tab_to_close.take() {
-            self.close_tab(index);
+        ui.same_line();
+        let _add_button_token = ui.push_style_color(StyleColor::Button, PulsarTheme::BLUE_PRIMARY);
+        let _add_hover_token = ui.push_style_color(StyleColor::ButtonHovered, PulsarTheme::BLUE_HOVER);
+        if ui.button_with_size("+ Add Tab", [100.0, 28.0]) {
+            self.show_new_tab_popup = true;
+            self.search_query.clear();
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text(keybindings::with_shortcut("Add new editor tab", editor_actions::NEW_TAB));
+        }
+
+        action
+    }
+
+    /// One tab button with its close button and right-click menu
+    fn render_tab(&mut self, ui: &Ui, index: usize) -> Option<TabAction> {
+        let mut action = None;
+        let tab = &self.tabs[index];
+        let (id, pinned, can_close) = (tab.id, tab.pinned, tab.content.can_close());
+        let label = if pinned { format!("📌{}", tab.label()) } else { tab.label() };
+        let is_selected = self.selected == Some(id);
+        let _id = ui.push_id(&format!("tab{}", id.0));
+
+        {
+            let _button_token = ui.push_style_color(
+                StyleColor::Button,
+                if is_selected { PulsarTheme::TAB_ACTIVE } else { PulsarTheme::TAB_INACTIVE },
+            );
+            let _hover_token = ui.push_style_color(
+                StyleColor::ButtonHovered,
+                if is_selected { PulsarTheme::BLUE_HOVER } else { PulsarTheme::TAB_HOVER },
+            );
+            if ui.button_with_size(&label, [150.0, 28.0]) {
+                action = Some(TabAction::Activate(id));
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip_text(format!("{} ({})", tab.content.get_title(), tab.type_name));
+            }
+        }
+        if ui.is_item_clicked_with_button(MouseButton::Right) {
+            ui.open_popup("tab_context_menu");
+        }
+
+        // Scroll a newly selected tab into view if it's outside the bar
+        if is_selected && self.scroll_to_selected {
+            let left = ui.window_pos()[0];
+            let right = left + ui.window_size()[0];
+            if ui.item_rect_min()[0] < left || ui.item_rect_max()[0] > right {
+                ui.set_scroll_here_x_with_ratio(0.5);
+            }
+            self.scroll_to_selected = false;
+        }
+
+        if let Some(tooltip) = ui.drag_drop_source_config(TAB_PAYLOAD).begin_payload(id) {
+            ui.text(&label);
+            tooltip.end();
+            self.dragging = Some(id);
+        }
+        if let Some(target) = ui.drag_drop_target() {
+            if let Some(Ok(payload)) = target.accept_payload::<TabId, _>(TAB_PAYLOAD, DragDropFlags::empty()) {
+                action = Some(TabAction::Move { id: payload.data, to: index });
+            }
+            target.pop();
         }
 
-        // Context menu
         ui.popup("tab_context_menu", || {
-            if let Some(tab_index) = self.context_menu_tab {
-                if ui.menu_item("Close Tab") && tab_index < self.tabs.len() {
-                    self.tab_to_close = Some(tab_index);
-                    self.context_menu_tab = None;
-                }
+            if ui.menu_item(if pinned { "📌 Unpin" } else { "📌 Pin" }) {
+                action = Some(TabAction::SetPinned(id, !pinned));
+            }
+            if ui.menu_item("🗗 Float") {
+                let mouse = ui.io().mouse_pos;
+                action = Some(TabAction::Float(id, [mouse[0] - 60.0, mouse[1] - 10.0]));
+            }
+            if ui.menu_item("🗔 Detach to Window") {
+                let mouse = ui.io().mouse_pos;
+                action = Some(TabAction::Detach(id, [mouse[0] - 60.0, mouse[1] - 10.0]));
+            }
+            ui.separator();
+            if ui.menu_item_config("✖ Close").enabled(can_close).build() {
+                action = Some(TabAction::Close(id));
+            }
+        });
 
-                if ui.menu_item("Close Other Tabs") {
-                    let mut indices_to_close = Vec::new();
-                    for (i, tab) in self.tabs.iter().enumerate() {
-                        if i != tab_index && tab.can_close() {
-                            indices_to_close.push(i);
-                        }
+        // Pinned tabs can only be closed from the menu
+        if can_close && !pinned {
+            ui.same_line();
+            let _close_token = ui.push_style_color(StyleColor::Button, [0.6, 0.2, 0.2, 0.8]);
+            if ui.small_button("×") {
+                action = Some(TabAction::Close(id));
+            }
+        }
+
+        action
+    }
+
+    /// Draw the selected docked tab, or a hint when no tabs are open
+    pub fn render_active(&mut self, ui: &Ui) {
+        // Clicking into the editor area hands focus back from a floating tab
+        if self.active != self.selected && ui.is_window_focused_with_flags(WindowFocusedFlags::ROOT_AND_CHILD_WINDOWS) {
+            self.active = self.selected;
+        }
+
+        let tab = self.selected.and_then(|id| self.tabs.iter_mut().find(|t| t.id == id));
+        match tab {
+            Some(tab) => {
+                // Keeps widget state of tabs of the same type apart
+                let _id = ui.push_id(&format!("tab_content{}", tab.id.0));
+                tab.content.render(ui);
+            }
+            None => {
+                ui.text_colored(PulsarTheme::TEXT_MUTED, "No tabs open");
+                ui.text_colored(PulsarTheme::TEXT_MUTED, "Click '+ Add Tab' to open an editor");
+            }
+        }
+    }
+
+    /// Draw each torn-off tab in its own window. Focusing one makes it the active tab.
+    pub fn render_floating_tabs(&mut self, ui: &Ui) -> Option<TabAction> {
+        let mut action = None;
+        for tab in self.tabs.iter_mut().filter(|t| !t.detached) {
+            let Some(position) = tab.floating else { continue };
+            let id = tab.id;
+            let mut open = true;
+            ui.window(format!("{}###floating_tab{}", tab.label(), id.0))
+                .position(position, Condition::Appearing)
+                .size([640.0, 480.0], Condition::FirstUseEver)
+                .flags(WindowFlags::NO_COLLAPSE)
+                .opened(&mut open)
+                .build(|| {
+                    if self.active != Some(id) && ui.is_window_focused_with_flags(WindowFocusedFlags::ROOT_AND_CHILD_WINDOWS) {
+                        action = Some(TabAction::Activate(id));
                     }
-                    // Close from highest index to lowest to maintain indices
-                    indices_to_close.sort_by(|a, b| b.cmp(a));
-                    for idx in indices_to_close {
-                        self.close_tab(idx);
+                    if ui.small_button("📥 Dock") {
+                        action = Some(TabAction::Dock(id));
                     }
-                    self.context_menu_tab = None;
-                }
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text("Put this tab back into the tab bar");
+                    }
+                    ui.separator();
+
+                    let _id = ui.push_id(&format!("tab_content{}", id.0));
+                    tab.content.render(ui);
+                });
+            // Closing the window closes the tab, asking about unsaved changes first
+            if !open {
+                action = Some(TabAction::Close(id));
             }
-        });
+        }
+        action
+    }
 
-        // New tab popup
-        if self.show_new_tab_popup {
-            ui.open_popup("new_tab_popup");
-            self.show_new_tab_popup = false;
+    /// Draw a detached tab's content into its OS window. Focusing the window makes
+    /// it the active tab.
+    pub fn render_detached(&mut self, ui: &Ui, id: TabId) {
+        if self.active != Some(id) && ui.is_window_focused_with_flags(WindowFocusedFlags::ROOT_AND_CHILD_WINDOWS) {
+            self.active = Some(id);
+        }
+        let Some(tab) = self.tabs.iter_mut().find(|t| t.id == id && t.detached) else { return };
+        let _id = ui.push_id(&format!("tab_content{}", id.0));
+        tab.content.render(ui);
+    }
+
+    /// Searchable list of registered tab types. Any type can be opened more than once.
+    pub fn render_new_tab_modal(&mut self, ui: &Ui) -> Option<TabAction> {
+        if !self.show_new_tab_popup {
+            return None;
         }
+        let mut action = None;
+
+        // Create a modal popup with proper visibility
+        let center = ui.io().display_size;
+
+        // Draw a semi-transparent overlay behind the modal
+        let draw_list = ui.get_background_draw_list();
+        draw_list
+            .add_rect([0.0, 0.0], center, [0.0, 0.0, 0.0, 0.7])
+            .filled(true)
+            .build();
+
+        // Modal window with higher z-order
+        ui.window("Add Editor Tab")
+            .position([center[0] * 0.5 - 300.0, center[1] * 0.5 - 250.0], Condition::Always)
+            .size([600.0, 500.0], Condition::Always)
+            .flags(WindowFlags::NO_RESIZE | WindowFlags::NO_MOVE | WindowFlags::NO_COLLAPSE | WindowFlags::ALWAYS_AUTO_RESIZE)
+            .focus_on_appearing(true)
+            .build(|| {
+                {
+                    let _title_color = ui.push_style_color(StyleColor::Text, PulsarTheme::BLUE_PRIMARY);
+                    ui.text("🎯 Choose Editor to Add");
+                }
+                ui.separator();
+                ui.spacing();
+
+                ui.text("Search:");
+                ui.input_text("##search", &mut self.search_query)
+                    .auto_select_all(true)
+                    .build();
+                ui.spacing();
+
+                let query = self.search_query.to_lowercase();
+                let filtered: Vec<&TabType> = self
+                    .tab_types
+                    .iter()
+                    .filter(|t| {
+                        query.is_empty()
+                            || t.name.to_lowercase().contains(&query)
+                            || t.description.to_lowercase().contains(&query)
+                    })
+                    .collect();
+
+                ui.child_window("TabList")
+                    .size([0.0, -80.0])
+                    .border(true)
+                    .build(|| {
+                        for tab_type in &filtered {
+                            let label = format!("{} {}", tab_type.icon, tab_type.name);
+                            let _button_color = ui.push_style_color(StyleColor::Button, PulsarTheme::DARKER_PANEL);
+                            let _hover_color = ui.push_style_color(StyleColor::ButtonHovered, PulsarTheme::BLUE_PRIMARY);
+                            if ui.button_with_size(&label, [550.0, 45.0]) {
+                                action = Some(TabAction::Open(tab_type.name.clone()));
+                            }
+
+                            // Show description below
+                            ui.text_colored(PulsarTheme::TEXT_MUTED, &format!("    {}", tab_type.description));
+                            ui.spacing();
+                        }
+
+                        if filtered.is_empty() {
+                            ui.text_colored(PulsarTheme::TEXT_MUTED, "❌ No matching editors");
+                        }
+                    });
 
-        ui.popup("new_tab_popup", || {
-                ui.text("Select Tab Type");
                 ui.separator();
+                ui.spacing();
 
-                for (name, (icon, factory)) in &self.tab_types {
-                    if ui.button(&format!("{} {}", icon, name)) {
-                        let new_tab = factory();
-                        self.tabs.push(new_tab);
-                        self.selected_tab = self.tabs.len() - 1;
-                        ui.close_current_popup();
+                {
+                    let _cancel_color = ui.push_style_color(StyleColor::Button, [0.6, 0.2, 0.2, 1.0]);
+                    if ui.button_with_size("Cancel", [80.0, 30.0]) {
+                        self.show_new_tab_popup = false;
                     }
                 }
+                if let Some(shortcut) = keybindings::shortcut_label(editor_actions::NEW_TAB) {
+                    ui.same_line();
+                    ui.text_colored(PulsarTheme::TEXT_MUTED, format!("💡 Tip: Use {} to quickly add tabs", shortcut));
+                }
 
-                ui.separator();
-                if ui.button("Cancel") {
-                    ui.close_current_popup();
+                if ui.is_key_pressed(Key::Escape) {
+                    self.show_new_tab_popup = false;
                 }
-        });
+            });
 
-        ui.separator();
+        if action.is_some() {
+            self.show_new_tab_popup = false;
+        }
+        action
+    }
+}
 
-        // Content area
-        if !self.tabs.is_empty() && self.selected_tab < self.tabs.len() {
-            let remaining_size = [window_size[0], window_size[1] - tab_bar_height - 20.0];
-            ui.child_window("TabContent")
-                .size(remaining_size)
-                .build(|| {
-                    self.tabs[self.selected_tab].render(ui);
-                });
-        } else {
-            // No tabs open
-            let text_size = ui.calc_text_size("No tabs open");
-            let center_pos = [
-                (window_size[0] - text_size[0]) * 0.5,
-                (window_size[1] - text_size[1]) * 0.5
-            ];
-            ui.set_cursor_pos(center_pos);
-            ui.text("No tabs open");
-            ui.text("Click the '+' button to create a new tab");
+impl Default for TabSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Open a tab. The tab is created on first apply and kept across undo/redo so
+/// its state survives.
+pub struct OpenTab {
+    type_name: String,
+    tab: Option<Tab>,
+    id: Option<TabId>,
+    previous_active: Option<TabId>,
+}
+
+impl OpenTab {
+    /// Open a new tab of a registered type
+    pub fn new(type_name: &str) -> Self {
+        Self { type_name: type_name.to_string(), tab: None, id: None, previous_active: None }
+    }
+
+    /// Open a tab for existing content
+    pub fn with_content(tabs: &mut TabSystem, type_name: &str, content: Box<dyn TabContent>) -> Self {
+        let id = TabId(tabs.next_tab_id);
+        tabs.next_tab_id += 1;
+        let tab = Tab::new(id, type_name, content);
+        Self { type_name: type_name.to_string(), tab: Some(tab), id: Some(id), previous_active: None }
+    }
+
+    /// The opened tab, once the command has been applied
+    pub fn tab_id(&self) -> Option<TabId> {
+        self.id
+    }
+}
+
+impl Command<TabSystem> for OpenTab {
+    fn label(&self) -> String {
+        format!("Open {}", self.type_name)
+    }
+
+    fn apply(&mut self, tabs: &mut TabSystem) {
+        self.previous_active = tabs.active;
+        match self.tab.take() {
+            Some(tab) => {
+                let id = tab.id;
+                tabs.insert_at(tabs.tabs.len(), tab);
+                tabs.set_active(id);
+            }
+            None => self.id = tabs.add_tab(&self.type_name),
         }
     }
+
+    fn revert(&mut self, tabs: &mut TabSystem) {
+        if let Some(index) = self.id.and_then(|id| tabs.index_of(id)) {
+            self.tab = Some(tabs.remove_at(index));
+        }
+        if let Some(id) = self.previous_active {
+            tabs.set_active(id);
+        }
+    }
+
+    fn memory_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.tab.as_ref().map_or(0, |tab| tab.content.memory_size())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Close a tab, keeping it so undo can bring it back with its state
+pub struct CloseTab {
+    id: TabId,
+    title: String,
+    index: usize,
+    tab: Option<Tab>,
+    previous_active: Option<TabId>,
+}
+
+impl CloseTab {
+    /// None if the tab doesn't exist or refuses to close
+    pub fn new(tabs: &TabSystem, id: TabId) -> Option<Self> {
+        let index = tabs.index_of(id)?;
+        let content = &tabs.tabs[index].content;
+        content.can_close().then(|| Self {
+            id,
+            title: content.get_title().to_string(),
+            index,
+            tab: None,
+            previous_active: tabs.active,
+        })
+    }
+
+    pub fn tab_id(&self) -> TabId {
+        self.id
+    }
+}
+
+impl Command<TabSystem> for CloseTab {
+    fn label(&self) -> String {
+        format!("Close {}", self.title)
+    }
+
+    fn apply(&mut self, tabs: &mut TabSystem) {
+        self.tab = tabs.close_tab(self.id);
+    }
+
+    fn revert(&mut self, tabs: &mut TabSystem) {
+        if let Some(tab) = self.tab.take() {
+            tabs.insert_at(self.index, tab);
+            if let Some(id) = self.previous_active {
+                tabs.set_active(id);
+            }
+        }
+    }
+
+    fn memory_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.title.len() + self.tab.as_ref().map_or(0, |tab| tab.content.memory_size())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
 
 // Example tab implementations
@@ -269,7 +907,10 @@ impl TabContent for Settings {
         ui.separator();
 
         ui.text("Graphics Settings");
-        ui.checkbox("VSync", &mut true);
+        let mut vsync = console::cvar(viewport::VSYNC).map_or(true, |value| value.as_bool());
+        if ui.checkbox("VSync", &mut vsync) {
+            let _ = console::set_cvar(viewport::VSYNC, if vsync { "1" } else { "0" });
+        }
         ui.checkbox("Fullscreen", &mut false);
 
         ui.separator();
@@ -295,4 +936,94 @@ impl TabContent for Settings {
     fn get_icon(&self) -> Option<&str> {
         Some("⚙️")
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_editor(title: &str) -> Box<dyn TabContent> {
+        Box::new(TextEditor::new(title.to_string()))
+    }
+
+    fn titles(tabs: &TabSystem) -> Vec<&str> {
+        tabs.tabs.iter().map(|t| t.content().get_title()).collect()
+    }
+
+    #[test]
+    fn open_tab_round_trip_keeps_the_same_tab() {
+        let mut tabs = TabSystem::new();
+        let mut history = History::default();
+        let first = tabs.insert_tab("Text", text_editor("a"));
+
+        let command = OpenTab::with_content(&mut tabs, "Text", text_editor("b"));
+        let opened = command.tab_id().unwrap();
+        history.execute(&mut tabs, Box::new(command));
+        assert_eq!(titles(&tabs), ["a", "b"]);
+        assert_eq!(tabs.active().map(Tab::id), Some(opened));
+
+        history.undo(&mut tabs);
+        assert_eq!(titles(&tabs), ["a"]);
+        assert_eq!(tabs.active().map(Tab::id), Some(first));
+
+        history.redo(&mut tabs);
+        assert_eq!(titles(&tabs), ["a", "b"]);
+        assert_eq!(tabs.active().map(Tab::id), Some(opened));
+    }
+
+    #[test]
+    fn open_tab_of_registered_type() {
+        let mut tabs = TabSystem::new();
+        tabs.register_tab_type("Text", "📝", "", || text_editor("new"));
+        let mut history = History::default();
+
+        history.execute(&mut tabs, Box::new(OpenTab::new("Text")));
+        let id = tabs.active().map(Tab::id).unwrap();
+        history.undo(&mut tabs);
+        assert!(tabs.get(id).is_none());
+        history.redo(&mut tabs);
+        assert!(tabs.get(id).is_some());
+    }
+
+    #[test]
+    fn close_tab_round_trip_restores_position_and_state() {
+        let mut tabs = TabSystem::new();
+        let mut history = History::default();
+        let a = tabs.insert_tab("Text", text_editor("a"));
+        let b = tabs.insert_tab("Text", Box::new(TextEditor { title: "b".to_string(), content: "text".to_string(), is_dirty: true }));
+        tabs.insert_tab("Text", text_editor("c"));
+        tabs.set_active(a);
+
+        let command = CloseTab::new(&tabs, b).unwrap();
+        history.execute(&mut tabs, Box::new(command));
+        assert_eq!(titles(&tabs), ["a", "c"]);
+        assert!(tabs.dirty_tabs().is_empty());
+
+        history.undo(&mut tabs);
+        assert_eq!(titles(&tabs), ["a", "b", "c"]);
+        assert_eq!(tabs.dirty_tabs(), [b]);
+        assert_eq!(tabs.active().map(Tab::id), Some(a));
+
+        history.redo(&mut tabs);
+        assert_eq!(titles(&tabs), ["a", "c"]);
+    }
+
+    #[test]
+    fn closed_tab_counts_toward_history_memory() {
+        let mut tabs = TabSystem::new();
+        let mut history = History::default();
+        let id = tabs.insert_tab("Text", text_editor("a"));
+
+        let command = CloseTab::new(&tabs, id).unwrap();
+        history.execute(&mut tabs, Box::new(command));
+        let closed = history.memory_used();
+        history.undo(&mut tabs);
+        assert!(history.memory_used() < closed);
+    }
+
+    #[test]
+    fn close_tab_of_missing_tab_is_none() {
+        let tabs = TabSystem::new();
+        assert!(CloseTab::new(&tabs, TabId(42)).is_none());
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use imgui::TextureId;
+use imgui_wgpu::{Renderer, Texture, TextureConfig};
+use lazy_static::lazy_static;
+
+/// Texture owned by UI code. UI code has no access to the renderer, so it queues
+/// RGBA pixels against a handle and main.rs uploads them before the imgui pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureHandle(u64);
+
+struct Upload {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>, // RGBA8, row-major
+}
+
+struct TextureRegistry {
+    next_handle: u64,
+    pending: HashMap<TextureHandle, Upload>, // only the latest image per handle is uploaded
+    released: Vec<TextureHandle>,
+    textures: HashMap<TextureHandle, (TextureId, [u32; 2])>,
+}
+
+lazy_static! {
+    static ref REGISTRY: Mutex<TextureRegistry> = Mutex::new(TextureRegistry {
+        next_handle: 1,
+        pending: HashMap::new(),
+        released: Vec::new(),
+        textures: HashMap::new(),
+    });
+}
+
+pub fn create_handle() -> TextureHandle {
+    let mut registry = REGISTRY.lock().unwrap();
+    let handle = TextureHandle(registry.next_handle);
+    registry.next_handle += 1;
+    handle
+}
+
+/// Queue new contents for `handle`. The texture is created on first upload and
+/// reallocated only when the size changes.
+pub fn upload(handle: TextureHandle, width: u32, height: u32, pixels: Vec<u8>) {
+    if width == 0 || height == 0 || pixels.len() != (width * height * 4) as usize {
+        return;
+    }
+    REGISTRY.lock().unwrap().pending.insert(handle, Upload { width, height, pixels });
+}
+
+/// Free the GPU texture behind `handle` on the next flush
+pub fn release(handle: TextureHandle) {
+    let mut registry = REGISTRY.lock().unwrap();
+    registry.pending.remove(&handle);
+    registry.released.push(handle);
+}
+
+/// Associate a texture the caller created on the renderer (e.g. a render target) with `handle`
+pub fn register(handle: TextureHandle, id: TextureId, size: [u32; 2]) {
+    REGISTRY.lock().unwrap().textures.insert(handle, (id, size));
+}
+
+/// imgui texture for `handle`, available from the frame after its first upload
+pub fn texture_id(handle: TextureHandle) -> Option<TextureId> {
+    REGISTRY.lock().unwrap().textures.get(&handle).map(|(id, _)| *id)
+}
+
+/// Apply queued uploads and releases. Call once per frame before `Renderer::render`.
+pub fn flush(renderer: &mut Renderer, device: &wgpu::Device, queue: &wgpu::Queue) {
+    let mut registry = REGISTRY.lock().unwrap();
+
+    for handle in std::mem::take(&mut registry.released) {
+        if let Some((id, _)) = registry.textures.remove(&handle) {
+            renderer.textures.remove(id);
+        }
+    }
+
+    for (handle, upload) in std::mem::take(&mut registry.pending) {
+        let size = [upload.width, upload.height];
+        match registry.textures.get(&handle).copied() {
+            // Same size: update in place
+            Some((id, current)) if current == size => {
+                if let Some(texture) = renderer.textures.get(id) {
+                    texture.write(queue, &upload.pixels, upload.width, upload.height);
+                }
+            }
+            existing => {
+                let config = TextureConfig {
+                    size: wgpu::Extent3d {
+                        width: upload.width,
+                        height: upload.height,
+                        depth_or_array_layers: 1,
+                    },
+                    label: Some("ui texture"),
+                    ..Default::default()
+                };
+                let texture = Texture::new(device, renderer, config);
+                texture.write(queue, &upload.pixels, upload.width, upload.height);
+
+                let id = match existing {
+                    Some((id, _)) => {
+                        renderer.textures.replace(id, texture);
+                        id
+                    }
+                    None => renderer.textures.insert(texture),
+                };
+                registry.textures.insert(handle, (id, size));
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{SystemTime, UNIX_EPOCH};
+use image::RgbaImage;
+use imgui::TextureId;
+use crate::session::config_dir;
+use crate::textures::{self, TextureHandle};
+
+/// Longest side of a thumbnail in pixels
+pub const THUMBNAIL_SIZE: u32 = 128;
+
+const THUMBNAILS_DIR: &str = "thumbnails";
+
+enum ThumbnailState {
+    Loading,
+    Ready(TextureHandle, [u32; 2]),
+    Failed, // not an image the `image` crate can decode
+}
+
+/// A thumbnail, tagged with the modified time of the file it was made from
+struct CachedThumbnail {
+    modified: Option<SystemTime>,
+    state: ThumbnailState,
+}
+
+struct Decoded {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    result: Result<RgbaImage, String>,
+}
+
+/// Thumbnails of image assets. Images are decoded and downscaled on rayon's
+/// threads, saved to a disk cache keyed by content hash and modified time, and
+/// uploaded through `textures` once ready.
+pub struct ThumbnailCache {
+    thumbnails: HashMap<PathBuf, CachedThumbnail>,
+    sender: Sender<Decoded>,
+    receiver: Receiver<Decoded>,
+}
+
+impl ThumbnailCache {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self { thumbnails: HashMap::new(), sender, receiver }
+    }
+
+    /// Whether `path` has no thumbnail yet, or one made before the file last changed
+    pub fn needs(&self, path: &Path, modified: Option<SystemTime>) -> bool {
+        !matches!(self.thumbnails.get(path), Some(thumbnail) if thumbnail.modified == modified)
+    }
+
+    /// Start making a thumbnail of the image at `path` in the background
+    pub fn request(&mut self, path: &Path, modified: Option<SystemTime>) {
+        if let Some(CachedThumbnail { state: ThumbnailState::Ready(handle, _), .. }) = self.thumbnails.remove(path) {
+            textures::release(handle);
+        }
+        self.thumbnails.insert(path.to_path_buf(), CachedThumbnail { modified, state: ThumbnailState::Loading });
+
+        let sender = self.sender.clone();
+        let path = path.to_path_buf();
+        rayon::spawn(move || {
+            let result = load_thumbnail(&path, modified);
+            let _ = sender.send(Decoded { path, modified, result });
+        });
+    }
+
+    /// Upload the thumbnails finished since last frame
+    pub fn poll(&mut self) {
+        while let Ok(decoded) = self.receiver.try_recv() {
+            // Skip results for files that were removed or changed again meanwhile
+            let Some(thumbnail) = self.thumbnails.get_mut(&decoded.path) else { continue };
+            if thumbnail.modified != decoded.modified || !matches!(thumbnail.state, ThumbnailState::Loading) {
+                continue;
+            }
+            thumbnail.state = match decoded.result {
+                Ok(image) => {
+                    let handle = textures::create_handle();
+                    let size = [image.width(), image.height()];
+                    textures::upload(handle, size[0], size[1], image.into_raw());
+                    ThumbnailState::Ready(handle, size)
+                }
+                Err(e) => {
+                    log::debug!("No thumbnail for {}: {}", decoded.path.display(), e);
+                    ThumbnailState::Failed
+                }
+            };
+        }
+    }
+
+    /// The thumbnail's texture and size in pixels, once uploaded
+    pub fn get(&self, path: &Path) -> Option<(TextureId, [f32; 2])> {
+        match self.thumbnails.get(path)?.state {
+            ThumbnailState::Ready(handle, size) => Some((textures::texture_id(handle)?, [size[0] as f32, size[1] as f32])),
+            _ => None,
+        }
+    }
+
+    /// Drop the thumbnails of files `keep` rejects, e.g. ones deleted since the last scan
+    pub fn retain(&mut self, keep: impl Fn(&Path) -> bool) {
+        self.thumbnails.retain(|path, thumbnail| {
+            let kept = keep(path);
+            if let (false, ThumbnailState::Ready(handle, _)) = (kept, &thumbnail.state) {
+                textures::release(*handle);
+            }
+            kept
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.retain(|_| false);
+    }
+}
+
+impl Default for ThumbnailCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ThumbnailCache {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// The cached thumbnail for the file's contents, or a new one made and cached
+fn load_thumbnail(path: &Path, modified: Option<SystemTime>) -> Result<RgbaImage, String> {
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    let seconds = modified.and_then(|time| time.duration_since(UNIX_EPOCH).ok()).map_or(0, |age| age.as_secs());
+    let cache_dir = config_dir().join(THUMBNAILS_DIR);
+    let cache_path = cache_dir.join(format!("{:016x}-{}.png", content_hash(&bytes), seconds));
+
+    if let Ok(cached) = image::open(&cache_path) {
+        return Ok(cached.to_rgba8());
+    }
+    let image = image::load_from_memory(&bytes)
+        .map_err(|e| e.to_string())?
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .to_rgba8();
+    let saved = fs::create_dir_all(&cache_dir)
+        .map_err(|e| e.to_string())
+        .and_then(|_| image.save(&cache_path).map_err(|e| e.to_string()));
+    if let Err(e) = saved {
+        log::warn!("Failed to cache thumbnail {}: {}", cache_path.display(), e);
+    }
+    Ok(image)
+}
+
+/// 64-bit FNV-1a, which unlike std's hasher is the same across Rust versions, so
+/// cache file names stay valid
+fn content_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
@@ -0,0 +1,209 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+/// Index into `TILE_PALETTE`; 0 is an empty cell
+pub type Tile = u8;
+
+pub const EMPTY_TILE: Tile = 0;
+
+/// Largest width or height of a tile map, which keeps cell indices within u32
+pub const MAX_TILE_MAP_SIZE: u32 = 4096;
+
+/// (name, colour) of every paintable tile, indexed by `Tile - 1`
+pub const TILE_PALETTE: &[(&str, [f32; 4])] = &[
+    ("Grass", [0.30, 0.65, 0.25, 1.0]),
+    ("Dirt", [0.50, 0.35, 0.20, 1.0]),
+    ("Stone", [0.50, 0.50, 0.55, 1.0]),
+    ("Sand", [0.85, 0.78, 0.50, 1.0]),
+    ("Water", [0.20, 0.40, 0.85, 1.0]),
+    ("Wall", [0.25, 0.22, 0.30, 1.0]),
+];
+
+pub fn tile_color(tile: Tile) -> Option<[f32; 4]> {
+    TILE_PALETTE.get((tile as usize).checked_sub(1)?).map(|(_, color)| *color)
+}
+
+/// One cell edit, kept so paint operations can be undone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileChange {
+    pub x: u32,
+    pub y: u32,
+    pub old: Tile,
+    pub new: Tile,
+}
+
+/// Single 2D layer of tiles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileMap {
+    width: u32,
+    height: u32,
+    cells: Vec<Tile>, // row-major
+}
+
+impl TileMap {
+    /// An empty map, or an error if the dimensions are over `MAX_TILE_MAP_SIZE`
+    pub fn new(width: u32, height: u32) -> Result<Self, String> {
+        let count = Self::cell_count(width, height)?;
+        Ok(Self {
+            width,
+            height,
+            cells: vec![EMPTY_TILE; count],
+        })
+    }
+
+    /// Number of cells in a map of the given dimensions
+    fn cell_count(width: u32, height: u32) -> Result<usize, String> {
+        let too_big = || format!("{}x{} tiles is over the {}x{} limit", width, height, MAX_TILE_MAP_SIZE, MAX_TILE_MAP_SIZE);
+        if width > MAX_TILE_MAP_SIZE || height > MAX_TILE_MAP_SIZE {
+            return Err(too_big());
+        }
+        (width as usize).checked_mul(height as usize).ok_or_else(too_big)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// False if the dimensions are too big or the cell data doesn't match them
+    /// (e.g. a hand-edited file)
+    pub fn is_valid(&self) -> bool {
+        Self::cell_count(self.width, self.height).is_ok_and(|count| count == self.cells.len())
+    }
+
+    /// Approximate memory held by the cells
+    pub fn memory_size(&self) -> usize {
+        self.cells.len() * std::mem::size_of::<Tile>()
+    }
+
+    pub fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (x as u32) < self.width && (y as u32) < self.height
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Option<Tile> {
+        if x < self.width && y < self.height {
+            self.cells.get((y * self.width + x) as usize).copied()
+        } else {
+            None
+        }
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, tile: Tile) {
+        if x < self.width && y < self.height {
+            self.cells[(y * self.width + x) as usize] = tile;
+        }
+    }
+
+    /// Number of non-empty cells
+    pub fn painted_count(&self) -> usize {
+        self.cells.iter().filter(|&&t| t != EMPTY_TILE).count()
+    }
+
+    /// Set every cell in `cells` to `tile`, returning the cells that actually changed
+    pub fn paint(&mut self, cells: impl IntoIterator<Item = (u32, u32)>, tile: Tile) -> Vec<TileChange> {
+        let mut changes = Vec::new();
+        for (x, y) in cells {
+            match self.get(x, y) {
+                Some(old) if old != tile => {
+                    self.set(x, y, tile);
+                    changes.push(TileChange { x, y, old, new: tile });
+                }
+                _ => {}
+            }
+        }
+        changes
+    }
+
+    /// Cells within `radius` of the centre cell (a single cell for radius 0)
+    pub fn circle_cells(&self, cx: i32, cy: i32, radius: f32) -> Vec<(u32, u32)> {
+        let r = radius.max(0.0);
+        let extent = r.ceil() as i32;
+        let mut cells = Vec::new();
+        for y in (cy - extent)..=(cy + extent) {
+            for x in (cx - extent)..=(cx + extent) {
+                let (dx, dy) = ((x - cx) as f32, (y - cy) as f32);
+                if dx * dx + dy * dy <= r * r + 0.25 && self.in_bounds(x, y) {
+                    cells.push((x as u32, y as u32));
+                }
+            }
+        }
+        cells
+    }
+
+    /// Cells of the axis-aligned rectangle spanned by two corners, clipped to the map
+    pub fn rect_cells(&self, a: (i32, i32), b: (i32, i32)) -> Vec<(u32, u32)> {
+        let (x0, x1) = (a.0.min(b.0).max(0), a.0.max(b.0).min(self.width as i32 - 1));
+        let (y0, y1) = (a.1.min(b.1).max(0), a.1.max(b.1).min(self.height as i32 - 1));
+        let mut cells = Vec::new();
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                cells.push((x as u32, y as u32));
+            }
+        }
+        cells
+    }
+
+    /// 4-connected region of cells sharing the tile at (x, y)
+    pub fn flood_cells(&self, x: u32, y: u32) -> Vec<(u32, u32)> {
+        let Some(target) = self.get(x, y) else { return Vec::new() };
+        let mut visited = vec![false; self.cells.len()];
+        let mut queue = VecDeque::from([(x, y)]);
+        let mut cells = Vec::new();
+        visited[(y * self.width + x) as usize] = true;
+
+        while let Some((cx, cy)) = queue.pop_front() {
+            cells.push((cx, cy));
+            let neighbours = [
+                (cx as i32 - 1, cy as i32),
+                (cx as i32 + 1, cy as i32),
+                (cx as i32, cy as i32 - 1),
+                (cx as i32, cy as i32 + 1),
+            ];
+            for (nx, ny) in neighbours {
+                if !self.in_bounds(nx, ny) {
+                    continue;
+                }
+                let index = (ny as u32 * self.width + nx as u32) as usize;
+                if !visited[index] && self.cells[index] == target {
+                    visited[index] = true;
+                    queue.push_back((nx as u32, ny as u32));
+                }
+            }
+        }
+        cells
+    }
+}
+
+impl Default for TileMap {
+    fn default() -> Self {
+        Self::new(32, 24).expect("default tile map is within the size limit")
+    }
+}
+
+/// Cells on the line between two cells (Bresenham), so fast strokes leave no gaps
+pub fn line_cells(from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+    let (mut x, mut y) = from;
+    let dx = (to.0 - x).abs();
+    let dy = -(to.1 - y).abs();
+    let sx = if x < to.0 { 1 } else { -1 };
+    let sy = if y < to.1 { 1 } else { -1 };
+    let mut err = dx + dy;
+    let mut cells = vec![(x, y)];
+
+    while (x, y) != to {
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+        cells.push((x, y));
+    }
+    cells
+}
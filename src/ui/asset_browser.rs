@@ -0,0 +1,771 @@
+use imgui::*;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use crate::assets::{self, AssetEntry, AssetKind, AssetScan, AssetTree};
+use crate::keybindings::{self, KeyScope};
+use crate::thumbnails::ThumbnailCache;
+use crate::ui::editor_actions;
+use crate::ui::theme::PulsarTheme;
+
+const ASSET_PAYLOAD: &str = "ASSET_PATH";
+const TILE_SIZE: f32 = 72.0;
+const TILE_PADDING: f32 = 6.0;
+const FOLDER_TREE_WIDTH: f32 = 180.0;
+const STATUS_HEIGHT: f32 = 24.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetView {
+    Grid,
+    List,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Name,
+    Type,
+    Size,
+    Modified,
+}
+
+impl SortKey {
+    const ALL: [SortKey; 4] = [Self::Name, Self::Type, Self::Size, Self::Modified];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Type => "Type",
+            Self::Size => "Size",
+            Self::Modified => "Modified",
+        }
+    }
+}
+
+/// Edits requested while the browser is drawn, applied afterwards
+#[derive(Debug, Clone)]
+enum AssetAction {
+    OpenFolder(PathBuf),
+    Select(PathBuf),
+    ContextMenu(Option<PathBuf>), // None for the empty space around the assets
+    BeginDrag(PathBuf),
+    Rename(PathBuf),
+    Delete(PathBuf),
+    Move { path: PathBuf, dir: PathBuf },
+    NewFolder(PathBuf),
+    ShowInFileManager(PathBuf),
+    SortBy(SortKey), // flips the order if already sorted by this
+    LoadThumbnail(PathBuf, Option<SystemTime>),
+    Refresh,
+}
+
+/// A file operation waiting for the user to confirm it
+enum PendingEdit {
+    Rename { path: PathBuf, name: String },
+    Delete(PathBuf),
+    Move { path: PathBuf, dir: PathBuf },
+}
+
+/// The Asset Browser panel: the open project's asset folder as a folder tree and
+/// a grid or list of the current folder's contents
+pub struct AssetBrowser {
+    root: Option<PathBuf>, // asset folder of the open project
+    tree: AssetTree,
+    scan: Option<AssetScan>,
+    thumbnails: ThumbnailCache,
+    current: PathBuf, // folder being shown
+    selected: Option<PathBuf>,
+    search: String,
+    sort: SortKey,
+    ascending: bool,
+    view: AssetView,
+    context_target: Option<PathBuf>,
+    dragging: Option<PathBuf>,
+    pending: Option<PendingEdit>,
+    error: Option<String>, // why the last scan or file operation failed
+}
+
+impl AssetBrowser {
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            tree: AssetTree::default(),
+            scan: None,
+            thumbnails: ThumbnailCache::new(),
+            current: PathBuf::new(),
+            selected: None,
+            search: String::new(),
+            sort: SortKey::Name,
+            ascending: true,
+            view: AssetView::Grid,
+            context_target: None,
+            dragging: None,
+            pending: None,
+            error: None,
+        }
+    }
+
+    /// Browse the assets of the project saved at `project_file`, rescanning if it changed
+    pub fn set_project(&mut self, project_file: Option<&Path>) {
+        let root = project_file.map(assets::asset_dir);
+        if root == self.root {
+            return;
+        }
+        self.root = root;
+        self.tree = AssetTree::default();
+        self.thumbnails.clear();
+        self.current = self.root.clone().unwrap_or_default();
+        self.selected = None;
+        self.pending = None;
+        self.error = None;
+        self.rescan();
+    }
+
+    /// Scan the asset folder again in the background
+    pub fn rescan(&mut self) {
+        self.scan = self.root.as_deref().filter(|root| root.is_dir()).map(AssetScan::start);
+    }
+
+    pub fn render(&mut self, ui: &Ui) {
+        self.poll_scan();
+        self.thumbnails.poll();
+
+        let Some(root) = self.root.clone() else {
+            ui.text_colored(PulsarTheme::TEXT_MUTED, "📁 Save the project to browse its assets");
+            return;
+        };
+        if !root.is_dir() {
+            ui.text_colored(PulsarTheme::TEXT_MUTED, format!("No assets folder at {}", root.display()));
+            if ui.button("📁 Create Assets Folder") {
+                match std::fs::create_dir_all(&root) {
+                    Ok(()) => self.rescan(),
+                    Err(e) => log::error!("Failed to create {}: {}", root.display(), e),
+                }
+            }
+            return;
+        }
+
+        let mut actions = Vec::new();
+        self.render_toolbar(ui, &root, &mut actions);
+        ui.separator();
+
+        ui.child_window("AssetFolders")
+            .size([FOLDER_TREE_WIDTH, -STATUS_HEIGHT])
+            .border(true)
+            .build(|| {
+                let name = root.file_name().map_or_else(|| root.display().to_string(), |n| n.to_string_lossy().into_owned());
+                self.render_folder(ui, &root, &name, &root, &mut actions);
+            });
+        ui.same_line();
+        ui.child_window("AssetContents")
+            .size([0.0, -STATUS_HEIGHT])
+            .build(|| {
+                let entries = self.visible_entries();
+                if entries.is_empty() {
+                    let text = if self.search.is_empty() { "This folder is empty" } else { "No assets match the search" };
+                    ui.text_colored(PulsarTheme::TEXT_MUTED, text);
+                }
+                match self.view {
+                    AssetView::Grid => self.render_grid(ui, &entries, &mut actions),
+                    AssetView::List => self.render_list(ui, &root, &entries, &mut actions),
+                }
+
+                // Empty space below the assets: clicking clears the selection, dropping
+                // an asset here moves it into the current folder
+                let avail = ui.content_region_avail();
+                ui.invisible_button("##asset_background", [avail[0].max(1.0), avail[1].max(24.0)]);
+                if ui.is_item_clicked() {
+                    self.selected = None;
+                }
+                if ui.is_item_clicked_with_button(MouseButton::Right) {
+                    actions.push(AssetAction::ContextMenu(None));
+                }
+                self.folder_drop_target(ui, &self.current, &mut actions);
+
+                // Rename, delete and parent folder shortcuts apply while the contents have focus
+                if ui.is_window_focused() && self.pending.is_none() {
+                    keybindings::claim_scope(KeyScope::AssetBrowser);
+                }
+            });
+        self.render_status(ui);
+
+        if let Some(target) = actions.iter().find_map(|a| match a {
+            AssetAction::ContextMenu(target) => Some(target.clone()),
+            _ => None,
+        }) {
+            self.context_target = target;
+            ui.open_popup("asset_context_menu");
+        }
+        ui.popup("asset_context_menu", || self.render_context_menu(ui, &root, &mut actions));
+        self.render_pending_edit(ui);
+
+        self.apply_actions(actions);
+    }
+
+    fn render_toolbar(&mut self, ui: &Ui, root: &Path, actions: &mut Vec<AssetAction>) {
+        // Breadcrumbs from the asset folder down to the current one
+        let mut crumb = root.to_path_buf();
+        let root_name = root.file_name().map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+        if ui.small_button(format!("📁 {}##crumb", root_name)) {
+            actions.push(AssetAction::OpenFolder(crumb.clone()));
+        }
+        self.folder_drop_target(ui, &crumb, actions);
+        if let Ok(relative) = self.current.strip_prefix(root) {
+            for (i, part) in relative.iter().enumerate() {
+                crumb.push(part);
+                ui.same_line();
+                ui.text_colored(PulsarTheme::TEXT_MUTED, "›");
+                ui.same_line();
+                if ui.small_button(format!("{}##crumb{}", part.to_string_lossy(), i)) {
+                    actions.push(AssetAction::OpenFolder(crumb.clone()));
+                }
+                self.folder_drop_target(ui, &crumb, actions);
+            }
+        }
+
+        ui.set_next_item_width(200.0);
+        ui.input_text("##asset_search", &mut self.search)
+            .hint("🔍 Search this folder")
+            .build();
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Finds assets in this folder and every folder inside it");
+        }
+
+        ui.same_line();
+        ui.set_next_item_width(130.0);
+        if let Some(_combo) = ui.begin_combo("##asset_sort", format!("Sort: {}", self.sort.display_name())) {
+            for key in SortKey::ALL {
+                if ui.selectable_config(key.display_name()).selected(self.sort == key).build() {
+                    self.sort = key;
+                }
+            }
+        }
+        ui.same_line();
+        if ui.button(if self.ascending { "⬆" } else { "⬇" }) {
+            self.ascending = !self.ascending;
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text(if self.ascending { "Ascending" } else { "Descending" });
+        }
+
+        ui.same_line();
+        for (view, label, tooltip) in [(AssetView::Grid, "▦", "Grid view"), (AssetView::List, "☰", "List view")] {
+            let _color = (self.view == view).then(|| ui.push_style_color(StyleColor::Button, PulsarTheme::BLUE_PRIMARY));
+            if ui.button(label) {
+                self.view = view;
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip_text(tooltip);
+            }
+            ui.same_line();
+        }
+        if ui.button("🔄") {
+            actions.push(AssetAction::Refresh);
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Scan the assets folder again");
+        }
+    }
+
+    /// A folder in the tree on the left, and the folders inside it
+    fn render_folder(&self, ui: &Ui, path: &Path, name: &str, root: &Path, actions: &mut Vec<AssetAction>) {
+        let subfolders = self.tree.subfolders(path);
+        let mut flags = TreeNodeFlags::OPEN_ON_ARROW | TreeNodeFlags::SPAN_AVAIL_WIDTH;
+        if subfolders.is_empty() {
+            flags |= TreeNodeFlags::LEAF;
+        }
+        if path == self.current {
+            flags |= TreeNodeFlags::SELECTED;
+        }
+        if path == root {
+            flags |= TreeNodeFlags::DEFAULT_OPEN;
+        }
+        let token = ui.tree_node_config(format!("📁 {}##{}", name, path.display())).flags(flags).push();
+        if ui.is_item_clicked() && !ui.is_item_toggled_open() {
+            actions.push(AssetAction::OpenFolder(path.to_path_buf()));
+        }
+        if ui.is_item_clicked_with_button(MouseButton::Right) {
+            actions.push(AssetAction::ContextMenu(Some(path.to_path_buf())));
+        }
+        if path != root {
+            self.drag_source(ui, path, name, actions);
+        }
+        self.folder_drop_target(ui, path, actions);
+
+        if let Some(_token) = token {
+            for folder in subfolders {
+                self.render_folder(ui, &folder.path, &folder.name, root, actions);
+            }
+        }
+    }
+
+    fn render_grid(&self, ui: &Ui, entries: &[&AssetEntry], actions: &mut Vec<AssetAction>) {
+        let spacing = ui.clone_style().item_spacing[0];
+        let columns = ((ui.content_region_avail()[0] + spacing) / (TILE_SIZE + spacing)).floor().max(1.0) as usize;
+        for (i, entry) in entries.iter().enumerate() {
+            if i % columns != 0 {
+                ui.same_line();
+            }
+            let group = ui.begin_group();
+            let pos = ui.cursor_screen_pos();
+            let selected = self.selected.as_deref() == Some(entry.path.as_path());
+            ui.selectable_config(format!("##tile{}", entry.path.display()))
+                .selected(selected)
+                .size([TILE_SIZE, TILE_SIZE])
+                .build();
+            self.item_interactions(ui, entry, actions);
+
+            // Images get thumbnails once they scroll into view
+            let is_visible = ui.is_item_visible();
+            if is_visible && entry.kind == AssetKind::Image && self.thumbnails.needs(&entry.path, entry.modified) {
+                actions.push(AssetAction::LoadThumbnail(entry.path.clone(), entry.modified));
+            }
+            if is_visible {
+                self.draw_tile(ui, entry, pos);
+            }
+            ui.text(fit_text(ui, &entry.name, TILE_SIZE));
+            group.end();
+        }
+    }
+
+    /// The thumbnail, or the icon of the asset's type while there is none
+    fn draw_tile(&self, ui: &Ui, entry: &AssetEntry, pos: [f32; 2]) {
+        let draw_list = ui.get_window_draw_list();
+        let min = [pos[0] + TILE_PADDING, pos[1] + TILE_PADDING];
+        let max = [pos[0] + TILE_SIZE - TILE_PADDING, pos[1] + TILE_SIZE - TILE_PADDING];
+        draw_list.add_rect(min, max, PulsarTheme::DARK_PANEL).filled(true).rounding(4.0).build();
+
+        if let Some((texture, size)) = self.thumbnails.get(&entry.path) {
+            // Fit inside the tile, keeping the aspect ratio
+            let room = max[0] - min[0];
+            let scale = (room / size[0]).min(room / size[1]);
+            let (width, height) = (size[0] * scale, size[1] * scale);
+            let corner = [min[0] + (room - width) * 0.5, min[1] + (room - height) * 0.5];
+            draw_list.add_image(texture, corner, [corner[0] + width, corner[1] + height]).build();
+            return;
+        }
+
+        let color = kind_color(entry.kind);
+        draw_list.add_rect(min, max, color).rounding(4.0).build();
+        let icon_size = ui.calc_text_size(entry.kind.icon());
+        draw_list.add_text(
+            [pos[0] + (TILE_SIZE - icon_size[0]) * 0.5, pos[1] + (TILE_SIZE - icon_size[1]) * 0.5 - 6.0],
+            color,
+            entry.kind.icon(),
+        );
+        // Extension under the icon, so e.g. .fbx and .obj models can be told apart
+        if let Some(extension) = entry.path.extension().filter(|_| !entry.is_folder()) {
+            let label = fit_text(ui, &extension.to_string_lossy().to_uppercase(), max[0] - min[0] - 4.0);
+            let label_size = ui.calc_text_size(&label);
+            draw_list.add_text(
+                [pos[0] + (TILE_SIZE - label_size[0]) * 0.5, max[1] - label_size[1] - 4.0],
+                PulsarTheme::TEXT_MUTED,
+                &label,
+            );
+        }
+    }
+
+    fn render_list(&self, ui: &Ui, root: &Path, entries: &[&AssetEntry], actions: &mut Vec<AssetAction>) {
+        ui.columns(4, "AssetListColumns", false);
+        let width = ui.window_content_region_max()[0];
+        ui.set_column_width(0, (width - 300.0).max(150.0));
+        ui.set_column_width(1, 90.0);
+        ui.set_column_width(2, 80.0);
+        // Clicking a heading sorts by it, or flips the order if it already does
+        for key in SortKey::ALL {
+            let arrow = match (self.sort == key, self.ascending) {
+                (true, true) => " ⬆",
+                (true, false) => " ⬇",
+                (false, _) => "",
+            };
+            if ui.selectable(format!("{}{}##heading", key.display_name(), arrow)) {
+                actions.push(AssetAction::SortBy(key));
+            }
+            ui.next_column();
+        }
+        ui.separator();
+
+        for entry in entries {
+            let selected = self.selected.as_deref() == Some(entry.path.as_path());
+            ui.selectable_config(format!("{} {}##row{}", entry.kind.icon(), entry.name, entry.path.display()))
+                .selected(selected)
+                .flags(SelectableFlags::SPAN_ALL_COLUMNS)
+                .build();
+            self.item_interactions(ui, entry, actions);
+            if ui.is_item_hovered() && !self.search.is_empty() {
+                let relative = entry.path.strip_prefix(root).unwrap_or(&entry.path);
+                ui.tooltip_text(relative.display().to_string());
+            }
+            ui.next_column();
+            ui.text_colored(PulsarTheme::TEXT_SECONDARY, entry.kind.display_name());
+            ui.next_column();
+            if !entry.is_folder() {
+                ui.text_colored(PulsarTheme::TEXT_SECONDARY, format_size(entry.size));
+            }
+            ui.next_column();
+            if let Some(modified) = entry.modified {
+                ui.text_colored(PulsarTheme::TEXT_SECONDARY, format_age(modified));
+            }
+            ui.next_column();
+        }
+        ui.columns(1, "", false);
+    }
+
+    /// Clicks, drags and drops on an asset drawn in the grid or list
+    fn item_interactions(&self, ui: &Ui, entry: &AssetEntry, actions: &mut Vec<AssetAction>) {
+        if ui.is_item_clicked() {
+            actions.push(AssetAction::Select(entry.path.clone()));
+        }
+        if entry.is_folder() && ui.is_item_hovered() && ui.is_mouse_double_clicked(MouseButton::Left) {
+            actions.push(AssetAction::OpenFolder(entry.path.clone()));
+        }
+        if ui.is_item_clicked_with_button(MouseButton::Right) {
+            actions.push(AssetAction::Select(entry.path.clone()));
+            actions.push(AssetAction::ContextMenu(Some(entry.path.clone())));
+        }
+        self.drag_source(ui, &entry.path, &entry.name, actions);
+        if entry.is_folder() {
+            self.folder_drop_target(ui, &entry.path, actions);
+        }
+    }
+
+    /// Drag an asset onto a folder to move it there
+    fn drag_source(&self, ui: &Ui, path: &Path, name: &str, actions: &mut Vec<AssetAction>) {
+        if let Some(tooltip) = ui.drag_drop_source_config(ASSET_PAYLOAD).begin() {
+            let kind = if path.is_dir() { AssetKind::Folder } else { AssetKind::from_path(path) };
+            ui.text(format!("{} {}", kind.icon(), name));
+            tooltip.end();
+            if self.dragging.as_deref() != Some(path) {
+                actions.push(AssetAction::BeginDrag(path.to_path_buf()));
+            }
+        }
+    }
+
+    fn folder_drop_target(&self, ui: &Ui, dir: &Path, actions: &mut Vec<AssetAction>) {
+        let Some(target) = ui.drag_drop_target() else { return };
+        if target.accept_payload_empty(ASSET_PAYLOAD, DragDropFlags::empty()).is_some() {
+            if let Some(path) = &self.dragging {
+                if path.parent() != Some(dir) && !dir.starts_with(path) {
+                    actions.push(AssetAction::Move { path: path.clone(), dir: dir.to_path_buf() });
+                }
+            }
+        }
+        target.pop();
+    }
+
+    fn render_context_menu(&self, ui: &Ui, root: &Path, actions: &mut Vec<AssetAction>) {
+        let Some(path) = &self.context_target else {
+            if ui.menu_item("📁 New Folder") {
+                actions.push(AssetAction::NewFolder(self.current.clone()));
+            }
+            if ui.menu_item("🔍 Show in File Manager") {
+                actions.push(AssetAction::ShowInFileManager(self.current.clone()));
+            }
+            ui.separator();
+            if ui.menu_item("🔄 Refresh") {
+                actions.push(AssetAction::Refresh);
+            }
+            return;
+        };
+
+        let is_folder = path.is_dir();
+        if is_folder {
+            if ui.menu_item("📂 Open") {
+                actions.push(AssetAction::OpenFolder(path.clone()));
+            }
+            if ui.menu_item("📁 New Folder") {
+                actions.push(AssetAction::NewFolder(path.clone()));
+            }
+        }
+        if path == root {
+            if ui.menu_item("🔍 Show in File Manager") {
+                actions.push(AssetAction::ShowInFileManager(path.clone()));
+            }
+            return;
+        }
+        let rename_shortcut = keybindings::shortcut_label(editor_actions::ASSETS_RENAME).unwrap_or_default();
+        if ui.menu_item_config("✏️ Rename").shortcut(rename_shortcut).build() {
+            actions.push(AssetAction::Rename(path.clone()));
+        }
+        if let Some(_menu) = ui.begin_menu("➡ Move To") {
+            // Every folder except this one, anything inside it and where it already is
+            let mut folders = vec![root];
+            folders.extend(self.tree.descendants(root).into_iter().filter(|e| e.is_folder()).map(|e| e.path.as_path()));
+            folders.sort();
+            for dir in folders {
+                if dir.starts_with(path) || path.parent() == Some(dir) {
+                    continue;
+                }
+                let relative = dir.strip_prefix(root.parent().unwrap_or(root)).unwrap_or(dir);
+                if ui.menu_item(format!("📁 {}", relative.display())) {
+                    actions.push(AssetAction::Move { path: path.clone(), dir: dir.to_path_buf() });
+                }
+            }
+        }
+        if ui.menu_item("🔍 Show in File Manager") {
+            actions.push(AssetAction::ShowInFileManager(path.clone()));
+        }
+        ui.separator();
+        let delete_shortcut = keybindings::shortcut_label(editor_actions::ASSETS_DELETE).unwrap_or_default();
+        if ui.menu_item_config("🗑 Delete").shortcut(delete_shortcut).build() {
+            actions.push(AssetAction::Delete(path.clone()));
+        }
+    }
+
+    /// The rename, move or delete modal, if one is waiting
+    fn render_pending_edit(&mut self, ui: &Ui) {
+        let Some(pending) = &mut self.pending else { return };
+        let title = match pending {
+            PendingEdit::Rename { .. } => "Rename Asset",
+            PendingEdit::Delete(_) => "Delete Asset",
+            PendingEdit::Move { .. } => "Move Asset",
+        };
+
+        let mut confirm = false;
+        let mut cancel = false;
+        let shown = ui.modal_popup_config(title)
+            .always_auto_resize(true)
+            .build(|| {
+                match pending {
+                    PendingEdit::Rename { path, name } => {
+                        ui.text(format!("Rename {}", file_name(path)));
+                        if ui.is_window_appearing() {
+                            ui.set_keyboard_focus_here();
+                        }
+                        confirm |= ui.input_text("Name", name).enter_returns_true(true).auto_select_all(true).build();
+                    }
+                    PendingEdit::Delete(path) => {
+                        let what = if path.is_dir() { "the folder and everything in it" } else { "the file" };
+                        ui.text(format!("Delete {}?", file_name(path)));
+                        ui.text_colored(PulsarTheme::WARNING, format!("This deletes {} from disk and can't be undone.", what));
+                    }
+                    PendingEdit::Move { path, dir } => {
+                        ui.text(format!("Move {} into {}?", file_name(path), file_name(dir)));
+                        ui.text_colored(PulsarTheme::TEXT_MUTED, "Scenes that refer to it by path will need updating.");
+                    }
+                }
+                ui.separator();
+                let label = match pending {
+                    PendingEdit::Rename { .. } => "✏️ Rename",
+                    PendingEdit::Delete(_) => "🗑 Delete",
+                    PendingEdit::Move { .. } => "➡ Move",
+                };
+                confirm |= ui.button_with_size(label, [100.0, 28.0]);
+                ui.same_line();
+                cancel = ui.button_with_size("Cancel", [100.0, 28.0]) || ui.is_key_pressed(Key::Escape);
+                if confirm || cancel {
+                    ui.close_current_popup();
+                }
+            })
+            .is_some();
+
+        // Opens from the next frame
+        if !shown {
+            ui.open_popup(title);
+        }
+
+        if cancel {
+            self.pending = None;
+        } else if confirm {
+            if let Some(pending) = self.pending.take() {
+                self.apply_edit(pending);
+            }
+        }
+    }
+
+    fn apply_edit(&mut self, edit: PendingEdit) {
+        let result = match edit {
+            PendingEdit::Rename { path, name } => assets::rename(&path, &name).map(Some),
+            PendingEdit::Delete(path) => assets::delete(&path).map(|_| None),
+            PendingEdit::Move { path, dir } => assets::move_into(&path, &dir).map(Some),
+        };
+        match result {
+            Ok(new_path) => {
+                self.selected = new_path;
+                self.error = None;
+            }
+            Err(e) => {
+                log::error!("{}", e);
+                self.error = Some(e);
+            }
+        }
+        self.rescan();
+    }
+
+    fn render_status(&self, ui: &Ui) {
+        let mut status = format!("{} folders, {} files", self.tree.folder_count().saturating_sub(1), self.tree.file_count());
+        if self.scan.is_some() {
+            status.push_str("  ⏳ Scanning...");
+        }
+        ui.text_colored(PulsarTheme::TEXT_MUTED, status);
+        if let Some(error) = &self.error {
+            ui.same_line();
+            ui.text_colored(PulsarTheme::WARNING, format!("⚠ {}", error));
+        }
+    }
+
+    /// Take the result of the background scan once it is done
+    fn poll_scan(&mut self) {
+        let Some(result) = self.scan.as_ref().and_then(AssetScan::poll) else { return };
+        self.scan = None;
+        match result {
+            Ok(tree) => {
+                // The folder being shown may have been moved or deleted outside the editor
+                if !tree.has_folder(&self.current) {
+                    self.current = tree.root.clone();
+                }
+                let files: HashSet<&Path> = tree.descendants(&tree.root).into_iter().map(|e| e.path.as_path()).collect();
+                self.thumbnails.retain(|path| files.contains(path));
+                self.tree = tree;
+            }
+            Err(e) => {
+                log::error!("Failed to scan assets: {}", e);
+                self.error = Some(e);
+            }
+        }
+    }
+
+    /// The current folder's contents, or everything under it matching the search,
+    /// folders first and then in the chosen order
+    fn visible_entries(&self) -> Vec<&AssetEntry> {
+        let search = self.search.trim().to_lowercase();
+        let mut entries: Vec<&AssetEntry> = if search.is_empty() {
+            self.tree.children(&self.current).iter().collect()
+        } else {
+            self.tree
+                .descendants(&self.current)
+                .into_iter()
+                .filter(|e| e.name.to_lowercase().contains(&search))
+                .collect()
+        };
+        entries.sort_by(|a, b| {
+            let order = match self.sort {
+                SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                SortKey::Type => a.kind.display_name().cmp(b.kind.display_name()).then_with(|| a.name.cmp(&b.name)),
+                SortKey::Size => a.size.cmp(&b.size),
+                SortKey::Modified => a.modified.cmp(&b.modified),
+            };
+            let order = if self.ascending { order } else { order.reverse() };
+            b.is_folder().cmp(&a.is_folder()).then(order)
+        });
+        entries
+    }
+
+    /// Run an Asset Browser shortcut action on the selection or the folder shown
+    pub fn run_action(&mut self, id: &str) {
+        if self.pending.is_some() {
+            return;
+        }
+        let action = match id {
+            editor_actions::ASSETS_RENAME => self.selected.clone().map(AssetAction::Rename),
+            editor_actions::ASSETS_DELETE => self.selected.clone().map(AssetAction::Delete),
+            editor_actions::ASSETS_PARENT_FOLDER if self.root.as_ref() != Some(&self.current) => {
+                self.current.parent().map(|parent| AssetAction::OpenFolder(parent.to_path_buf()))
+            }
+            _ => None,
+        };
+        self.apply_actions(action.into_iter().collect());
+    }
+
+    fn apply_actions(&mut self, actions: Vec<AssetAction>) {
+        for action in actions {
+            match action {
+                AssetAction::OpenFolder(path) => {
+                    self.current = path;
+                    self.selected = None;
+                    self.search.clear();
+                }
+                AssetAction::Select(path) => self.selected = Some(path),
+                AssetAction::ContextMenu(_) => {}
+                AssetAction::BeginDrag(path) => self.dragging = Some(path),
+                AssetAction::Rename(path) => {
+                    let name = file_name(&path);
+                    self.pending = Some(PendingEdit::Rename { path, name });
+                }
+                AssetAction::Delete(path) => self.pending = Some(PendingEdit::Delete(path)),
+                AssetAction::Move { path, dir } => {
+                    self.dragging = None;
+                    self.pending = Some(PendingEdit::Move { path, dir });
+                }
+                AssetAction::NewFolder(parent) => match assets::create_folder(&parent, "New Folder") {
+                    Ok(path) => {
+                        self.current = parent;
+                        self.pending = Some(PendingEdit::Rename { name: file_name(&path), path });
+                        self.rescan();
+                    }
+                    Err(e) => {
+                        log::error!("{}", e);
+                        self.error = Some(e);
+                    }
+                },
+                AssetAction::ShowInFileManager(path) => {
+                    if let Err(e) = assets::show_in_file_manager(&path) {
+                        log::error!("{}", e);
+                        self.error = Some(e);
+                    }
+                }
+                AssetAction::SortBy(key) => {
+                    self.ascending = self.sort != key || !self.ascending;
+                    self.sort = key;
+                }
+                AssetAction::LoadThumbnail(path, modified) => self.thumbnails.request(&path, modified),
+                AssetAction::Refresh => self.rescan(),
+            }
+        }
+    }
+}
+
+impl Default for AssetBrowser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned())
+}
+
+/// `text`, shortened with "…" to fit in `width`
+fn fit_text(ui: &Ui, text: &str, width: f32) -> String {
+    if ui.calc_text_size(text)[0] <= width {
+        return text.to_string();
+    }
+    let mut fitted: String = text.to_string();
+    while !fitted.is_empty() && ui.calc_text_size(format!("{}…", fitted))[0] > width {
+        fitted.pop();
+    }
+    format!("{}…", fitted)
+}
+
+/// Tint for the icon of assets without a thumbnail
+fn kind_color(kind: AssetKind) -> [f32; 4] {
+    match kind {
+        AssetKind::Folder => [0.9, 0.75, 0.35, 1.0],
+        AssetKind::Image => [0.4, 0.7, 1.0, 1.0],
+        AssetKind::Model => [1.0, 0.6, 0.3, 1.0],
+        AssetKind::Audio => [0.5, 0.9, 0.5, 1.0],
+        AssetKind::Script => [0.95, 0.9, 0.4, 1.0],
+        AssetKind::Scene => PulsarTheme::BLUE_PRIMARY,
+        AssetKind::Material => [0.9, 0.5, 0.9, 1.0],
+        AssetKind::Shader => [0.6, 0.5, 1.0, 1.0],
+        AssetKind::Other => PulsarTheme::TEXT_SECONDARY,
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1_048_575 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// How long ago `time` was, e.g. "5 min ago"
+fn format_age(time: SystemTime) -> String {
+    let seconds = SystemTime::now().duration_since(time).map_or(0, |age| age.as_secs());
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86_399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86_400),
+    }
+}
@@ -0,0 +1,163 @@
+use imgui::*;
+use crate::actions::{fuzzy_score, Action, ActionRegistry};
+use crate::ui::theme::PulsarTheme;
+
+const PALETTE_WIDTH: f32 = 560.0;
+const LIST_HEIGHT: f32 = 320.0;
+
+/// Searchable list of every registered action, opened with Ctrl+Shift+P
+pub struct CommandPalette {
+    open: bool,
+    query: String,
+    selected: usize, // index into the ranked results
+    focus_query: bool, // put the cursor in the search field next frame
+    scroll_to_selected: bool,
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            query: String::new(),
+            selected: 0,
+            focus_query: false,
+            scroll_to_selected: false,
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.open = true;
+        self.query.clear();
+        self.selected = 0;
+        self.focus_query = true;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Draw the palette if it is open. Returns the id of the action picked to run;
+    /// actions `enabled` rejects are listed but can't be picked.
+    pub fn render(&mut self, ui: &Ui, actions: &ActionRegistry, enabled: &dyn Fn(&str) -> bool) -> Option<String> {
+        if !self.open {
+            return None;
+        }
+        let mut picked = None;
+        let display_size = ui.io().display_size;
+
+        ui.window("Command Palette")
+            .position([(display_size[0] - PALETTE_WIDTH) * 0.5, 60.0], Condition::Always)
+            .size([PALETTE_WIDTH, 0.0], Condition::Always)
+            .flags(WindowFlags::NO_TITLE_BAR | WindowFlags::NO_RESIZE | WindowFlags::NO_MOVE | WindowFlags::NO_SAVED_SETTINGS | WindowFlags::ALWAYS_AUTO_RESIZE)
+            .focus_on_appearing(true)
+            .build(|| {
+                let focusing = self.focus_query;
+                if self.focus_query {
+                    ui.set_keyboard_focus_here();
+                    self.focus_query = false;
+                }
+                ui.set_next_item_width(-1.0);
+                let entered = ui.input_text("##command_query", &mut self.query)
+                    .hint("Type a command...")
+                    .enter_returns_true(true)
+                    .build();
+                if ui.is_item_edited() {
+                    self.selected = 0;
+                }
+
+                let results = rank(actions, &self.query);
+                if !results.is_empty() {
+                    if ui.is_key_pressed(Key::DownArrow) {
+                        self.selected = (self.selected + 1) % results.len();
+                        self.scroll_to_selected = true;
+                    }
+                    if ui.is_key_pressed(Key::UpArrow) {
+                        self.selected = (self.selected + results.len() - 1) % results.len();
+                        self.scroll_to_selected = true;
+                    }
+                    self.selected = self.selected.min(results.len() - 1);
+                }
+                if entered {
+                    picked = results.get(self.selected).filter(|a| enabled(&a.id)).map(|a| a.id.clone());
+                }
+
+                ui.separator();
+                ui.child_window("##commands")
+                    .size([0.0, LIST_HEIGHT])
+                    .build(|| {
+                        for (i, action) in results.iter().enumerate() {
+                            let is_recent = actions.recent().contains(&action.id);
+                            let row_start = ui.cursor_pos();
+                            if ui.selectable_config(format!("{}##{}", action.full_name(), action.id))
+                                .selected(i == self.selected)
+                                .disabled(!enabled(&action.id))
+                                .build()
+                            {
+                                picked = Some(action.id.clone());
+                            }
+                            if i == self.selected && self.scroll_to_selected {
+                                ui.set_scroll_here_y();
+                                self.scroll_to_selected = false;
+                            }
+
+                            // Shortcut, or a note that it was used lately, at the right edge
+                            let note = match (action.shortcut(), is_recent && self.query.is_empty()) {
+                                (Some(shortcut), _) => Some(shortcut.to_string()),
+                                (None, true) => Some("recent".to_string()),
+                                (None, false) => None,
+                            };
+                            if let Some(note) = note {
+                                let width = ui.calc_text_size(&note)[0];
+                                ui.same_line_with_pos(ui.window_content_region_max()[0] - width);
+                                ui.text_colored(PulsarTheme::TEXT_MUTED, &note);
+                                ui.set_cursor_pos([row_start[0], ui.cursor_pos()[1]]);
+                            }
+                        }
+                        if results.is_empty() {
+                            ui.text_colored(PulsarTheme::TEXT_MUTED, "❌ No matching commands");
+                        }
+                    });
+
+                // Escape or clicking elsewhere dismisses the palette
+                let focused = ui.is_window_focused_with_flags(WindowFocusedFlags::ROOT_AND_CHILD_WINDOWS);
+                if ui.is_key_pressed(Key::Escape) || (!focused && !focusing) {
+                    self.open = false;
+                }
+            });
+
+        if picked.is_some() {
+            self.open = false;
+        }
+        picked
+    }
+}
+
+impl Default for CommandPalette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Actions matching `query`, best first. Recently run actions come first when the
+/// query is empty and get a boost otherwise.
+fn rank<'a>(actions: &'a ActionRegistry, query: &str) -> Vec<&'a Action> {
+    let recent = actions.recent();
+    let recency = |action: &Action| {
+        recent.iter().position(|id| *id == action.id).map_or(0, |i| (recent.len() - i) as i32)
+    };
+
+    let mut scored: Vec<(i32, &Action)> = actions
+        .actions()
+        .iter()
+        .filter_map(|action| {
+            if query.trim().is_empty() {
+                return Some((recency(action) * 100, action));
+            }
+            let score = fuzzy_score(query, &action.full_name())?;
+            Some((score * 4 + recency(action), action))
+        })
+        .collect();
+    // Stable, so equal scores keep registration order
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, action)| action).collect()
+}
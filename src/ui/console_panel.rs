@@ -0,0 +1,367 @@
+use imgui::*;
+use std::collections::{BTreeSet, VecDeque};
+use log::Level;
+use crate::console;
+use crate::logging::{self, LogEntry};
+use crate::ui::theme::PulsarTheme;
+
+/// Levels in the order their filters are shown
+const LEVELS: [Level; 4] = [Level::Error, Level::Warn, Level::Info, Level::Debug];
+
+/// Records kept by the panel; older ones are dropped as new ones arrive
+const MAX_ENTRIES: usize = 10_000;
+
+/// Commands remembered for Up/Down
+const MAX_HISTORY: usize = 100;
+
+/// The Console panel: log records with level, category and text filters
+pub struct ConsolePanel {
+    entries: VecDeque<LogEntry>,
+    last_sequence: u64, // newest record pulled from the log
+    categories: BTreeSet<String>, // every category seen so far
+    hidden_categories: BTreeSet<String>,
+    shown_levels: [bool; 4], // indexed like LEVELS
+    search: String,
+    collapse: bool, // show repeats of the same record once, with a count
+    auto_scroll: bool,
+    scroll_to_bottom: bool, // a command was run, so show its output
+    // Command line
+    command: String,
+    history: Vec<String>, // oldest first
+    history_pos: Option<usize>, // entry Up/Down last put in the command line
+    focus_command: bool,
+}
+
+/// Up/Down and Tab handling for the command line
+struct CommandCallbacks<'a> {
+    history: &'a [String],
+    history_pos: &'a mut Option<usize>,
+}
+
+/// A line of output: a record and how many times it repeated in a row
+struct Row<'a> {
+    entry: &'a LogEntry,
+    count: usize,
+}
+
+impl ConsolePanel {
+    pub fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            last_sequence: 0,
+            categories: BTreeSet::new(),
+            hidden_categories: BTreeSet::new(),
+            shown_levels: [true; 4],
+            search: String::new(),
+            collapse: true,
+            auto_scroll: true,
+            scroll_to_bottom: false,
+            command: String::new(),
+            history: Vec::new(),
+            history_pos: None,
+            focus_command: false,
+        }
+    }
+
+    /// Commands run before, oldest first, to restore from a session
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    pub fn set_history(&mut self, history: Vec<String>) {
+        self.history = history;
+        self.history_pos = None;
+    }
+
+    pub fn render(&mut self, ui: &Ui) {
+        self.pull_entries();
+        self.render_toolbar(ui);
+        ui.separator();
+
+        let scroll_to_bottom = std::mem::take(&mut self.scroll_to_bottom);
+        let rows = self.rows();
+        ui.child_window("ConsoleOutput")
+            .size([0.0, -30.0])
+            .horizontal_scrollbar(true)
+            .build(|| {
+                if rows.is_empty() {
+                    let text = if self.entries.is_empty() { "Nothing logged yet" } else { "No records match the filters" };
+                    ui.text_colored(PulsarTheme::TEXT_MUTED, text);
+                }
+                // Only the visible rows are drawn, so long logs stay cheap
+                let mut clipper = ListClipper::new(rows.len() as i32).begin(ui);
+                while clipper.step() {
+                    for row in &rows[clipper.display_start() as usize..clipper.display_end() as usize] {
+                        render_row(ui, row);
+                    }
+                }
+                // Follow new output, unless the user has scrolled up to read
+                if scroll_to_bottom || (self.auto_scroll && ui.scroll_y() >= ui.scroll_max_y()) {
+                    ui.set_scroll_here_y_with_ratio(1.0);
+                }
+            });
+
+        ui.separator();
+        self.render_command_line(ui);
+    }
+
+    fn render_command_line(&mut self, ui: &Ui) {
+        if self.focus_command {
+            ui.set_keyboard_focus_here();
+            self.focus_command = false;
+        }
+        ui.set_next_item_width(-1.0);
+        let callbacks = CommandCallbacks { history: &self.history, history_pos: &mut self.history_pos };
+        let entered = ui.input_text("##console_command", &mut self.command)
+            .hint("Command (Tab completes, Up/Down for history, 'help' lists commands)")
+            .enter_returns_true(true)
+            .callback(InputTextCallback::HISTORY | InputTextCallback::COMPLETION, callbacks)
+            .build();
+        if !entered {
+            return;
+        }
+
+        let line = self.command.trim().to_string();
+        self.command.clear();
+        self.history_pos = None;
+        // Enter takes the keyboard away from the field; keep typing commands
+        self.focus_command = true;
+        if line.is_empty() {
+            return;
+        }
+        if self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+            if self.history.len() > MAX_HISTORY {
+                self.history.remove(0);
+            }
+        }
+        console::execute(&line);
+        self.scroll_to_bottom = true;
+    }
+
+    fn render_toolbar(&mut self, ui: &Ui) {
+        for (i, level) in LEVELS.iter().enumerate() {
+            let count = self.entries.iter().filter(|entry| entry.level == *level).count();
+            let color = if count > 0 { level_color(*level) } else { PulsarTheme::TEXT_MUTED };
+            let _color = ui.push_style_color(StyleColor::Text, color);
+            ui.checkbox(format!("{} {}##level{}", level_name(*level), count, i), &mut self.shown_levels[i]);
+            ui.same_line();
+        }
+
+        if ui.button("🏷 Categories") {
+            ui.open_popup("ConsoleCategories");
+        }
+        if let Some(_popup) = ui.begin_popup("ConsoleCategories") {
+            if ui.small_button("All") {
+                self.hidden_categories.clear();
+            }
+            ui.same_line();
+            if ui.small_button("None") {
+                self.hidden_categories = self.categories.clone();
+            }
+            ui.separator();
+            if self.categories.is_empty() {
+                ui.text_colored(PulsarTheme::TEXT_MUTED, "No categories yet");
+            }
+            for category in &self.categories {
+                let mut shown = !self.hidden_categories.contains(category);
+                if ui.checkbox(category, &mut shown) {
+                    if shown {
+                        self.hidden_categories.remove(category);
+                    } else {
+                        self.hidden_categories.insert(category.clone());
+                    }
+                }
+            }
+        }
+        ui.same_line();
+        ui.set_next_item_width(200.0);
+        ui.input_text("##console_search", &mut self.search)
+            .hint("🔍 Search")
+            .build();
+
+        ui.same_line();
+        ui.checkbox("Collapse", &mut self.collapse);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Show a record repeated in a row once, with a count");
+        }
+        ui.same_line();
+        ui.checkbox("Auto-scroll", &mut self.auto_scroll);
+
+        ui.same_line();
+        if ui.button("📋 Copy") {
+            let text: Vec<String> = self.rows().iter().map(row_text).collect();
+            ui.set_clipboard_text(text.join("\n"));
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Copy the records shown to the clipboard");
+        }
+        ui.same_line();
+        if ui.button("🗑 Clear") {
+            self.entries.clear();
+        }
+
+        ui.same_line();
+        let mut to_file = logging::log_file().is_some();
+        if ui.checkbox("📄 Log file", &mut to_file) {
+            let path = logging::default_log_file();
+            match logging::set_log_file(to_file.then_some(path.as_path())) {
+                Ok(()) if to_file => log::info!("Mirroring the log to {}", path.display()),
+                Ok(()) => {}
+                Err(e) => log::error!("Failed to open log file {}: {}", path.display(), e),
+            }
+        }
+        if ui.is_item_hovered() {
+            let path = logging::log_file().unwrap_or_else(logging::default_log_file);
+            ui.tooltip_text(format!("Also write the log to {}", path.display()));
+        }
+    }
+
+    /// Take the records logged since last frame
+    fn pull_entries(&mut self) {
+        for entry in logging::entries_since(self.last_sequence) {
+            self.last_sequence = entry.sequence;
+            if !self.categories.contains(&entry.category) {
+                self.categories.insert(entry.category.clone());
+            }
+            if self.entries.len() == MAX_ENTRIES {
+                self.entries.pop_front();
+            }
+            self.entries.push_back(entry);
+        }
+    }
+
+    /// The records passing the filters, with repeats merged if collapsing
+    fn rows(&self) -> Vec<Row<'_>> {
+        let search = self.search.to_lowercase();
+        let mut rows: Vec<Row> = Vec::new();
+        for entry in &self.entries {
+            let level_hidden = LEVELS.iter().zip(self.shown_levels).any(|(level, shown)| *level == entry.level && !shown);
+            if level_hidden || self.hidden_categories.contains(&entry.category) {
+                continue;
+            }
+            if !search.is_empty()
+                && !entry.message.to_lowercase().contains(&search)
+                && !entry.category.to_lowercase().contains(&search)
+            {
+                continue;
+            }
+            if self.collapse {
+                if let Some(last) = rows.last_mut() {
+                    if last.entry.level == entry.level && last.entry.category == entry.category && last.entry.message == entry.message {
+                        // Keep the newest timestamp for the merged row
+                        last.entry = entry;
+                        last.count += 1;
+                        continue;
+                    }
+                }
+            }
+            rows.push(Row { entry, count: 1 });
+        }
+        rows
+    }
+}
+
+impl InputTextCallbackHandler for CommandCallbacks<'_> {
+    fn on_history(&mut self, direction: HistoryDirection, mut data: TextCallbackData) {
+        if self.history.is_empty() {
+            return;
+        }
+        let last = self.history.len() - 1;
+        let pos = match (direction, *self.history_pos) {
+            (HistoryDirection::Up, None) => Some(last),
+            (HistoryDirection::Up, Some(i)) => Some(i.saturating_sub(1)),
+            (HistoryDirection::Down, Some(i)) if i < last => Some(i + 1),
+            (HistoryDirection::Down, _) => None, // past the newest: back to an empty line
+        };
+        *self.history_pos = pos;
+        data.clear();
+        if let Some(i) = pos {
+            data.push_str(&self.history[i]);
+        }
+    }
+
+    /// Complete the word at the end of the line to a command or variable name. When
+    /// several match and there is nothing more in common, list them.
+    fn on_completion(&mut self, mut data: TextCallbackData) {
+        let text = data.str().to_string();
+        let start = text.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let word = &text[start..];
+        let candidates = console::completions(word);
+        let Some(first) = candidates.first() else {
+            return;
+        };
+        let completion = if candidates.len() == 1 {
+            format!("{} ", first)
+        } else {
+            common_prefix(&candidates).to_string()
+        };
+        if completion.len() > word.len() {
+            data.remove_chars(start, word.chars().count());
+            data.insert_chars(start, &completion);
+        } else {
+            log::info!("{}", candidates.join("  "));
+        }
+    }
+}
+
+/// The longest start shared by every name
+fn common_prefix(names: &[String]) -> &str {
+    let first = &names[0];
+    let mut len = first.len();
+    for name in &names[1..] {
+        len = first
+            .char_indices()
+            .zip(name.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(name.len()), |((i, _), _)| i.min(len));
+    }
+    &first[..len]
+}
+
+impl Default for ConsolePanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn render_row(ui: &Ui, row: &Row) {
+    let entry = row.entry;
+    ui.text_colored(PulsarTheme::TEXT_MUTED, format!("[{}]", entry.timestamp()));
+    ui.same_line();
+    ui.text_colored(level_color(entry.level), format!("[{}]", entry.level));
+    ui.same_line();
+    ui.text_colored(PulsarTheme::TEXT_SECONDARY, format!("{}:", entry.category));
+    ui.same_line();
+    ui.text(&entry.message);
+    if row.count > 1 {
+        ui.same_line();
+        ui.text_colored(PulsarTheme::BLUE_PRIMARY, format!("×{}", row.count));
+    }
+}
+
+/// A row as copied to the clipboard
+fn row_text(row: &Row) -> String {
+    match row.count {
+        1 => row.entry.format_line(),
+        count => format!("{} (×{})", row.entry.format_line(), count),
+    }
+}
+
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Error => "❌ Errors",
+        Level::Warn => "⚠ Warnings",
+        Level::Info => "ℹ Info",
+        Level::Debug | Level::Trace => "🐞 Debug",
+    }
+}
+
+fn level_color(level: Level) -> [f32; 4] {
+    match level {
+        Level::Error => [1.0, 0.5, 0.5, 1.0],
+        Level::Warn => [1.0, 1.0, 0.5, 1.0],
+        Level::Info => [0.5, 1.0, 0.5, 1.0],
+        Level::Debug | Level::Trace => PulsarTheme::TEXT_MUTED,
+    }
+}
@@ -0,0 +1,100 @@
+use imgui::*;
+use crate::tab_system::TabId;
+use crate::ui::editor_panels::EditorPanelType;
+use crate::ui::theme::PulsarTheme;
+
+/// Size a tab's OS window opens at
+pub const DETACHED_TAB_SIZE: [f32; 2] = [800.0, 600.0];
+const TITLE_BAR_HEIGHT: f32 = 26.0;
+
+/// What a detached OS window shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DetachedContent {
+    Panel(EditorPanelType),
+    Tab(TabId),
+}
+
+/// A panel or tab shown in an OS window of its own. Positions are logical pixels
+/// relative to the main window's client area, so they follow the main window around.
+#[derive(Debug, Clone)]
+pub struct DetachedWindow {
+    pub content: DetachedContent,
+    pub title: String,
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+}
+
+/// A detached window being dragged by its title bar
+#[derive(Debug, Clone, Copy)]
+pub struct DetachedDrag {
+    pub content: DetachedContent,
+    grab: [f32; 2],        // mouse position within the window when the drag started
+    pub cursor: [f32; 2], // relative to the main window
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DetachedBarAction {
+    Move([f32; 2]), // new window position
+    Drop([f32; 2]), // released with the cursor here, relative to the main window
+    Dock,
+}
+
+/// Title bar across the top of a detached window. Dragging it moves the OS window,
+/// and releasing it over the main window's drop hints docks the content again.
+/// `origin` is where the window is relative to the main window.
+pub fn render_title_bar(
+    ui: &Ui,
+    window: &DetachedWindow,
+    origin: [f32; 2],
+    drag: &mut Option<DetachedDrag>,
+) -> Option<DetachedBarAction> {
+    let mut action = None;
+    let mouse = ui.io().mouse_pos;
+    let cursor = [origin[0] + mouse[0], origin[1] + mouse[1]];
+
+    let dock_width = 80.0;
+    let grip_width = (ui.content_region_avail()[0] - dock_width - 8.0).max(1.0);
+    let start = ui.cursor_screen_pos();
+    ui.invisible_button("##detached_grip", [grip_width, TITLE_BAR_HEIGHT]);
+    let active = ui.is_item_active();
+    let hovered = ui.is_item_hovered();
+
+    let draw_list = ui.get_window_draw_list();
+    draw_list
+        .add_rect(start, [start[0] + grip_width, start[1] + TITLE_BAR_HEIGHT], if active { PulsarTheme::BLUE_PRIMARY } else { PulsarTheme::DARK_PANEL })
+        .filled(true)
+        .rounding(4.0)
+        .build();
+    draw_list.add_text([start[0] + 8.0, start[1] + 5.0], PulsarTheme::TEXT_PRIMARY, format!("⠿ {}", window.title));
+    if hovered && !active {
+        ui.tooltip_text("Drag onto the main window to dock");
+    }
+
+    if ui.is_item_activated() {
+        *drag = Some(DetachedDrag { content: window.content, grab: mouse, cursor });
+    }
+    match drag {
+        Some(current) if current.content == window.content => {
+            if active {
+                current.cursor = cursor;
+                let position = [cursor[0] - current.grab[0], cursor[1] - current.grab[1]];
+                if position != window.position {
+                    action = Some(DetachedBarAction::Move(position));
+                }
+            } else {
+                action = Some(DetachedBarAction::Drop(current.cursor));
+                *drag = None;
+            }
+        }
+        _ => {}
+    }
+
+    ui.same_line();
+    if ui.button_with_size("📥 Dock", [dock_width, TITLE_BAR_HEIGHT]) {
+        action = Some(DetachedBarAction::Dock);
+    }
+    if ui.is_item_hovered() {
+        ui.tooltip_text("Put this back into the main window");
+    }
+    action
+}
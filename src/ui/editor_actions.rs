@@ -0,0 +1,134 @@
+use imgui::Key;
+use crate::actions::{ActionRegistry, Shortcut};
+use crate::keybindings::KeyScope;
+use crate::tab_system::TabSystem;
+use crate::ui::editor_panels::EditorPanelType;
+
+pub const NEW_PROJECT: &str = "file.new_project";
+pub const OPEN_PROJECT: &str = "file.open_project";
+pub const NEW_TAB: &str = "file.new_tab";
+pub const CLOSE_TAB: &str = "file.close_tab";
+pub const SAVE: &str = "file.save";
+pub const SAVE_AS: &str = "file.save_as";
+pub const EXIT: &str = "file.exit";
+pub const UNDO: &str = "edit.undo";
+pub const REDO: &str = "edit.redo";
+pub const KEYBOARD_SHORTCUTS: &str = "edit.keyboard_shortcuts";
+pub const COMMAND_PALETTE: &str = "view.command_palette";
+pub const SAVE_LAYOUT_AS: &str = "layout.save_as";
+pub const RESET_LAYOUT: &str = "layout.reset";
+pub const MANAGE_LAYOUTS: &str = "layout.manage";
+pub const ABOUT: &str = "help.about";
+pub const FRAME_SELECTION: &str = "viewport.frame_selection";
+pub const TOGGLE_PROJECTION: &str = "viewport.toggle_projection";
+pub const HIERARCHY_RENAME: &str = "hierarchy.rename";
+pub const HIERARCHY_DELETE: &str = "hierarchy.delete";
+pub const ASSETS_RENAME: &str = "assets.rename";
+pub const ASSETS_DELETE: &str = "assets.delete";
+pub const ASSETS_PARENT_FOLDER: &str = "assets.parent_folder";
+
+/// Prefix of actions the active tab runs, through `TabContent::run_action`
+pub const VIEWPORT: &str = "viewport.";
+/// Prefix of actions the Hierarchy panel runs on the active tab's scene
+pub const HIERARCHY: &str = "hierarchy.";
+/// Prefix of actions the Asset Browser runs
+pub const ASSETS: &str = "assets.";
+
+/// Followed by a panel's display name, shows or hides that panel
+pub const TOGGLE_PANEL: &str = "view.panel.";
+/// Followed by a preset name, applies that layout
+pub const APPLY_LAYOUT: &str = "layout.apply.";
+/// Followed by a tab type name, opens a tab of that type
+pub const OPEN_TAB: &str = "tab.open.";
+
+/// Submenu the layout presets are listed in, ahead of its actions
+pub const LAYOUT_MENU: &str = "View/Layout";
+
+/// Register the editor's built-in actions and lay out the main menu bar from them
+pub fn register_editor_actions(actions: &mut ActionRegistry) {
+    actions.register(NEW_PROJECT, "File", "New Project", &[]);
+    actions.register(OPEN_PROJECT, "File", "Open Project", &[]);
+    actions.register(NEW_TAB, "File", "New Tab...", &[Shortcut::ctrl(Key::T)]);
+    actions.register(CLOSE_TAB, "File", "Close Tab", &[Shortcut::ctrl(Key::W)]);
+    actions.register(SAVE, "File", "Save", &[Shortcut::ctrl(Key::S)]);
+    actions.register(SAVE_AS, "File", "Save As...", &[Shortcut::ctrl_shift(Key::S)]);
+    actions.register(EXIT, "File", "Exit", &[]);
+    actions.register(UNDO, "Edit", "Undo", &[Shortcut::ctrl(Key::Z)]);
+    actions.register(REDO, "Edit", "Redo", &[Shortcut::ctrl(Key::Y), Shortcut::ctrl_shift(Key::Z)]);
+    actions.register(KEYBOARD_SHORTCUTS, "Edit", "Keyboard Shortcuts...", &[]);
+    actions.register(COMMAND_PALETTE, "View", "Command Palette...", &[Shortcut::ctrl_shift(Key::P)]);
+    actions.register(SAVE_LAYOUT_AS, "Layout", "Save Layout As...", &[]);
+    actions.register(RESET_LAYOUT, "Layout", "Reset Layout", &[]);
+    actions.register(MANAGE_LAYOUTS, "Layout", "Manage Layouts...", &[]);
+    actions.register(ABOUT, "Help", "About", &[]);
+    actions.register_scoped(FRAME_SELECTION, "Viewport", "Frame Selection", KeyScope::Viewport, &[Shortcut::key(Key::F)]);
+    actions.register_scoped(TOGGLE_PROJECTION, "Viewport", "Toggle Perspective/Orthographic", KeyScope::Viewport, &[Shortcut::key(Key::Keypad5)]);
+    actions.register_scoped(HIERARCHY_RENAME, "Hierarchy", "Rename Entity", KeyScope::Hierarchy, &[Shortcut::key(Key::F2)]);
+    actions.register_scoped(HIERARCHY_DELETE, "Hierarchy", "Delete Selected Entities", KeyScope::Hierarchy, &[Shortcut::key(Key::Delete)]);
+    actions.register_scoped(ASSETS_RENAME, "Assets", "Rename Asset", KeyScope::AssetBrowser, &[Shortcut::key(Key::F2)]);
+    actions.register_scoped(ASSETS_DELETE, "Assets", "Delete Asset", KeyScope::AssetBrowser, &[Shortcut::key(Key::Delete)]);
+    actions.register_scoped(ASSETS_PARENT_FOLDER, "Assets", "Go to Parent Folder", KeyScope::AssetBrowser, &[Shortcut::key(Key::Backspace)]);
+    for panel in EditorPanelType::all_panels() {
+        let name = format!("{} {}", panel.icon(), panel.display_name());
+        actions.register(&panel_action(panel), "Toggle Panel", &name, &[]);
+    }
+
+    for id in [NEW_PROJECT, OPEN_PROJECT] {
+        actions.add_to_menu("File", id);
+    }
+    actions.add_separator("File");
+    for id in [NEW_TAB, CLOSE_TAB] {
+        actions.add_to_menu("File", id);
+    }
+    actions.add_separator("File");
+    for id in [SAVE, SAVE_AS] {
+        actions.add_to_menu("File", id);
+    }
+    actions.add_separator("File");
+    actions.add_to_menu("File", EXIT);
+
+    actions.add_to_menu("Edit", UNDO);
+    actions.add_to_menu("Edit", REDO);
+    actions.add_separator("Edit");
+    actions.add_to_menu("Edit", KEYBOARD_SHORTCUTS);
+
+    actions.add_to_menu("View", COMMAND_PALETTE);
+    actions.add_separator("View");
+    actions.add_separator(LAYOUT_MENU);
+    for id in [SAVE_LAYOUT_AS, RESET_LAYOUT, MANAGE_LAYOUTS] {
+        actions.add_to_menu(LAYOUT_MENU, id);
+    }
+    actions.add_separator("View");
+    for panel in EditorPanelType::all_panels() {
+        actions.add_to_menu("View", &panel_action(panel));
+    }
+
+    actions.add_to_menu("Help", ABOUT);
+}
+
+/// An "open tab" action for each registered tab type, for the palette
+pub fn register_tab_actions(actions: &mut ActionRegistry, tabs: &TabSystem) {
+    actions.unregister_prefix(OPEN_TAB);
+    for (name, icon) in tabs.tab_types() {
+        actions.register(&format!("{}{}", OPEN_TAB, name), "New Tab", &format!("{} {}", icon, name), &[]);
+    }
+}
+
+/// An "apply layout" action for each preset, for the palette. Presets can be added
+/// and removed at any time, so call this before listing actions.
+pub fn register_layout_actions(actions: &mut ActionRegistry, presets: &[String]) {
+    actions.unregister_prefix(APPLY_LAYOUT);
+    for name in presets {
+        actions.register(&format!("{}{}", APPLY_LAYOUT, name), "Layout", &format!("Apply {}", name), &[]);
+    }
+}
+
+pub fn panel_action(panel: EditorPanelType) -> String {
+    format!("{}{}", TOGGLE_PANEL, panel.display_name())
+}
+
+/// The panel a `panel_action` id shows or hides
+pub fn action_panel(id: &str) -> Option<EditorPanelType> {
+    let name = id.strip_prefix(TOGGLE_PANEL)?;
+    EditorPanelType::all_panels().into_iter().find(|panel| panel.display_name() == name)
+}
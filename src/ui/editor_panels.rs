@@ -1,8 +1,11 @@
 use imgui::*;
-use crate::ui::{PulsarTheme, VisualEffects, ButtonVariant};
+use serde::{Deserialize, Serialize};
+use crate::history::History;
+use crate::scene::{Scene, Selection};
+use crate::ui::{PulsarTheme, HierarchyPanel, InspectorPanel};
 
 /// Different types of editor panels available
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EditorPanelType {
     LevelEditor,
     ScriptEditor,
@@ -76,19 +79,27 @@ impl EditorPanelType {
     }
 }
 
+/// Editor state that panel content reads from and edits
+pub struct PanelContext<'a> {
+    pub scene: &'a mut Scene,
+    pub history: &'a mut History<Scene>,
+    pub selection: &'a mut Selection,
+    pub hierarchy: &'a mut HierarchyPanel,
+    pub inspector: &'a mut InspectorPanel,
+}
+
 /// Content renderer for each editor panel type
 pub struct EditorPanelRenderer;
 
 impl EditorPanelRenderer {
-    pub fn render_panel_content(ui: &Ui, panel_type: EditorPanelType, content_region: [f32; 2]) {
+    /// `ctx` is None when no scene is open, e.g. the active tab isn't a scene editor
+    pub fn render_panel_content(ui: &Ui, panel_type: EditorPanelType, content_region: [f32; 2], ctx: Option<&mut PanelContext>) {
         match panel_type {
             EditorPanelType::LevelEditor => Self::render_level_editor(ui, content_region),
             EditorPanelType::ScriptEditor => Self::render_script_editor(ui, content_region),
             EditorPanelType::BlueprintEditor => Self::render_blueprint_editor(ui, content_region),
-            EditorPanelType::AssetBrowser => Self::render_asset_browser(ui, content_region),
-            EditorPanelType::Inspector => Self::render_inspector(ui, content_region),
-            EditorPanelType::Hierarchy => Self::render_hierarchy(ui, content_region),
-            EditorPanelType::Console => Self::render_console(ui, content_region),
+            EditorPanelType::Inspector => Self::render_inspector(ui, ctx),
+            EditorPanelType::Hierarchy => Self::render_hierarchy(ui, ctx),
             EditorPanelType::Profiler => Self::render_profiler(ui, content_region),
             EditorPanelType::Animation => Self::render_animation(ui, content_region),
             EditorPanelType::Material => Self::render_material_editor(ui, content_region),
@@ -98,6 +109,8 @@ impl EditorPanelRenderer {
             EditorPanelType::Lighting => Self::render_lighting(ui, content_region),
             EditorPanelType::Terrain => Self::render_terrain_editor(ui, content_region),
             EditorPanelType::Weather => Self::render_weather_system(ui, content_region),
+            // Drawn by `SimpleGameUI`, which owns their state
+            EditorPanelType::Console | EditorPanelType::AssetBrowser => {}
         }
     }
 
@@ -181,112 +194,20 @@ impl EditorPanelRenderer {
         ui.dummy(size);
     }
 
-    fn render_asset_browser(ui: &Ui, content_region: [f32; 2]) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "📁 Asset Browser");
-        ui.separator();
-
-        // Search bar
-        let mut search = String::new();
-        ui.input_text("Search", &mut search).build();
-
-        ui.spacing();
-
-        // Asset grid
-        let child_size = [content_region[0] - 20.0, content_region[1] - 80.0];
-        ui.child_window("asset_grid")
-            .size(child_size)
-            .build(|| {
-                let assets = vec!["texture1.png", "model.fbx", "audio.wav", "script.rs", "material.mat"];
-                for (i, asset) in assets.iter().enumerate() {
-                    if i > 0 && i % 3 != 0 { ui.same_line(); }
-
-                    let pos = ui.cursor_screen_pos();
-                    let thumbnail_size = [64.0, 64.0];
-
-                    // Asset thumbnail
-                    ui.get_window_draw_list()
-                        .add_rect(pos, [pos[0] + thumbnail_size[0], pos[1] + thumbnail_size[1]], PulsarTheme::DARK_PANEL)
-                        .filled(true)
-                        .build();
-
-                    ui.dummy(thumbnail_size);
-                    ui.text(asset);
-                    ui.spacing();
-                }
-            });
-    }
-
-    fn render_inspector(ui: &Ui, _content_region: [f32; 2]) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "🔍 Inspector");
-        ui.separator();
-
-        ui.text("Selected: None");
-        ui.spacing();
-
-        // Transform component
-        if ui.collapsing_header("Transform", TreeNodeFlags::DEFAULT_OPEN) {
-            ui.text("Position:");
-            let mut pos = [0.0f32, 0.0, 0.0];
-            ui.input_float3("##pos", &mut pos).build();
-
-            ui.text("Rotation:");
-            let mut rot = [0.0f32, 0.0, 0.0];
-            ui.input_float3("##rot", &mut rot).build();
-
-            ui.text("Scale:");
-            let mut scale = [1.0f32, 1.0, 1.0];
-            ui.input_float3("##scale", &mut scale).build();
-        }
-
-        // Mesh Renderer component
-        if ui.collapsing_header("Mesh Renderer", TreeNodeFlags::empty()) {
-            ui.text("Material: Default");
-            if ui.button("Browse...") {}
+    fn render_inspector(ui: &Ui, ctx: Option<&mut PanelContext>) {
+        match ctx {
+            Some(ctx) => ctx.inspector.render(ui, ctx.scene, ctx.history, ctx.selection),
+            None => ui.text_colored(PulsarTheme::TEXT_MUTED, "No scene open"),
         }
     }
 
-    fn render_hierarchy(ui: &Ui, _content_region: [f32; 2]) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "📋 Hierarchy");
-        ui.separator();
-
-        // Scene tree
-        if let Some(_token) = ui.tree_node("Scene") {
-            if let Some(_token) = ui.tree_node("Player") {
-                ui.tree_node_leaf("Mesh");
-                ui.tree_node_leaf("Collider");
-                ui.tree_node_leaf("Script");
-            }
-            if let Some(_token) = ui.tree_node("Environment") {
-                ui.tree_node_leaf("Terrain");
-                ui.tree_node_leaf("Sky");
-                if let Some(_token) = ui.tree_node("Lighting") {
-                    ui.tree_node_leaf("Sun");
-                    ui.tree_node_leaf("Ambient");
-                }
-            }
+    fn render_hierarchy(ui: &Ui, ctx: Option<&mut PanelContext>) {
+        match ctx {
+            Some(ctx) => ctx.hierarchy.render(ui, ctx.scene, ctx.history, ctx.selection),
+            None => ui.text_colored(PulsarTheme::TEXT_MUTED, "No scene open"),
         }
     }
 
-    fn render_console(ui: &Ui, content_region: [f32; 2]) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "💻 Console");
-        ui.separator();
-
-        // Console output
-        let child_size = [content_region[0] - 20.0, content_region[1] - 100.0];
-        ui.child_window("console_output")
-            .size(child_size)
-            .build(|| {
-                ui.text_colored([0.5, 1.0, 0.5, 1.0], "[INFO] Pulsar Engine initialized");
-                ui.text_colored([1.0, 1.0, 0.5, 1.0], "[WARN] Missing texture: default.png");
-                ui.text_colored([1.0, 0.5, 0.5, 1.0], "[ERROR] Failed to load script: test.rs");
-                ui.text_colored([0.5, 1.0, 0.5, 1.0], "[INFO] Level loaded successfully");
-            });
-
-        // Command input
-        let mut command = String::new();
-        ui.input_text("Command", &mut command).build();
-    }
-
     fn render_profiler(ui: &Ui, content_region: [f32; 2]) {
         ui.text_colored(PulsarTheme::TEXT_PRIMARY, "📊 Profiler");
         ui.separator();
@@ -405,7 +326,7 @@ impl EditorPanelRenderer {
 
         ui.text("Sun Light");
         let mut sun_color = [1.0f32, 0.95, 0.8, 1.0];
-        ui.color_edit3("Color", &mut sun_color);
+        ui.color_edit4("Color", &mut sun_color);
 
         let mut sun_intensity = 1.0f32;
         ui.slider("Intensity", 0.0, 5.0, &mut sun_intensity);
@@ -413,7 +334,7 @@ impl EditorPanelRenderer {
         ui.spacing();
         ui.text("Ambient");
         let mut ambient = [0.2f32, 0.2, 0.3, 1.0];
-        ui.color_edit3("Ambient Color", &mut ambient);
+        ui.color_edit4("Ambient Color", &mut ambient);
     }
 
     fn render_terrain_editor(ui: &Ui, content_region: [f32; 2]) {
@@ -448,7 +369,7 @@ impl EditorPanelRenderer {
         ui.text("Weather Type:");
         let weather_types = ["Clear", "Cloudy", "Rain", "Storm", "Snow", "Fog"];
         let mut current_weather = 0;
-        ui.combo("##weather", &mut current_weather, &weather_types, |item| item.into());
+        ui.combo("##weather", &mut current_weather, &weather_types, |item| (*item).into());
 
         ui.text("Intensity:");
         let mut intensity = 0.5f32;
@@ -0,0 +1,178 @@
+use imgui::*;
+use crate::level_editor::LevelEditor;
+use crate::tab_system::{TabContent, TabSystem, TextEditor};
+use crate::ui::scene_editor::SceneEditor;
+use crate::ui::theme::PulsarTheme;
+
+/// Tab type of the 3D level editor, which File > New/Open Project create
+pub const LEVEL_EDITOR: &str = "Level Editor";
+
+/// Register the built-in editor tab types, in the order the "Add Tab" list shows them
+pub fn register_editor_tabs(tabs: &mut TabSystem) {
+    tabs.register_tab_type(LEVEL_EDITOR, "🌍", "Design and build game levels", || Box::new(SceneEditor::new()));
+    tabs.register_tab_type("Tile Map Editor", "🎮", "Paint 2D tile layers for a level", || {
+        Box::new(LevelEditor::new("Untitled Tiles".to_string()))
+    });
+    tabs.register_tab_type("Text Editor", "📝", "Edit plain text", || Box::new(TextEditor::new("Untitled".to_string())));
+
+    let previews: [(&'static str, &'static str, &str, fn(&Ui)); 9] = [
+        ("Script Editor", "📜", "Write and debug game scripts", script_editor),
+        ("Blueprint Editor", "🔧", "Visual scripting system", blueprint_editor),
+        ("Material Editor", "🎨", "Create and edit materials", material_editor),
+        ("Animation Editor", "🎬", "Create character animations", animation_editor),
+        ("Particle Editor", "✨", "Design particle effects", particle_editor),
+        ("Audio Editor", "🔊", "Edit and manage audio", audio_editor),
+        ("Terrain Editor", "🏔️", "Sculpt and paint terrain", terrain_editor),
+        ("Physics Debug", "⚡", "Debug physics simulation", physics_debug),
+        ("Profiler", "📊", "Performance monitoring", profiler),
+    ];
+    for (title, icon, description, content) in previews {
+        tabs.register_tab_type(title, icon, description, move || Box::new(PreviewTab { title, icon, content }));
+    }
+}
+
+/// Mock-up of an editor that isn't implemented yet
+struct PreviewTab {
+    title: &'static str,
+    icon: &'static str,
+    content: fn(&Ui),
+}
+
+impl TabContent for PreviewTab {
+    fn render(&mut self, ui: &Ui) {
+        (self.content)(ui);
+    }
+
+    fn get_title(&self) -> &str {
+        self.title
+    }
+
+    fn is_dirty(&self) -> bool {
+        false
+    }
+
+    fn get_icon(&self) -> Option<&str> {
+        Some(self.icon)
+    }
+}
+
+fn script_editor(ui: &Ui) {
+    ui.text_colored(PulsarTheme::TEXT_PRIMARY, "📜 Script Editor");
+    ui.separator();
+
+    ui.text("Write and debug game scripts in Rust");
+    ui.spacing();
+
+    // Code editor placeholder
+    let mut code = String::from("// Pulsar Engine Script\nfn main() {\n    println!(\"Hello, Pulsar!\");\n}");
+    ui.input_text_multiline("##code", &mut code, [0.0, 300.0]).build();
+}
+
+fn blueprint_editor(ui: &Ui) {
+    ui.text_colored(PulsarTheme::TEXT_PRIMARY, "🔧 Blueprint Editor");
+    ui.separator();
+
+    ui.text("Visual scripting system for game logic");
+    ui.spacing();
+
+    // Node graph placeholder
+    let pos = ui.cursor_screen_pos();
+    let size = [600.0, 400.0];
+
+    ui.get_window_draw_list()
+        .add_rect(pos, [pos[0] + size[0], pos[1] + size[1]], PulsarTheme::PURE_BLACK)
+        .filled(true)
+        .build();
+
+    ui.get_window_draw_list()
+        .add_rect(pos, [pos[0] + size[0], pos[1] + size[1]], PulsarTheme::SUBTLE_BORDER)
+        .build();
+
+    ui.dummy(size);
+}
+
+fn material_editor(ui: &Ui) {
+    ui.text_colored(PulsarTheme::TEXT_PRIMARY, "🎨 Material Editor");
+    ui.separator();
+
+    // Material properties
+    let mut albedo = [1.0f32, 1.0, 1.0, 1.0];
+    ui.color_edit4("Albedo", &mut albedo);
+
+    let mut metallic = 0.0f32;
+    ui.slider("Metallic", 0.0, 1.0, &mut metallic);
+
+    let mut roughness = 0.5f32;
+    ui.slider("Roughness", 0.0, 1.0, &mut roughness);
+}
+
+fn animation_editor(ui: &Ui) {
+    ui.text_colored(PulsarTheme::TEXT_PRIMARY, "🎬 Animation Editor");
+    ui.separator();
+
+    // Timeline
+    let mut timeline = 0.0f32;
+    ui.slider("Timeline", 0.0, 10.0, &mut timeline);
+
+    ui.spacing();
+    ui.text("Animation Tracks:");
+    ui.bullet_text("Walk Cycle");
+    ui.bullet_text("Run Cycle");
+    ui.bullet_text("Attack Animation");
+}
+
+fn particle_editor(ui: &Ui) {
+    ui.text_colored(PulsarTheme::TEXT_PRIMARY, "✨ Particle Editor");
+    ui.separator();
+
+    let mut emission_rate = 100.0f32;
+    ui.slider("Emission Rate", 1.0, 1000.0, &mut emission_rate);
+
+    let mut lifetime = 2.0f32;
+    ui.slider("Particle Lifetime", 0.1, 10.0, &mut lifetime);
+}
+
+fn audio_editor(ui: &Ui) {
+    ui.text_colored(PulsarTheme::TEXT_PRIMARY, "🔊 Audio Editor");
+    ui.separator();
+
+    let mut master_volume = 0.8f32;
+    ui.slider("Master Volume", 0.0, 1.0, &mut master_volume);
+
+    let mut music_volume = 0.6f32;
+    ui.slider("Music Volume", 0.0, 1.0, &mut music_volume);
+}
+
+fn terrain_editor(ui: &Ui) {
+    ui.text_colored(PulsarTheme::TEXT_PRIMARY, "🏔️ Terrain Editor");
+    ui.separator();
+
+    let mut brush_size = 10.0f32;
+    ui.slider("Brush Size", 1.0, 50.0, &mut brush_size);
+
+    let mut strength = 0.5f32;
+    ui.slider("Strength", 0.0, 1.0, &mut strength);
+}
+
+fn physics_debug(ui: &Ui) {
+    ui.text_colored(PulsarTheme::TEXT_PRIMARY, "⚡ Physics Debug");
+    ui.separator();
+
+    ui.checkbox("Show Colliders", &mut true);
+    ui.checkbox("Show Velocities", &mut false);
+    ui.checkbox("Show Forces", &mut false);
+}
+
+fn profiler(ui: &Ui) {
+    ui.text_colored(PulsarTheme::TEXT_PRIMARY, "📊 Profiler");
+    ui.separator();
+
+    ui.text("CPU Usage: 45%");
+    ProgressBar::new(0.45).build(ui);
+
+    ui.text("GPU Usage: 62%");
+    ProgressBar::new(0.62).build(ui);
+
+    ui.text("Memory: 1.2GB / 8.0GB");
+    ProgressBar::new(0.15).build(ui);
+}
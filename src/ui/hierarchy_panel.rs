@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use imgui::*;
+use crate::history::{Command, CommandGroup, History};
+use crate::keybindings::{self, KeyScope};
+use crate::scene::{DeleteEntity, EntityId, RenameEntity, ReparentEntity, Scene, SelectMode, Selection, SpawnEntity, Transform};
+use crate::ui::editor_actions;
+use crate::ui::theme::PulsarTheme;
+
+const ENTITY_PAYLOAD: &str = "SCENE_ENTITY";
+
+/// Edits requested by the hierarchy tree, applied after the tree has been drawn
+#[derive(Debug, Clone)]
+pub enum HierarchyAction {
+    Select(Option<EntityId>, SelectMode),
+    Create { parent: Option<EntityId> },
+    Rename(EntityId, String),
+    Reparent { entity: EntityId, parent: Option<EntityId> },
+    Delete(Vec<EntityId>),
+}
+
+/// Interactive scene tree shared by the Hierarchy windows
+pub struct HierarchyPanel {
+    renaming: Option<EntityId>,
+    rename_buffer: String,
+    focus_rename: bool,
+    context_entity: Option<EntityId>,
+    open_context_menu: bool,
+    label_colors: HashMap<EntityId, [f32; 4]>,
+}
+
+impl HierarchyPanel {
+    pub fn new() -> Self {
+        Self {
+            renaming: None,
+            rename_buffer: String::new(),
+            focus_rename: false,
+            context_entity: None,
+            open_context_menu: false,
+            label_colors: HashMap::new(),
+        }
+    }
+
+    /// Draw these entities' names in a different color, e.g. to flag play mode changes
+    pub fn set_label_colors(&mut self, colors: HashMap<EntityId, [f32; 4]>) {
+        self.label_colors = colors;
+    }
+
+    /// Draw the tree and record any edits in `history`
+    pub fn render(&mut self, ui: &Ui, scene: &mut Scene, history: &mut History<Scene>, selection: &mut Selection) {
+        let mut actions = Vec::new();
+
+        // The entity being renamed may have been removed by undo
+        if self.renaming.map_or(false, |id| !scene.contains(id)) {
+            self.renaming = None;
+        }
+
+        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "🌳 Scene Hierarchy");
+        ui.same_line();
+        let add_width = ui.calc_text_size("+")[0] + 24.0;
+        ui.set_cursor_pos([ui.window_content_region_max()[0] - add_width, ui.cursor_pos()[1]]);
+        if ui.small_button("+##create_entity") {
+            actions.push(HierarchyAction::Create { parent: None });
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Create empty entity");
+        }
+        ui.separator();
+
+        // Delete and rename shortcuts apply while the hierarchy has focus
+        let focused = ui.is_window_focused_with_flags(WindowFocusedFlags::ROOT_AND_CHILD_WINDOWS);
+        if focused && self.renaming.is_none() {
+            keybindings::claim_scope(KeyScope::Hierarchy);
+        }
+
+        ui.child_window("HierarchyTree")
+            .size([0.0, 0.0])
+            .border(false)
+            .build(|| {
+                ui.text_colored(PulsarTheme::TEXT_MUTED, &format!("📁 {}", scene.name));
+
+                for &root in scene.roots() {
+                    self.render_node(ui, scene, root, selection, &mut actions);
+                }
+
+                // Empty space below the tree: clicking clears the selection,
+                // dropping an entity here moves it to the root level
+                let avail = ui.content_region_avail();
+                ui.invisible_button("##hierarchy_background", [avail[0].max(1.0), avail[1].max(24.0)]);
+                if ui.is_item_clicked() {
+                    actions.push(HierarchyAction::Select(None, select_mode(ui)));
+                }
+                if ui.is_item_clicked_with_button(MouseButton::Right) {
+                    self.context_entity = None;
+                    self.open_context_menu = true;
+                }
+                if let Some(target) = ui.drag_drop_target() {
+                    if let Some(Ok(payload)) = target.accept_payload::<EntityId, _>(ENTITY_PAYLOAD, DragDropFlags::empty()) {
+                        actions.push(HierarchyAction::Reparent { entity: payload.data, parent: None });
+                    }
+                    target.pop();
+                }
+
+                if self.open_context_menu {
+                    ui.open_popup("hierarchy_context_menu");
+                    self.open_context_menu = false;
+                }
+
+                ui.popup("hierarchy_context_menu", || {
+                    match self.context_entity {
+                        Some(id) => {
+                            if ui.menu_item("➕ Create Child") {
+                                actions.push(HierarchyAction::Create { parent: Some(id) });
+                            }
+                            let rename_shortcut = keybindings::shortcut_label(editor_actions::HIERARCHY_RENAME).unwrap_or_default();
+                            if ui.menu_item_config("✏️ Rename").shortcut(rename_shortcut).build() {
+                                if let Some(entity) = scene.get(id) {
+                                    self.begin_rename(id, &entity.name.clone());
+                                }
+                            }
+                            let has_parent = scene.get(id).and_then(|e| e.parent()).is_some();
+                            if ui.menu_item_config("⬆ Unparent").enabled(has_parent).build() {
+                                actions.push(HierarchyAction::Reparent { entity: id, parent: None });
+                            }
+                            ui.separator();
+                            let delete_shortcut = keybindings::shortcut_label(editor_actions::HIERARCHY_DELETE).unwrap_or_default();
+                            if ui.menu_item_config("🗑 Delete").shortcut(delete_shortcut).build() {
+                                // Deleting a selected entity deletes the whole selection
+                                let targets = if selection.contains(id) { selection.roots(scene) } else { vec![id] };
+                                actions.push(HierarchyAction::Delete(targets));
+                            }
+                        }
+                        None => {
+                            if ui.menu_item("➕ Create Entity") {
+                                actions.push(HierarchyAction::Create { parent: None });
+                            }
+                        }
+                    }
+                });
+            });
+
+        self.apply_actions(scene, history, selection, actions);
+    }
+
+    fn render_node(
+        &mut self,
+        ui: &Ui,
+        scene: &Scene,
+        id: EntityId,
+        selection: &Selection,
+        actions: &mut Vec<HierarchyAction>,
+    ) {
+        let Some(entity) = scene.get(id) else { return };
+
+        // Inline rename field replaces the tree node label
+        if self.renaming == Some(id) {
+            if self.focus_rename {
+                ui.set_keyboard_focus_here();
+                self.focus_rename = false;
+            }
+            ui.set_next_item_width(-1.0);
+            let committed = ui.input_text(&format!("##rename{}", id.0), &mut self.rename_buffer)
+                .enter_returns_true(true)
+                .auto_select_all(true)
+                .build();
+
+            if ui.is_key_pressed(Key::Escape) {
+                self.renaming = None;
+            } else if committed || ui.is_item_deactivated() {
+                actions.push(HierarchyAction::Rename(id, self.rename_buffer.clone()));
+                self.renaming = None;
+            }
+
+            ui.indent();
+            for &child in entity.children() {
+                self.render_node(ui, scene, child, selection, actions);
+            }
+            ui.unindent();
+            return;
+        }
+
+        let mut flags = TreeNodeFlags::OPEN_ON_ARROW
+            | TreeNodeFlags::SPAN_AVAIL_WIDTH
+            | TreeNodeFlags::DEFAULT_OPEN;
+        if entity.children().is_empty() {
+            flags |= TreeNodeFlags::LEAF;
+        }
+        if selection.contains(id) {
+            flags |= TreeNodeFlags::SELECTED;
+        }
+
+        let label = format!("{} {}##entity{}", entity.icon(), entity.name, id.0);
+        let label_color = self.label_colors.get(&id).map(|&color| ui.push_style_color(StyleColor::Text, color));
+        let token = ui.tree_node_config(&label).flags(flags).push();
+        drop(label_color);
+
+        if ui.is_item_clicked() && !ui.is_item_toggled_open() {
+            actions.push(HierarchyAction::Select(Some(id), select_mode(ui)));
+        }
+        if ui.is_item_hovered() && ui.is_mouse_double_clicked(MouseButton::Left) {
+            self.begin_rename(id, &entity.name);
+        }
+        if ui.is_item_clicked_with_button(MouseButton::Right) {
+            // Keep a multi-selection so its context menu can act on all of it
+            if !selection.contains(id) {
+                actions.push(HierarchyAction::Select(Some(id), SelectMode::Replace));
+            }
+            self.context_entity = Some(id);
+            self.open_context_menu = true;
+        }
+
+        // Drag an entity onto another one to reparent it
+        if let Some(tooltip) = ui.drag_drop_source_config(ENTITY_PAYLOAD).begin_payload(id) {
+            ui.text(&format!("{} {}", entity.icon(), entity.name));
+            tooltip.end();
+        }
+        if let Some(target) = ui.drag_drop_target() {
+            if let Some(Ok(payload)) = target.accept_payload::<EntityId, _>(ENTITY_PAYLOAD, DragDropFlags::empty()) {
+                if !scene.is_ancestor(payload.data, id) {
+                    actions.push(HierarchyAction::Reparent { entity: payload.data, parent: Some(id) });
+                }
+            }
+            target.pop();
+        }
+
+        if let Some(_token) = token {
+            for &child in entity.children() {
+                self.render_node(ui, scene, child, selection, actions);
+            }
+        }
+    }
+
+    /// Run a Hierarchy shortcut action on the selection
+    pub fn run_action(&mut self, id: &str, scene: &mut Scene, history: &mut History<Scene>, selection: &mut Selection) {
+        if self.renaming.is_some() {
+            return;
+        }
+        match id {
+            editor_actions::HIERARCHY_RENAME => {
+                if let Some(entity) = selection.primary().and_then(|id| scene.get(id)) {
+                    self.begin_rename(entity.id(), &entity.name.clone());
+                }
+            }
+            editor_actions::HIERARCHY_DELETE if !selection.is_empty() => {
+                let targets = selection.roots(scene);
+                self.apply_actions(scene, history, selection, vec![HierarchyAction::Delete(targets)]);
+            }
+            _ => {}
+        }
+    }
+
+    fn begin_rename(&mut self, id: EntityId, current_name: &str) {
+        self.renaming = Some(id);
+        self.rename_buffer = current_name.to_string();
+        self.focus_rename = true;
+    }
+
+    fn apply_actions(
+        &mut self,
+        scene: &mut Scene,
+        history: &mut History<Scene>,
+        selection: &mut Selection,
+        actions: Vec<HierarchyAction>,
+    ) {
+        for action in actions {
+            match action {
+                HierarchyAction::Select(id, mode) => selection.click(id, mode),
+                HierarchyAction::Create { parent } => {
+                    let id = scene.spawn("New Entity", parent);
+                    scene.add_component(id, Transform::default());
+                    history.push(Box::new(SpawnEntity::new(id)));
+                    selection.select(id);
+                    self.begin_rename(id, "New Entity");
+                }
+                HierarchyAction::Rename(id, name) => {
+                    if let Some(command) = RenameEntity::new(scene, id, &name) {
+                        history.execute(scene, Box::new(command));
+                    }
+                }
+                HierarchyAction::Reparent { entity, parent } => {
+                    if let Some(command) = ReparentEntity::new(scene, entity, parent) {
+                        history.execute(scene, Box::new(command));
+                    }
+                }
+                HierarchyAction::Delete(ids) => {
+                    // Each delete is applied before the next is built so the
+                    // recorded sibling indices restore correctly in reverse
+                    let mut commands: Vec<Box<dyn Command<Scene>>> = Vec::new();
+                    for id in ids {
+                        if self.renaming.map_or(false, |r| scene.is_ancestor(id, r)) {
+                            self.renaming = None;
+                        }
+                        if let Some(mut command) = DeleteEntity::new(scene, id) {
+                            command.apply(scene);
+                            commands.push(Box::new(command));
+                        }
+                    }
+                    selection.retain_existing(scene);
+                    match commands.len() {
+                        0 => {}
+                        1 => history.push(commands.pop().unwrap()),
+                        count => history.push(Box::new(CommandGroup::new(format!("Delete {} Entities", count), commands))),
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn select_mode(ui: &Ui) -> SelectMode {
+    let io = ui.io();
+    SelectMode::from_modifiers(io.key_shift, io.key_ctrl)
+}
+
+impl Default for HierarchyPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,317 @@
+use imgui::*;
+use crate::history::{Command, CommandGroup, History};
+use crate::scene::{
+    AddComponent, Component, ComponentRegistry, EntityId, FieldInfo, FieldValue, RemoveComponent, RenameEntity, Scene,
+    Selection, SetField, SetFields,
+};
+use crate::ui::theme::PulsarTheme;
+
+/// Reflection-driven editor for the components of the selected entities
+pub struct InspectorPanel {
+    registry: ComponentRegistry,
+    name_buffer: String,
+    name_entity: Option<EntityId>,
+    component_search: String,
+}
+
+impl InspectorPanel {
+    pub fn new() -> Self {
+        Self {
+            registry: ComponentRegistry::default(),
+            name_buffer: String::new(),
+            name_entity: None,
+            component_search: String::new(),
+        }
+    }
+
+    /// Draw the Inspector for the current selection and record edits in `history`
+    pub fn render(&mut self, ui: &Ui, scene: &mut Scene, history: &mut History<Scene>, selection: &Selection) {
+        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "🔍 Inspector");
+        ui.separator();
+
+        let ids: Vec<EntityId> = selection.iter().filter(|id| scene.contains(*id)).collect();
+        match ids.as_slice() {
+            [] => {
+                ui.text("Selected: None");
+                ui.spacing();
+                ui.text_colored(PulsarTheme::TEXT_MUTED, "Select an entity in the Hierarchy to edit it");
+                self.name_entity = None;
+            }
+            [id] => self.render_entity(ui, scene, history, *id),
+            _ => self.render_multiple(ui, scene, history, &ids),
+        }
+    }
+
+    fn render_entity(&mut self, ui: &Ui, scene: &mut Scene, history: &mut History<Scene>, id: EntityId) {
+        // Edits are collected while the entity is borrowed and executed at the end
+        let mut commands: Vec<Box<dyn Command<Scene>>> = Vec::new();
+
+        // Keep the name buffer in sync with the selection
+        if self.name_entity != Some(id) {
+            self.name_entity = Some(id);
+            self.name_buffer = scene.get(id).map(|e| e.name.clone()).unwrap_or_default();
+        }
+
+        ui.text("Name:");
+        ui.set_next_item_width(-1.0);
+        let committed = ui.input_text("##entity_name", &mut self.name_buffer)
+            .enter_returns_true(true)
+            .build();
+        if committed || ui.is_item_deactivated_after_edit() {
+            match RenameEntity::new(scene, id, &self.name_buffer) {
+                Some(command) => commands.push(Box::new(command)),
+                None => self.name_buffer = scene.get(id).map(|e| e.name.clone()).unwrap_or_default(),
+            }
+        } else if !ui.is_item_active() {
+            // Pick up renames made elsewhere (e.g. the Hierarchy)
+            if let Some(entity) = scene.get(id) {
+                if entity.name != self.name_buffer {
+                    self.name_buffer = entity.name.clone();
+                }
+            }
+        }
+        ui.text_colored(PulsarTheme::TEXT_MUTED, &format!("ID: {}", id.0));
+        ui.spacing();
+
+        let entity = scene.get(id).unwrap();
+        let mut remove_index = None;
+
+        for (index, component) in entity.components.iter().enumerate() {
+            let header = format!("{} {}##component{}", component.icon(), component.type_name(), index);
+            let open = ui.collapsing_header(&header, TreeNodeFlags::DEFAULT_OPEN);
+
+            if component.removable() {
+                let menu_id = format!("component_menu{}", index);
+                if ui.is_item_clicked_with_button(MouseButton::Right) {
+                    ui.open_popup(&menu_id);
+                }
+                ui.popup(&menu_id, || {
+                    if ui.menu_item("🗑 Remove Component") {
+                        remove_index = Some(index);
+                    }
+                });
+            }
+
+            if open {
+                let _id = ui.push_id(&format!("component{}", index));
+                for field in component.fields() {
+                    if let Some(value) = component.get_field(field.name) {
+                        if let Some(new_value) = Self::field_widget(ui, field, value.clone()) {
+                            commands.push(Box::new(SetField {
+                                entity: id,
+                                component: component.type_name(),
+                                field: field.name,
+                                label: field.label,
+                                old: value,
+                                new: new_value,
+                            }));
+                        }
+                    }
+                }
+
+                if component.removable() {
+                    let _remove_color = ui.push_style_color(StyleColor::Button, [0.6, 0.2, 0.2, 0.8]);
+                    if ui.small_button("Remove Component") {
+                        remove_index = Some(index);
+                    }
+                }
+                ui.spacing();
+            }
+        }
+
+        if let Some(command) = remove_index.and_then(|index| RemoveComponent::new(scene, id, index)) {
+            commands.push(Box::new(command));
+        }
+
+        ui.separator();
+
+        let chosen = self.render_add_component(ui, |name| !entity.components.iter().any(|c| c.type_name() == name));
+        if let Some(component) = chosen.and_then(|name| self.registry.create(name)) {
+            commands.push(Box::new(AddComponent::new(id, component)));
+        }
+
+        for command in commands {
+            history.execute(scene, command);
+        }
+    }
+
+    /// Edit the components every selected entity has. A changed field is written to
+    /// all of them as one undo step.
+    fn render_multiple(&mut self, ui: &Ui, scene: &mut Scene, history: &mut History<Scene>, ids: &[EntityId]) {
+        self.name_entity = None;
+        let primary = *ids.last().unwrap();
+        ui.text(&format!("{} entities selected", ids.len()));
+        ui.text_colored(PulsarTheme::TEXT_MUTED, "Showing components shared by all of them");
+        ui.spacing();
+
+        let mut commands: Vec<Box<dyn Command<Scene>>> = Vec::new();
+        let mut remove_type = None;
+        let entity = scene.get(primary).unwrap();
+
+        for (index, component) in entity.components.iter().enumerate() {
+            let type_name = component.type_name();
+            let shared: Vec<&dyn Component> = ids
+                .iter()
+                .filter_map(|id| scene.get(*id)?.components.iter().find(|c| c.type_name() == type_name))
+                .map(|c| c.as_ref())
+                .collect();
+            if shared.len() != ids.len() {
+                continue;
+            }
+
+            let header = format!("{} {}##component{}", component.icon(), type_name, index);
+            let open = ui.collapsing_header(&header, TreeNodeFlags::DEFAULT_OPEN);
+            if !open {
+                continue;
+            }
+
+            let _id = ui.push_id(&format!("component{}", index));
+            for field in component.fields() {
+                let Some(shown) = component.get_field(field.name) else { continue };
+                let values: Vec<FieldValue> = shared.iter().filter_map(|c| c.get_field(field.name)).collect();
+                if values.len() != ids.len() {
+                    continue;
+                }
+
+                if let Some(edited) = Self::field_widget(ui, field, shown.clone()) {
+                    let values = ids
+                        .iter()
+                        .zip(values.iter())
+                        .map(|(id, old)| (*id, old.clone(), apply_edit(&shown, &edited, old)))
+                        .collect();
+                    commands.push(Box::new(SetFields {
+                        component: type_name,
+                        field: field.name,
+                        label: field.label,
+                        values,
+                    }));
+                }
+                if values.iter().any(|v| *v != shown) {
+                    ui.text_colored(PulsarTheme::TEXT_MUTED, "  (mixed values)");
+                }
+            }
+
+            if component.removable() {
+                let _remove_color = ui.push_style_color(StyleColor::Button, [0.6, 0.2, 0.2, 0.8]);
+                if ui.small_button("Remove Component") {
+                    remove_type = Some(type_name);
+                }
+            }
+            ui.spacing();
+        }
+
+        if let Some(type_name) = remove_type {
+            let removals: Vec<Box<dyn Command<Scene>>> = ids
+                .iter()
+                .filter_map(|&id| {
+                    let index = scene.get(id)?.components.iter().position(|c| c.type_name() == type_name)?;
+                    RemoveComponent::new(scene, id, index).map(|c| Box::new(c) as Box<dyn Command<Scene>>)
+                })
+                .collect();
+            commands.push(Box::new(CommandGroup::new(format!("Remove {}", type_name), removals)));
+        }
+
+        ui.separator();
+
+        // Offer components that at least one selected entity is missing
+        let has_component = |id: &EntityId, name: &str| {
+            scene.get(*id).map_or(true, |e| e.components.iter().any(|c| c.type_name() == name))
+        };
+        let chosen = self.render_add_component(ui, |name| !ids.iter().all(|id| has_component(id, name)));
+        if let Some(name) = chosen {
+            let additions: Vec<Box<dyn Command<Scene>>> = ids
+                .iter()
+                .filter(|id| !has_component(id, name))
+                .filter_map(|&id| Some(Box::new(AddComponent::new(id, self.registry.create(name)?)) as Box<dyn Command<Scene>>))
+                .collect();
+            commands.push(Box::new(CommandGroup::new(format!("Add {}", name), additions)));
+        }
+
+        for command in commands {
+            history.execute(scene, command);
+        }
+    }
+
+    /// "Add Component" button and search popup. Returns the component type picked this frame.
+    fn render_add_component(&mut self, ui: &Ui, is_available: impl Fn(&str) -> bool) -> Option<&'static str> {
+        {
+            let _btn_color = ui.push_style_color(StyleColor::Button, PulsarTheme::BLUE_PRIMARY);
+            let _hover_color = ui.push_style_color(StyleColor::ButtonHovered, PulsarTheme::BLUE_HOVER);
+            let width = ui.content_region_avail()[0];
+            if ui.button_with_size("➕ Add Component", [width, 28.0]) {
+                self.component_search.clear();
+                ui.open_popup("add_component_popup");
+            }
+        }
+
+        let mut chosen = None;
+        ui.popup("add_component_popup", || {
+            ui.input_text("##component_search", &mut self.component_search)
+                .hint("Search components...")
+                .build();
+            ui.separator();
+
+            let query = self.component_search.to_lowercase();
+            let mut any = false;
+            for (name, icon) in self.registry.iter() {
+                if !is_available(name) || (!query.is_empty() && !name.to_lowercase().contains(&query)) {
+                    continue;
+                }
+                any = true;
+                if ui.selectable(&format!("{} {}", icon, name)) {
+                    chosen = Some(name);
+                    ui.close_current_popup();
+                }
+            }
+
+            if !any {
+                ui.text_colored(PulsarTheme::TEXT_MUTED, "No matching components");
+            }
+        });
+        chosen
+    }
+
+    /// Draw the widget for one field, returning the new value if it was edited
+    fn field_widget(ui: &Ui, field: &FieldInfo, value: FieldValue) -> Option<FieldValue> {
+        match value {
+            FieldValue::Bool(mut v) => ui.checkbox(field.label, &mut v).then(|| FieldValue::Bool(v)),
+            FieldValue::Int(mut v) => Drag::new(field.label)
+                .build(ui, &mut v)
+                .then(|| FieldValue::Int(v)),
+            FieldValue::Float(mut v) => Drag::new(field.label)
+                .speed(0.05)
+                .build(ui, &mut v)
+                .then(|| FieldValue::Float(v)),
+            FieldValue::Vec3(mut v) => {
+                ui.text(&format!("{}:", field.label));
+                Drag::new(&format!("##{}", field.name))
+                    .speed(0.05)
+                    .build_array(ui, &mut v)
+                    .then(|| FieldValue::Vec3(v))
+            }
+            FieldValue::Color(mut v) => ui.color_edit3(field.label, &mut v).then(|| FieldValue::Color(v)),
+            FieldValue::Text(mut v) => ui.input_text(field.label, &mut v)
+                .build()
+                .then(|| FieldValue::Text(v)),
+        }
+    }
+}
+
+/// New value for one entity of a multi-edit. Vector lanes the user didn't touch keep
+/// the entity's own value, so dragging X doesn't flatten differing Y and Z values.
+fn apply_edit(shown: &FieldValue, edited: &FieldValue, current: &FieldValue) -> FieldValue {
+    let lanes = |shown: &[f32; 3], edited: &[f32; 3], current: &[f32; 3]| -> [f32; 3] {
+        std::array::from_fn(|i| if edited[i] != shown[i] { edited[i] } else { current[i] })
+    };
+    match (shown, edited, current) {
+        (FieldValue::Vec3(s), FieldValue::Vec3(e), FieldValue::Vec3(c)) => FieldValue::Vec3(lanes(s, e, c)),
+        (FieldValue::Color(s), FieldValue::Color(e), FieldValue::Color(c)) => FieldValue::Color(lanes(s, e, c)),
+        _ => edited.clone(),
+    }
+}
+
+impl Default for InspectorPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
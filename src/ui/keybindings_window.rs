@@ -0,0 +1,211 @@
+use imgui::*;
+use crate::actions::{self, ActionRegistry, Shortcut};
+use crate::keybindings::KeyScope;
+use crate::ui::theme::PulsarTheme;
+
+/// Edit > Keyboard Shortcuts: rebind actions, with conflicts highlighted
+pub struct KeybindingsWindow {
+    pub open: bool,
+    filter: String,
+    capture: Option<Capture>,
+}
+
+/// Waiting for the user to press the chord to bind
+struct Capture {
+    action: String,
+    add: bool, // bind alongside the existing shortcuts rather than replacing them
+}
+
+impl KeybindingsWindow {
+    pub fn new() -> Self {
+        Self { open: false, filter: String::new(), capture: None }
+    }
+
+    /// Whether key presses are being recorded, so shortcuts shouldn't run
+    pub fn is_capturing(&self) -> bool {
+        self.open && self.capture.is_some()
+    }
+
+    /// Draw the window if it is open. Returns true if any shortcut changed.
+    pub fn render(&mut self, ui: &Ui, actions: &mut ActionRegistry) -> bool {
+        if !self.open {
+            self.capture = None;
+            return false;
+        }
+        let mut changed = false;
+        if let Some(shortcut) = self.capture_shortcut(ui) {
+            if let Some(capture) = self.capture.take() {
+                let mut shortcuts = match actions.get(&capture.action) {
+                    Some(action) if capture.add => action.shortcuts.clone(),
+                    _ => Vec::new(),
+                };
+                if !shortcuts.contains(&shortcut) {
+                    shortcuts.push(shortcut);
+                }
+                actions.set_shortcuts(&capture.action, shortcuts);
+                changed = true;
+            }
+        }
+
+        let mut open = self.open;
+        let mut clear = None;
+        let mut reset = None;
+        let mut reset_all = false;
+        ui.window("⌨ Keyboard Shortcuts")
+            .opened(&mut open)
+            .size([760.0, 520.0], Condition::FirstUseEver)
+            .flags(WindowFlags::NO_COLLAPSE)
+            .build(|| {
+                let conflicts = actions.all_conflicts();
+                if !conflicts.is_empty() {
+                    ui.text_colored(
+                        PulsarTheme::WARNING,
+                        format!("⚠ {} shortcut(s) are bound to more than one action", conflicts.len()),
+                    );
+                }
+                ui.set_next_item_width(-110.0);
+                ui.input_text("##filter", &mut self.filter)
+                    .hint("🔍 Search actions or shortcuts")
+                    .build();
+                ui.same_line();
+                if ui.button_with_size("Reset All", [100.0, 0.0]) {
+                    reset_all = true;
+                }
+                if let Some(capture) = &self.capture {
+                    let name = actions.get(&capture.action).map(|a| a.full_name()).unwrap_or_default();
+                    ui.text_colored(PulsarTheme::BLUE_PRIMARY, format!("Press the shortcut for {}... (Escape cancels)", name));
+                }
+                ui.separator();
+
+                ui.child_window("##bindings").build(|| {
+                    ui.columns(4, "KeybindingColumns", false);
+                    ui.set_column_width(0, 300.0);
+                    ui.set_column_width(1, 100.0);
+                    ui.set_column_width(2, 180.0);
+                    for heading in ["Action", "Scope", "Shortcut", ""] {
+                        ui.text_colored(PulsarTheme::TEXT_SECONDARY, heading);
+                        ui.next_column();
+                    }
+                    ui.separator();
+
+                    let filter = self.filter.to_lowercase();
+                    for action in actions.actions() {
+                        let shortcuts: Vec<String> = action.shortcuts.iter().map(Shortcut::to_string).collect();
+                        let shortcut_text = shortcuts.join(", ");
+                        let matches = filter.is_empty()
+                            || action.full_name().to_lowercase().contains(&filter)
+                            || shortcut_text.to_lowercase().contains(&filter);
+                        if !matches {
+                            continue;
+                        }
+                        let _id = ui.push_id(&action.id);
+
+                        ui.text(action.full_name());
+                        ui.next_column();
+                        ui.text_colored(PulsarTheme::TEXT_MUTED, action.scope.display_name());
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(scope_help(action.scope));
+                        }
+                        ui.next_column();
+
+                        let conflicting = actions.conflicts(&action.id);
+                        if shortcut_text.is_empty() {
+                            ui.text_colored(PulsarTheme::TEXT_DISABLED, "-");
+                        } else if conflicting.is_empty() {
+                            ui.text(&shortcut_text);
+                        } else {
+                            ui.text_colored(PulsarTheme::WARNING, format!("⚠ {}", shortcut_text));
+                            if ui.is_item_hovered() {
+                                let names: Vec<String> = conflicting.iter().map(|a| a.full_name()).collect();
+                                ui.tooltip_text(format!("Also bound to: {}", names.join(", ")));
+                            }
+                        }
+                        ui.next_column();
+
+                        if ui.small_button("Set") {
+                            self.capture = Some(Capture { action: action.id.clone(), add: false });
+                        }
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text("Replace the shortcuts with the next one pressed");
+                        }
+                        ui.same_line();
+                        if ui.small_button("➕") {
+                            self.capture = Some(Capture { action: action.id.clone(), add: true });
+                        }
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text("Add another shortcut");
+                        }
+                        if !action.shortcuts.is_empty() {
+                            ui.same_line();
+                            if ui.small_button("✖") {
+                                clear = Some(action.id.clone());
+                            }
+                            if ui.is_item_hovered() {
+                                ui.tooltip_text("Remove the shortcuts");
+                            }
+                        }
+                        if actions.is_overridden(&action.id) {
+                            ui.same_line();
+                            if ui.small_button("↺") {
+                                reset = Some(action.id.clone());
+                            }
+                            if ui.is_item_hovered() {
+                                let defaults: Vec<String> = action.default_shortcuts.iter().map(Shortcut::to_string).collect();
+                                let defaults = if defaults.is_empty() { "none".to_string() } else { defaults.join(", ") };
+                                ui.tooltip_text(format!("Reset to the default ({})", defaults));
+                            }
+                        }
+                        ui.next_column();
+                    }
+                    ui.columns(1, "", false);
+                });
+            });
+        self.open = open;
+
+        if let Some(id) = clear {
+            actions.set_shortcuts(&id, Vec::new());
+            changed = true;
+        }
+        if let Some(id) = reset {
+            actions.reset_shortcuts(&id);
+            changed = true;
+        }
+        if reset_all {
+            actions.set_overrides(Default::default());
+            changed = true;
+        }
+        changed
+    }
+
+    /// The chord pressed while capturing, if any. Escape cancels the capture.
+    fn capture_shortcut(&mut self, ui: &Ui) -> Option<Shortcut> {
+        self.capture.as_ref()?;
+        if ui.is_key_pressed(Key::Escape) {
+            self.capture = None;
+            return None;
+        }
+        let io = ui.io();
+        let key = Key::VARIANTS
+            .iter()
+            .copied()
+            .find(|&key| actions::is_bindable(key) && ui.is_key_pressed_no_repeat(key))?;
+        Some(Shortcut { key, ctrl: io.key_ctrl, shift: io.key_shift, alt: io.key_alt })
+    }
+}
+
+impl Default for KeybindingsWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Shown in the scope column's tooltip
+fn scope_help(scope: KeyScope) -> &'static str {
+    match scope {
+        KeyScope::Global => "Works anywhere, except for keys a focused text field uses",
+        KeyScope::Viewport => "Works while the 3D viewport is hovered, ahead of global shortcuts",
+        KeyScope::Hierarchy => "Works while the Hierarchy panel has focus, ahead of global shortcuts",
+        KeyScope::AssetBrowser => "Works while the Asset Browser has focus, ahead of global shortcuts",
+        KeyScope::TextEditing => "Works while typing in a text field",
+    }
+}
@@ -1,10 +1,18 @@
 use imgui::*;
 use std::collections::HashMap;
-use crate::ui::{PulsarTheme, VisualEffects, EditorPanelType};
-
-/// Manages the overall layout and docking system for the Pulsar Engine UI
+use std::fs;
+use serde::{Deserialize, Serialize};
+use crate::session::{config_dir, write_ron};
+use crate::ui::{PulsarTheme, VisualEffects};
+use crate::ui::editor_panels::EditorPanelType;
+use crate::ui::panel_system::{DockArea, DockLayout, PanelManager};
+
+const LAYOUTS_FILE: &str = "layouts.ron";
+/// Presets that ship with the editor, in menu order. They can't be renamed, deleted or replaced.
+const BUILTIN_LAYOUTS: [&str; 4] = ["Default", "Code", "Art", "Debug"];
+
+/// Layout presets and the chrome around the docked panels (see `PanelManager`)
 pub struct LayoutManager {
-    pub dock_space_id: Id,
     pub main_menu_height: f32,
     pub status_bar_height: f32,
     pub sidebar_width: f32,
@@ -12,12 +20,31 @@ pub struct LayoutManager {
     pub fullscreen_mode: bool,
     pub saved_layouts: HashMap<String, LayoutPreset>,
     pub current_layout: String,
+    pub startup_layout: Option<String>, // None reopens the arrangement of the last session
+    pending_layout: Option<String>,     // applied once the size of the editor is known
+    save_as: Option<SaveAsState>,
+    show_manager: bool,
+    renaming: Option<(String, String)>, // preset being renamed and the name typed so far
+    focus_rename: bool,
+}
+
+/// Contents of the "Save Layout As" dialog
+struct SaveAsState {
+    name: String,
+    description: String,
+}
+
+/// User presets and the startup choice, as stored in the config directory
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct LayoutsFile {
+    startup_layout: Option<String>,
+    presets: Vec<LayoutPreset>,
 }
 
 impl LayoutManager {
     pub fn new() -> Self {
         Self {
-            dock_space_id: Id::new("MainDockSpace"),
             main_menu_height: 24.0,
             status_bar_height: 20.0,
             sidebar_width: 250.0,
@@ -25,7 +52,58 @@ impl LayoutManager {
             fullscreen_mode: false,
             saved_layouts: Self::create_default_layouts(),
             current_layout: "Default".to_string(),
+            startup_layout: None,
+            pending_layout: None,
+            save_as: None,
+            show_manager: false,
+            renaming: None,
+            focus_rename: false,
+        }
+    }
+
+    /// The built-in presets plus those the user saved. The startup preset, if any,
+    /// is applied on the first `update`.
+    pub fn load() -> Self {
+        let mut manager = Self::new();
+        let path = config_dir().join(LAYOUTS_FILE);
+        let file: LayoutsFile = match fs::read_to_string(&path) {
+            Ok(text) => ron::de::from_str(&text).unwrap_or_else(|e| {
+                log::warn!("Ignoring corrupt layouts file {}: {}", path.display(), e);
+                LayoutsFile::default()
+            }),
+            Err(_) => LayoutsFile::default(),
+        };
+        for preset in file.presets {
+            if !is_builtin(&preset.name) {
+                manager.saved_layouts.insert(preset.name.clone(), preset);
+            }
         }
+        manager.startup_layout = file.startup_layout.filter(|name| manager.saved_layouts.contains_key(name));
+        manager.pending_layout = manager.startup_layout.clone();
+        manager
+    }
+
+    /// Write the user presets and startup choice to disk
+    fn save_presets(&self) {
+        let mut presets: Vec<LayoutPreset> = self.saved_layouts
+            .values()
+            .filter(|preset| !is_builtin(&preset.name))
+            .cloned()
+            .collect();
+        presets.sort_by(|a, b| a.name.cmp(&b.name));
+        let file = LayoutsFile { startup_layout: self.startup_layout.clone(), presets };
+
+        let path = config_dir().join(LAYOUTS_FILE);
+        if let Err(e) = write_ron(&path, &file) {
+            log::error!("Failed to save layouts to {}: {}", path.display(), e);
+        }
+    }
+
+    /// Built-in presets first, then the user's in alphabetical order
+    pub fn preset_names(&self) -> Vec<String> {
+        let mut user: Vec<String> = self.saved_layouts.keys().filter(|name| !is_builtin(name)).cloned().collect();
+        user.sort();
+        BUILTIN_LAYOUTS.iter().map(|name| name.to_string()).chain(user).collect()
     }
 
     /// Create default layout presets
@@ -79,34 +157,6 @@ impl LayoutManager {
         layouts
     }
 
-    /// Setup the main docking layout
-    pub fn begin_dockspace(&self, ui: &Ui) {
-        let viewport = ui.main_viewport();
-        let work_pos = viewport.work_pos;
-        let work_size = viewport.work_size;
-
-        // Full window dockspace
-        ui.set_next_window_pos(work_pos, Condition::Always);
-        ui.set_next_window_size(work_size, Condition::Always);
-
-        let window_flags = WindowFlags::NO_TITLE_BAR
-            | WindowFlags::NO_COLLAPSE
-            | WindowFlags::NO_RESIZE
-            | WindowFlags::NO_MOVE
-            | WindowFlags::NO_BRING_TO_FRONT_ON_FOCUS
-            | WindowFlags::NO_NAV_FOCUS
-            | WindowFlags::NO_BACKGROUND
-            | WindowFlags::NO_DOCKING;
-
-        ui.window("MainDockSpaceWindow")
-            .flags(window_flags)
-            .build(|| {
-                // Create main dockspace
-                let dockspace_flags = DockNodeFlags::PASSTHRU_CENTRAL_NODE;
-                ui.dockspace_over_main_viewport_with_flags(self.dock_space_id, dockspace_flags);
-            });
-    }
-
     /// Render the main menu bar
     pub fn render_main_menu(&mut self, ui: &Ui) -> bool {
         let mut should_quit = false;
@@ -142,17 +192,7 @@ impl LayoutManager {
 
             // View menu
             ui.menu("View", || {
-                ui.menu("Layout", || {
-                    for layout_name in self.saved_layouts.keys() {
-                        let is_current = layout_name == &self.current_layout;
-                        if ui.menu_item_config(layout_name).selected(is_current).build() {
-                            self.current_layout = layout_name.clone();
-                        }
-                    }
-                    ui.separator();
-                    if ui.menu_item("Save Layout As...") {}
-                    if ui.menu_item("Reset Layout") {}
-                });
+                ui.menu("Layout", || self.render_layout_menu(ui));
 
                 ui.separator();
 
@@ -204,7 +244,7 @@ impl LayoutManager {
             let fps_text = format!("FPS: {}", crate::frame_counter::get_fps());
             let fps_width = ui.calc_text_size(&fps_text)[0];
 
-            ui.set_cursor_pos_x(menu_bar_width - fps_width - 10.0);
+            ui.set_cursor_pos([menu_bar_width - fps_width - 10.0, ui.cursor_pos()[1]]);
             ui.text_colored(PulsarTheme::TEXT_SECONDARY, &fps_text);
         });
 
@@ -213,13 +253,7 @@ impl LayoutManager {
 
     /// Render the status bar
     pub fn render_status_bar(&self, ui: &Ui) {
-        let viewport = ui.main_viewport();
-        let work_pos = viewport.work_pos;
-        let work_size = viewport.work_size;
-
-        // Position at bottom
-        ui.set_next_window_pos([work_pos[0], work_pos[1] + work_size[1] - self.status_bar_height], Condition::Always);
-        ui.set_next_window_size([work_size[0], self.status_bar_height], Condition::Always);
+        let display_size = ui.io().display_size;
 
         let window_flags = WindowFlags::NO_TITLE_BAR
             | WindowFlags::NO_RESIZE
@@ -227,14 +261,17 @@ impl LayoutManager {
             | WindowFlags::NO_SCROLLBAR
             | WindowFlags::NO_COLLAPSE;
 
+        // Position at bottom
         ui.window("StatusBar")
+            .position([0.0, display_size[1] - self.status_bar_height], Condition::Always)
+            .size([display_size[0], self.status_bar_height], Condition::Always)
             .flags(window_flags)
             .build(|| {
                 // Status text
                 ui.text_colored(PulsarTheme::TEXT_SECONDARY, "Ready");
 
                 ui.same_line();
-                ui.set_cursor_pos_x(200.0);
+                ui.set_cursor_pos([200.0, ui.cursor_pos()[1]]);
                 ui.text_colored(PulsarTheme::TEXT_MUTED, "Scene: Untitled");
 
                 // Right-aligned info
@@ -242,75 +279,302 @@ impl LayoutManager {
                 let memory_text = "Memory: 256MB";
                 let memory_width = ui.calc_text_size(memory_text)[0];
 
-                ui.set_cursor_pos_x(status_width - memory_width - 10.0);
+                ui.set_cursor_pos([status_width - memory_width - 10.0, ui.cursor_pos()[1]]);
                 ui.text_colored(PulsarTheme::TEXT_MUTED, memory_text);
             });
     }
 
-    /// Apply a specific layout preset
-    pub fn apply_layout(&mut self, layout_name: &str) {
-        if let Some(layout) = self.saved_layouts.get(layout_name) {
+    /// Rearrange `panels` to a preset. `content_size` is the size of the area the
+    /// panels are docked in, which the preset's size ratios are relative to.
+    pub fn apply_layout(&mut self, layout_name: &str, panels: &mut PanelManager, content_size: [f32; 2]) {
+        if let Some(preset) = self.saved_layouts.get(layout_name) {
+            panels.set_layout(preset.to_dock_layout(content_size));
             self.current_layout = layout_name.to_string();
-            // Layout application would integrate with ImGui's docking system
-            // This would need to be implemented with ImGui's dock builder API
         }
     }
 
-    /// Get the current content area (excluding menu bar and status bar)
-    pub fn get_content_area(&self, ui: &Ui) -> [f32; 4] {
-        let viewport = ui.main_viewport();
-        let work_pos = viewport.work_pos;
-        let work_size = viewport.work_size;
+    /// Store the current arrangement of `panels` as a user preset, replacing one
+    /// with the same name
+    pub fn save_layout_as(&mut self, name: &str, description: &str, panels: &PanelManager, content_size: [f32; 2]) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Enter a name for the layout".to_string());
+        }
+        if is_builtin(name) {
+            return Err(format!("\"{}\" is a built-in layout", name));
+        }
+        let preset = LayoutPreset::capture(name, description, panels, content_size);
+        self.saved_layouts.insert(name.to_string(), preset);
+        self.current_layout = name.to_string();
+        self.save_presets();
+        Ok(())
+    }
 
-        [
-            work_pos[0],
-            work_pos[1] + self.main_menu_height,
-            work_size[0],
-            work_size[1] - self.main_menu_height - self.status_bar_height,
-        ]
+    pub fn rename_layout(&mut self, old_name: &str, new_name: &str) -> Result<(), String> {
+        let new_name = new_name.trim();
+        if is_builtin(old_name) {
+            return Err("Built-in layouts can't be renamed".to_string());
+        }
+        if new_name.is_empty() || new_name == old_name {
+            return Ok(());
+        }
+        if self.saved_layouts.contains_key(new_name) {
+            return Err(format!("A layout named \"{}\" already exists", new_name));
+        }
+        let Some(mut preset) = self.saved_layouts.remove(old_name) else { return Ok(()) };
+        preset.name = new_name.to_string();
+        self.saved_layouts.insert(new_name.to_string(), preset);
+        if self.current_layout == old_name {
+            self.current_layout = new_name.to_string();
+        }
+        if self.startup_layout.as_deref() == Some(old_name) {
+            self.startup_layout = Some(new_name.to_string());
+        }
+        self.save_presets();
+        Ok(())
     }
 
-    /// Render floating panel hints for drag and drop
-    pub fn render_drop_hints(&self, ui: &Ui, is_dragging: bool) {
-        if !is_dragging {
+    pub fn delete_layout(&mut self, name: &str) {
+        if is_builtin(name) || self.saved_layouts.remove(name).is_none() {
             return;
         }
+        if self.startup_layout.as_deref() == Some(name) {
+            self.startup_layout = None;
+        }
+        if self.current_layout == name {
+            self.current_layout = "Default".to_string();
+        }
+        self.save_presets();
+    }
 
-        let content_area = self.get_content_area(ui);
-        let center_x = content_area[0] + content_area[2] / 2.0;
-        let center_y = content_area[1] + content_area[3] / 2.0;
+    /// Pick the preset applied on launch, or None to reopen the last session's arrangement
+    pub fn set_startup_layout(&mut self, name: Option<String>) {
+        self.startup_layout = name.filter(|name| self.saved_layouts.contains_key(name));
+        self.save_presets();
+    }
 
-        // Central drop zone
-        let drop_size = 100.0;
-        let central_pos = [center_x - drop_size / 2.0, center_y - drop_size / 2.0];
+    /// The presets listed in the View > Layout menu, ahead of the layout actions
+    pub fn render_layout_menu(&mut self, ui: &Ui) {
+        for layout_name in self.preset_names() {
+            let is_current = layout_name == self.current_layout;
+            if ui.menu_item_config(&layout_name).selected(is_current).build() {
+                self.request_layout(&layout_name);
+            }
+            if let Some(preset) = self.saved_layouts.get(&layout_name) {
+                if ui.is_item_hovered() && !preset.description.is_empty() {
+                    ui.tooltip_text(&preset.description);
+                }
+            }
+        }
+    }
+
+    /// Apply a preset at the start of the next `update`
+    pub fn request_layout(&mut self, name: &str) {
+        self.pending_layout = Some(name.to_string());
+    }
+
+    /// Undo changes made since the current preset was applied
+    pub fn reset_layout(&mut self) {
+        self.pending_layout = Some(self.current_layout.clone());
+    }
+
+    pub fn open_save_as(&mut self) {
+        let name = if is_builtin(&self.current_layout) { String::new() } else { self.current_layout.clone() };
+        self.save_as = Some(SaveAsState { name, description: String::new() });
+    }
+
+    pub fn open_manager(&mut self) {
+        self.show_manager = true;
+    }
+
+    /// Apply a preset picked from the menu and draw the layout dialogs. Call once a
+    /// frame, before the panels are laid out in `content_area`.
+    pub fn update(&mut self, ui: &Ui, panels: &mut PanelManager, content_area: [f32; 4]) {
+        let content_size = [content_area[2], content_area[3]];
+        if let Some(name) = self.pending_layout.take() {
+            self.apply_layout(&name, panels, content_size);
+        }
+        self.render_save_as_modal(ui, panels, content_size);
+        self.render_manage_window(ui);
+    }
+
+    fn render_save_as_modal(&mut self, ui: &Ui, panels: &PanelManager, content_size: [f32; 2]) {
+        let Some(state) = &mut self.save_as else { return };
+
+        let mut save = false;
+        let mut cancel = false;
+        let replaces = self.saved_layouts.contains_key(state.name.trim()) && !is_builtin(state.name.trim());
+        let shown = ui.modal_popup_config("Save Layout As")
+            .always_auto_resize(true)
+            .build(|| {
+                if ui.is_window_appearing() {
+                    ui.set_keyboard_focus_here();
+                }
+                save |= ui.input_text("Name", &mut state.name).enter_returns_true(true).build();
+                ui.input_text("Description", &mut state.description).build();
+                if is_builtin(state.name.trim()) {
+                    ui.text_colored(PulsarTheme::TEXT_MUTED, "Built-in layouts can't be replaced");
+                } else if replaces {
+                    ui.text_colored(PulsarTheme::TEXT_MUTED, "Replaces the saved layout with this name");
+                }
+                ui.separator();
+
+                save |= ui.button_with_size("💾 Save", [100.0, 28.0]);
+                ui.same_line();
+                cancel = ui.button_with_size("Cancel", [100.0, 28.0]) || ui.is_key_pressed(Key::Escape);
+            })
+            .is_some();
+
+        // Opens from the next frame
+        if !shown {
+            ui.open_popup("Save Layout As");
+        }
+
+        if save {
+            let (name, description) = (state.name.clone(), state.description.clone());
+            if self.save_layout_as(&name, &description, panels, content_size).is_ok() {
+                self.save_as = None;
+            }
+        } else if cancel {
+            self.save_as = None;
+        }
+    }
+
+    /// Rename and delete presets, and pick the one used on launch
+    fn render_manage_window(&mut self, ui: &Ui) {
+        if !self.show_manager {
+            return;
+        }
+
+        let mut open = true;
+        let mut startup = None;
+        let mut rename = None;
+        let mut delete = None;
+        ui.window("Manage Layouts")
+            .opened(&mut open)
+            .always_auto_resize(true)
+            .flags(WindowFlags::NO_COLLAPSE)
+            .build(|| {
+                ui.text_colored(PulsarTheme::TEXT_SECONDARY, "On startup, open:");
+                if ui.radio_button_bool("The last session's layout", self.startup_layout.is_none()) {
+                    startup = Some(None);
+                }
+                ui.separator();
+
+                for name in self.preset_names() {
+                    let _id = ui.push_id(&name);
+                    if ui.radio_button_bool("##startup", self.startup_layout.as_deref() == Some(&name)) {
+                        startup = Some(Some(name.clone()));
+                    }
+                    ui.same_line();
+
+                    match &mut self.renaming {
+                        Some((old_name, new_name)) if *old_name == name => {
+                            if std::mem::take(&mut self.focus_rename) {
+                                ui.set_keyboard_focus_here();
+                            }
+                            if ui.input_text("##rename", new_name).enter_returns_true(true).build() {
+                                rename = Some((old_name.clone(), new_name.clone()));
+                            }
+                            if ui.is_key_pressed(Key::Escape) {
+                                rename = Some((old_name.clone(), old_name.clone()));
+                            }
+                        }
+                        _ => {
+                            ui.text(&name);
+                            if is_builtin(&name) {
+                                ui.same_line();
+                                ui.text_colored(PulsarTheme::TEXT_MUTED, "(built-in)");
+                            } else {
+                                ui.same_line();
+                                if ui.small_button("✏ Rename") {
+                                    self.renaming = Some((name.clone(), name.clone()));
+                                    self.focus_rename = true;
+                                }
+                                ui.same_line();
+                                if ui.small_button("🗑 Delete") {
+                                    delete = Some(name.clone());
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+        self.show_manager = open;
+
+        if let Some(name) = startup {
+            self.set_startup_layout(name);
+        }
+        if let Some((old_name, new_name)) = rename {
+            // A taken name keeps the field open so it can be changed
+            if self.rename_layout(&old_name, &new_name).is_ok() {
+                self.renaming = None;
+            }
+        }
+        if let Some(name) = delete {
+            self.delete_layout(&name);
+        }
+    }
 
-        VisualEffects::draw_glow(ui, central_pos, [drop_size, drop_size], PulsarTheme::BLUE_GLOW, 0.8);
+    /// Get the current content area (excluding menu bar and status bar)
+    pub fn get_content_area(&self, ui: &Ui) -> [f32; 4] {
+        let display_size = ui.io().display_size;
 
-        ui.get_window_draw_list()
-            .add_rect(central_pos, [central_pos[0] + drop_size, central_pos[1] + drop_size], PulsarTheme::BLUE_PRIMARY)
-            .filled(true)
-            .rounding(8.0)
-            .build();
+        [
+            0.0,
+            self.main_menu_height,
+            display_size[0],
+            display_size[1] - self.main_menu_height - self.status_bar_height,
+        ]
+    }
 
-        // Side drop zones
+    /// Drop zones shown while a panel is dragged, as [x, y, width, height]
+    pub fn drop_zones(content_area: [f32; 4]) -> [(DockArea, [f32; 4]); 4] {
+        let center_x = content_area[0] + content_area[2] / 2.0;
+        let center_y = content_area[1] + content_area[3] / 2.0;
+        let drop_size = 100.0;
         let side_thickness = 40.0;
         let side_length = 120.0;
 
-        // Left
-        let left_pos = [content_area[0] + 20.0, center_y - side_length / 2.0];
-        VisualEffects::draw_glow(ui, left_pos, [side_thickness, side_length], PulsarTheme::BLUE_GLOW, 0.6);
-
-        // Right
-        let right_pos = [content_area[0] + content_area[2] - side_thickness - 20.0, center_y - side_length / 2.0];
-        VisualEffects::draw_glow(ui, right_pos, [side_thickness, side_length], PulsarTheme::BLUE_GLOW, 0.6);
+        [
+            (DockArea::Center, [center_x - drop_size / 2.0, center_y - drop_size / 2.0, drop_size, drop_size]),
+            (DockArea::Left, [content_area[0] + 20.0, center_y - side_length / 2.0, side_thickness, side_length]),
+            (DockArea::Right, [content_area[0] + content_area[2] - side_thickness - 20.0, center_y - side_length / 2.0, side_thickness, side_length]),
+            (DockArea::Bottom, [center_x - side_length / 2.0, content_area[1] + content_area[3] - side_thickness - 20.0, side_length, side_thickness]),
+        ]
+    }
 
-        // Top
-        let top_pos = [center_x - side_length / 2.0, content_area[1] + 20.0];
-        VisualEffects::draw_glow(ui, top_pos, [side_length, side_thickness], PulsarTheme::BLUE_GLOW, 0.6);
+    /// The drop zone of `content_area` containing `point`
+    pub fn drop_target(content_area: [f32; 4], point: [f32; 2]) -> Option<DockArea> {
+        Self::drop_zones(content_area)
+            .into_iter()
+            .find(|(_, [x, y, width, height])| {
+                point[0] >= *x && point[0] <= x + width && point[1] >= *y && point[1] <= y + height
+            })
+            .map(|(area, _)| area)
+    }
 
-        // Bottom
-        let bottom_pos = [center_x - side_length / 2.0, content_area[1] + content_area[3] - side_thickness - 20.0];
-        VisualEffects::draw_glow(ui, bottom_pos, [side_length, side_thickness], PulsarTheme::BLUE_GLOW, 0.6);
+    /// Render floating panel hints for drag and drop over `content_area`, drawn above
+    /// all windows. `dragged_at` is the position of whatever is being dragged, which
+    /// may be over another OS window. Returns the zone under it.
+    pub fn render_drop_hints(&self, ui: &Ui, content_area: [f32; 4], dragged_at: Option<[f32; 2]>) -> Option<DockArea> {
+        let dragged_at = dragged_at?;
+        let draw_list = ui.get_foreground_draw_list();
+        let hovered = Self::drop_target(content_area, dragged_at);
+        for (area, [x, y, width, height]) in Self::drop_zones(content_area) {
+            let is_hovered = hovered == Some(area);
+
+            let intensity = if area == DockArea::Center { 0.8 } else { 0.6 };
+            VisualEffects::draw_glow(&draw_list, [x, y], [width, height], PulsarTheme::BLUE_GLOW, intensity);
+            if area == DockArea::Center || is_hovered {
+                draw_list
+                    .add_rect([x, y], [x + width, y + height], if is_hovered { PulsarTheme::BLUE_HOVER } else { PulsarTheme::BLUE_PRIMARY })
+                    .filled(true)
+                    .rounding(8.0)
+                    .build();
+            }
+        }
+        hovered
     }
 }
 
@@ -320,31 +584,109 @@ impl Default for LayoutManager {
     }
 }
 
+fn is_builtin(name: &str) -> bool {
+    BUILTIN_LAYOUTS.contains(&name)
+}
+
 /// Represents a saved layout configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LayoutPreset {
     pub name: String,
+    #[serde(default)]
     pub description: String,
     pub panels: Vec<PanelLayout>,
 }
 
 /// Individual panel configuration within a layout
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PanelLayout {
     pub panel_type: EditorPanelType,
     pub dock_area: DockArea,
+    /// Share of the editor's width (left and right) or height (bottom) taken by the
+    /// panel's dock area. Panels sharing an area split it in proportion to their
+    /// ratios, and the largest sets the area's size. Unused in the center, where
+    /// the first panel listed is shown.
     pub size_ratio: f32,
 }
 
-/// Available docking areas
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum DockArea {
-    Left,
-    Right,
-    Top,
-    Bottom,
-    Center,
-    Floating,
+impl LayoutPreset {
+    /// Arrange panels as this preset describes within an area of `content_size`
+    pub fn to_dock_layout(&self, content_size: [f32; 2]) -> DockLayout {
+        let mut layout = DockLayout {
+            left_panels: Vec::new(),
+            right_panels: Vec::new(),
+            bottom_panels: Vec::new(),
+            center_tabs: Vec::new(),
+            weights: HashMap::new(),
+            ..DockLayout::default()
+        };
+        let mut sizes = [0.0f32; 3]; // left, right and bottom ratios
+        for panel in &self.panels {
+            if layout.is_open(panel.panel_type) {
+                continue;
+            }
+            let ratio = panel.size_ratio.clamp(0.01, 1.0);
+            let (panels, size) = match panel.dock_area {
+                DockArea::Left => (&mut layout.left_panels, Some(&mut sizes[0])),
+                DockArea::Right => (&mut layout.right_panels, Some(&mut sizes[1])),
+                DockArea::Bottom => (&mut layout.bottom_panels, Some(&mut sizes[2])),
+                DockArea::Center => (&mut layout.center_tabs, None),
+            };
+            panels.push(panel.panel_type);
+            if let Some(size) = size {
+                *size = size.max(ratio);
+                layout.weights.insert(panel.panel_type, ratio);
+            }
+        }
+
+        let defaults = DockLayout::default();
+        layout.left_width = if sizes[0] > 0.0 { sizes[0] * content_size[0] } else { defaults.left_width };
+        layout.right_width = if sizes[1] > 0.0 { sizes[1] * content_size[0] } else { defaults.right_width };
+        layout.bottom_height = if sizes[2] > 0.0 { sizes[2] * content_size[1] } else { defaults.bottom_height };
+        layout.active_center = layout.center_tabs.first().copied();
+        layout
+    }
+
+    /// Record the current arrangement of `panels`. Floating and detached panels are
+    /// saved in the dock area they came from, sized like their window.
+    pub fn capture(name: &str, description: &str, panels: &PanelManager, content_size: [f32; 2]) -> Self {
+        let layout = panels.layout();
+        let width = content_size[0].max(1.0);
+        let height = content_size[1].max(1.0);
+        let mut entries = Vec::new();
+
+        // The active center panel first, so it is the one shown when applied
+        let center = layout.active_center.into_iter()
+            .chain(layout.center_tabs.iter().copied().filter(|&p| Some(p) != layout.active_center));
+        for panel_type in center {
+            entries.push(PanelLayout { panel_type, dock_area: DockArea::Center, size_ratio: 1.0 });
+        }
+
+        for (dock_area, area_ratio) in [
+            (DockArea::Left, layout.left_width / width),
+            (DockArea::Right, layout.right_width / width),
+            (DockArea::Bottom, layout.bottom_height / height),
+        ] {
+            let area_panels = layout.area(dock_area);
+            let largest = area_panels.iter().map(|&p| layout.weight(p)).fold(f32::EPSILON, f32::max);
+            for &panel_type in area_panels {
+                let size_ratio = area_ratio * layout.weight(panel_type) / largest;
+                entries.push(PanelLayout { panel_type, dock_area, size_ratio });
+            }
+        }
+
+        for floating in layout.floating.iter().chain(&layout.detached) {
+            let dock_area = panels.panel(floating.panel).map_or(DockArea::Center, |p| p.home);
+            let size_ratio = match dock_area {
+                DockArea::Left | DockArea::Right => floating.size[0] / width,
+                DockArea::Bottom => floating.size[1] / height,
+                DockArea::Center => 1.0,
+            };
+            entries.push(PanelLayout { panel_type: floating.panel, dock_area, size_ratio });
+        }
+
+        Self { name: name.to_string(), description: description.to_string(), panels: entries }
+    }
 }
 
 /// Grid system for precise panel positioning (inspired by CSS Grid)
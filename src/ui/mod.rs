@@ -1,5 +1,20 @@
 pub mod theme;
 pub mod simple_ui;
+pub mod scene_editor;
+pub mod editor_tabs;
+pub mod hierarchy_panel;
+pub mod inspector_panel;
+pub mod console_panel;
+pub mod asset_browser;
+pub mod editor_panels;
+pub mod panel_system;
+pub mod layout_manager;
+pub mod detached_window;
+pub mod editor_actions;
+pub mod command_palette;
+pub mod keybindings_window;
 
 pub use theme::*;
-pub use simple_ui::SimpleGameUI;
\ No newline at end of file
+pub use simple_ui::SimpleGameUI;
+pub use hierarchy_panel::HierarchyPanel;
+pub use inspector_panel::InspectorPanel;
@@ -1,615 +1,840 @@
 use imgui::*;
-use crate::ui::theme::{PulsarTheme, ButtonVariant, VisualEffects};
 use std::collections::HashMap;
-
-/// Represents a draggable, dockable panel that can be detached into floating windows
+use serde::{Deserialize, Serialize};
+use crate::ui::editor_panels::EditorPanelType;
+use crate::ui::theme::PulsarTheme;
+
+// Gap between docked panels, where the splitters are dragged
+const SPLITTER_SIZE: f32 = 4.0;
+// Smallest width or height a dock area is dragged to
+const MIN_DOCK_SIZE: f32 = 80.0;
+// Room always left for the center when the dock areas are squeezed
+const MIN_CENTER_SIZE: f32 = 200.0;
+// Thickness of a collapsed dock area
+const COLLAPSED_SIZE: f32 = 28.0;
+
+/// A dockable panel. Its content is drawn by the owner of the `PanelManager`.
 #[derive(Debug, Clone)]
 pub struct Panel {
-    pub id: String,
+    pub panel_type: EditorPanelType,
     pub title: String,
     pub icon: String,
-    pub content_type: PanelContentType,
-    pub is_open: bool,
-    pub is_floating: bool,
-    pub position: [f32; 2],
-    pub size: [f32; 2],
     pub min_size: [f32; 2],
-    pub dock_id: Option<u32>,
+    pub max_size: [f32; 2], // docked size limits, as [width, height]
     pub can_close: bool,
-    pub is_modified: bool,
+    pub can_detach: bool, // may move to an OS window of its own
+    pub home: DockArea, // where the panel docks when opened from the menu
 }
 
-#[derive(Debug, Clone)]
-pub enum PanelContentType {
-    SceneHierarchy,
-    Properties,
-    AssetBrowser,
-    Console,
-    Profiler,
-    LevelEditor,
-    ScriptEditor,
-    BlueprintEditor,
-    MaterialEditor,
-    AnimationEditor,
-    ParticleEditor,
-    SoundEditor,
-    TerrainEditor,
-    NavMeshEditor,
-    PhysicsDebug,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DockArea {
+    Left,
+    Right,
+    Bottom,
+    Center,
 }
 
-/// Manages the panel system with docking, floating, and layout persistence
-pub struct PanelManager {
-    panels: HashMap<String, Panel>,
-    floating_windows: Vec<String>,
-    dock_layout: DockLayout,
-    active_panel: Option<String>,
-    panel_order: Vec<String>,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FloatingPanel {
+    pub panel: EditorPanelType,
+    pub position: [f32; 2],
+    pub size: [f32; 2],
 }
 
-#[derive(Debug, Clone)]
+/// Where every open panel sits and how large the dock areas are. Panels that
+/// appear in none of the lists are closed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DockLayout {
-    pub left_panels: Vec<String>,
-    pub right_panels: Vec<String>,
-    pub bottom_panels: Vec<String>,
-    pub center_tabs: Vec<String>,
+    pub left_panels: Vec<EditorPanelType>,
+    pub right_panels: Vec<EditorPanelType>,
+    pub bottom_panels: Vec<EditorPanelType>,
+    pub center_tabs: Vec<EditorPanelType>,
+    pub floating: Vec<FloatingPanel>,
+    pub detached: Vec<FloatingPanel>, // in OS windows, positioned relative to the main window
+    pub active_center: Option<EditorPanelType>,
     pub left_width: f32,
     pub right_width: f32,
     pub bottom_height: f32,
+    pub weights: HashMap<EditorPanelType, f32>, // share of a split dock area, 1.0 if missing
+    pub collapsed: Vec<DockArea>,               // areas folded against the window edge
 }
 
 impl Default for DockLayout {
     fn default() -> Self {
         Self {
-            left_panels: vec!["scene_hierarchy".to_string()],
-            right_panels: vec!["properties".to_string(), "asset_browser".to_string()],
-            bottom_panels: vec!["console".to_string()],
-            center_tabs: vec!["level_editor".to_string(), "script_editor".to_string()],
-            left_width: 280.0,
-            right_width: 320.0,
+            left_panels: vec![EditorPanelType::Hierarchy],
+            right_panels: vec![EditorPanelType::Inspector],
+            bottom_panels: vec![EditorPanelType::Console, EditorPanelType::AssetBrowser],
+            center_tabs: vec![EditorPanelType::LevelEditor],
+            floating: Vec::new(),
+            detached: Vec::new(),
+            active_center: Some(EditorPanelType::LevelEditor),
+            left_width: 250.0,
+            right_width: 300.0,
             bottom_height: 200.0,
+            weights: HashMap::from([(EditorPanelType::Console, 1.5)]),
+            collapsed: Vec::new(),
         }
     }
 }
 
-impl PanelManager {
-    pub fn new() -> Self {
-        let mut panels = HashMap::new();
-
-        // Initialize default panels
-        let default_panels = vec![
-            Panel {
-                id: "scene_hierarchy".to_string(),
-                title: "Scene Hierarchy".to_string(),
-                icon: "🌳".to_string(),
-                content_type: PanelContentType::SceneHierarchy,
-                is_open: true,
-                is_floating: false,
-                position: [0.0, 0.0],
-                size: [280.0, 400.0],
-                min_size: [200.0, 200.0],
-                dock_id: None,
-                can_close: true,
-                is_modified: false,
-            },
-            Panel {
-                id: "properties".to_string(),
-                title: "Properties".to_string(),
-                icon: "🔧".to_string(),
-                content_type: PanelContentType::Properties,
-                is_open: true,
-                is_floating: false,
-                position: [0.0, 0.0],
-                size: [320.0, 500.0],
-                min_size: [250.0, 200.0],
-                dock_id: None,
-                can_close: true,
-                is_modified: false,
-            },
-            Panel {
-                id: "asset_browser".to_string(),
-                title: "Asset Browser".to_string(),
-                icon: "📁".to_string(),
-                content_type: PanelContentType::AssetBrowser,
-                is_open: true,
-                is_floating: false,
-                position: [0.0, 0.0],
-                size: [320.0, 300.0],
-                min_size: [300.0, 150.0],
-                dock_id: None,
-                can_close: true,
-                is_modified: false,
-            },
-            Panel {
-                id: "console".to_string(),
-                title: "Console".to_string(),
-                icon: "📋".to_string(),
-                content_type: PanelContentType::Console,
-                is_open: true,
-                is_floating: false,
-                position: [0.0, 0.0],
-                size: [800.0, 200.0],
-                min_size: [400.0, 100.0],
-                dock_id: None,
-                can_close: true,
-                is_modified: false,
-            },
-            Panel {
-                id: "level_editor".to_string(),
-                title: "Level Editor".to_string(),
-                icon: "🗺️".to_string(),
-                content_type: PanelContentType::LevelEditor,
-                is_open: true,
-                is_floating: false,
-                position: [0.0, 0.0],
-                size: [800.0, 600.0],
-                min_size: [400.0, 300.0],
-                dock_id: None,
-                can_close: false,
-                is_modified: false,
-            },
-            Panel {
-                id: "script_editor".to_string(),
-                title: "Script Editor".to_string(),
-                icon: "📜".to_string(),
-                content_type: PanelContentType::ScriptEditor,
-                is_open: true,
-                is_floating: false,
-                position: [0.0, 0.0],
-                size: [800.0, 600.0],
-                min_size: [400.0, 300.0],
-                dock_id: None,
-                can_close: true,
-                is_modified: true,
-            },
-        ];
-
-        for panel in default_panels {
-            panels.insert(panel.id.clone(), panel);
-        }
-
-        let panel_order = vec![
-            "scene_hierarchy".to_string(),
-            "properties".to_string(),
-            "asset_browser".to_string(),
-            "console".to_string(),
-            "level_editor".to_string(),
-            "script_editor".to_string(),
-        ];
-
-        Self {
-            panels,
-            floating_windows: Vec::new(),
-            dock_layout: DockLayout::default(),
-            active_panel: Some("level_editor".to_string()),
-            panel_order,
+impl DockLayout {
+    pub fn area(&self, area: DockArea) -> &Vec<EditorPanelType> {
+        match area {
+            DockArea::Left => &self.left_panels,
+            DockArea::Right => &self.right_panels,
+            DockArea::Bottom => &self.bottom_panels,
+            DockArea::Center => &self.center_tabs,
         }
     }
 
-    pub fn render(&mut self, ui: &Ui) {
-        let display_size = ui.io().display_size;
-        let menu_height = ui.frame_height();
+    fn area_mut(&mut self, area: DockArea) -> &mut Vec<EditorPanelType> {
+        match area {
+            DockArea::Left => &mut self.left_panels,
+            DockArea::Right => &mut self.right_panels,
+            DockArea::Bottom => &mut self.bottom_panels,
+            DockArea::Center => &mut self.center_tabs,
+        }
+    }
 
-        // Create main dockspace
-        self.render_dockspace(ui, display_size, menu_height);
+    /// The dock area holding `panel`, None if it is floating or closed
+    pub fn docked_area(&self, panel: EditorPanelType) -> Option<DockArea> {
+        [DockArea::Left, DockArea::Right, DockArea::Bottom, DockArea::Center]
+            .into_iter()
+            .find(|&area| self.area(area).contains(&panel))
+    }
 
-        // Render floating windows
-        self.render_floating_windows(ui);
+    pub fn is_floating(&self, panel: EditorPanelType) -> bool {
+        self.floating.iter().any(|f| f.panel == panel)
     }
 
-    fn render_dockspace(&mut self, ui: &Ui, display_size: [f32; 2], menu_height: f32) {
-        let dockspace_pos = [0.0, menu_height];
-        let dockspace_size = [display_size[0], display_size[1] - menu_height];
+    pub fn is_detached(&self, panel: EditorPanelType) -> bool {
+        self.detached.iter().any(|f| f.panel == panel)
+    }
 
-        ui.window("DockSpace")
-            .position(dockspace_pos, Condition::Always)
-            .size(dockspace_size, Condition::Always)
-            .flags(
-                WindowFlags::NO_DECORATION
-                | WindowFlags::NO_MOVE
-                | WindowFlags::NO_RESIZE
-                | WindowFlags::NO_BRING_TO_FRONT_ON_FOCUS
-                | WindowFlags::NO_NAV_FOCUS
-                | WindowFlags::NO_BACKGROUND
-            )
-            .build(|| {
-                // Use columns for docking layout since we're using imgui 0.10.0
-                self.render_docked_layout(ui, dockspace_size);
-            });
+    pub fn is_open(&self, panel: EditorPanelType) -> bool {
+        self.docked_area(panel).is_some() || self.is_floating(panel) || self.is_detached(panel)
     }
 
-    fn render_docked_layout(&mut self, ui: &Ui, available_size: [f32; 2]) {
-        // Three column layout: Left | Center | Right
-        ui.columns(3, "MainDockLayout", true);
+    pub fn weight(&self, panel: EditorPanelType) -> f32 {
+        self.weights.get(&panel).copied().unwrap_or(1.0)
+    }
 
-        // Left column
-        ui.set_column_width(0, self.dock_layout.left_width);
-        self.render_left_panels(ui);
+    /// Take a panel out of whichever area or window holds it
+    fn remove(&mut self, panel: EditorPanelType) {
+        for area in [DockArea::Left, DockArea::Right, DockArea::Bottom, DockArea::Center] {
+            self.area_mut(area).retain(|&p| p != panel);
+        }
+        self.floating.retain(|f| f.panel != panel);
+        self.detached.retain(|f| f.panel != panel);
+        if self.active_center == Some(panel) {
+            self.active_center = self.center_tabs.first().copied();
+        }
+    }
+}
 
-        ui.next_column();
+/// Requests from panel title bars and menus, applied once every panel has been drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PanelAction {
+    Dock(EditorPanelType, DockArea),
+    Float(EditorPanelType),
+    Detach(EditorPanelType),
+    Close(EditorPanelType),
+    Activate(EditorPanelType),
+}
 
-        // Center column with tabs and bottom panel
-        let center_width = available_size[0] - self.dock_layout.left_width - self.dock_layout.right_width;
-        ui.set_column_width(1, center_width);
-        self.render_center_area(ui, available_size[1]);
+/// A title bar being dragged. Docked panels are floated when the drag starts and
+/// follow the mouse at `grab_offset`; floating ones are moved by imgui itself.
+#[derive(Debug, Clone, Copy)]
+struct PanelDrag {
+    panel: EditorPanelType,
+    grab_offset: Option<[f32; 2]>,
+}
 
-        ui.next_column();
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Splitter {
+    LeftEdge,
+    RightEdge,
+    BottomEdge,
+    Between(DockArea, usize), // between panel `index` and `index + 1` of an area
+}
 
-        // Right column
-        ui.set_column_width(2, self.dock_layout.right_width);
-        self.render_right_panels(ui);
+/// Docks panels to the left, right, bottom and center of the editor, floats them
+/// in their own windows and resizes them with splitters. Used like an imgui window:
+///
+/// ```ignore
+/// panels.begin_frame(ui, area);
+/// for panel in panels.visible_panels() {
+///     if let Some(_window) = panels.begin_panel(ui, panel) {
+///         // draw the panel's content
+///     }
+/// }
+/// panels.end_frame(ui, drop_target);
+/// ```
+pub struct PanelManager {
+    panels: HashMap<EditorPanelType, Panel>,
+    dock_layout: DockLayout,
+    rects: HashMap<EditorPanelType, [f32; 4]>, // docked panels this frame, [x, y, width, height]
+    actions: Vec<PanelAction>,
+    pressed: Option<(EditorPanelType, [f32; 2])>, // title bar clicked, with the offset into the window
+    drag: Option<PanelDrag>,
+    unplaced: Vec<EditorPanelType>, // floating windows to move to their stored position and size
+    area: Option<[f32; 4]>,          // where the panels were docked last frame
+    resize_start: f32,               // size of the dock area whose edge is being dragged
+}
 
-        ui.columns(1, "", false);
-    }
+impl PanelManager {
+    pub fn new() -> Self {
+        Self::with_layout(DockLayout::default())
+    }
+
+    pub fn with_layout(dock_layout: DockLayout) -> Self {
+        let panels = EditorPanelType::all_panels()
+            .into_iter()
+            .map(|panel_type| {
+                let (min_size, max_size) = size_limits(panel_type);
+                let panel = Panel {
+                    panel_type,
+                    title: panel_type.display_name().to_string(),
+                    icon: panel_type.icon().to_string(),
+                    min_size,
+                    max_size,
+                    // The editor area holds the document tabs and is always open
+                    can_close: panel_type != EditorPanelType::LevelEditor,
+                    can_detach: panel_type != EditorPanelType::LevelEditor,
+                    home: default_home(panel_type),
+                };
+                (panel_type, panel)
+            })
+            .collect();
 
-    fn render_left_panels(&mut self, ui: &Ui) {
-        for panel_id in self.dock_layout.left_panels.clone() {
-            if let Some(panel) = self.panels.get_mut(&panel_id) {
-                if panel.is_open && !panel.is_floating {
-                    self.render_panel_content(ui, panel);
-                }
+        let mut manager = Self {
+            panels,
+            dock_layout: DockLayout::default(),
+            rects: HashMap::new(),
+            actions: Vec::new(),
+            pressed: None,
+            drag: None,
+            unplaced: Vec::new(),
+            area: None,
+            resize_start: 0.0,
+        };
+        manager.set_layout(dock_layout);
+        manager
+    }
+
+    pub fn layout(&self) -> &DockLayout {
+        &self.dock_layout
+    }
+
+    /// Replace the whole arrangement, e.g. from a saved session. Duplicate entries are
+    /// dropped and panels that can't be closed are reopened.
+    pub fn set_layout(&mut self, mut layout: DockLayout) {
+        let mut seen = Vec::new();
+        for area in [DockArea::Left, DockArea::Right, DockArea::Bottom, DockArea::Center] {
+            layout.area_mut(area).retain(|p| {
+                let first = !seen.contains(p);
+                seen.push(*p);
+                first
+            });
+        }
+        for windows in [&mut layout.floating, &mut layout.detached] {
+            windows.retain(|f| {
+                let first = !seen.contains(&f.panel);
+                seen.push(f.panel);
+                first
+            });
+        }
+        if layout.active_center.map_or(true, |p| !layout.center_tabs.contains(&p)) {
+            layout.active_center = layout.center_tabs.first().copied();
+        }
+        self.unplaced = layout.floating.iter().map(|f| f.panel).collect();
+        self.dock_layout = layout;
+
+        // Reopened behind whatever the layout shows in the center
+        let required: Vec<EditorPanelType> = self.panels.values().filter(|p| !p.can_close).map(|p| p.panel_type).collect();
+        for panel in required {
+            if !self.dock_layout.is_open(panel) {
+                self.dock_layout.center_tabs.push(panel);
+                self.dock_layout.active_center.get_or_insert(panel);
             }
         }
     }
 
-    fn render_right_panels(&mut self, ui: &Ui) {
-        for panel_id in self.dock_layout.right_panels.clone() {
-            if let Some(panel) = self.panels.get_mut(&panel_id) {
-                if panel.is_open && !panel.is_floating {
-                    self.render_panel_content(ui, panel);
-                }
+    pub fn panel(&self, panel: EditorPanelType) -> Option<&Panel> {
+        self.panels.get(&panel)
+    }
+
+    /// Change the window title, e.g. to show the document a panel is editing
+    pub fn set_title(&mut self, panel: EditorPanelType, title: &str) {
+        if let Some(panel) = self.panels.get_mut(&panel) {
+            if panel.title != title {
+                panel.title = title.to_string();
             }
         }
     }
 
-    fn render_center_area(&mut self, ui: &Ui, available_height: f32) {
-        // Top area with tabs
-        let tab_area_height = available_height - self.dock_layout.bottom_height - 40.0;
-
-        ui.child_window("CenterTabs")
-            .size([0.0, tab_area_height])
-            .build(|| {
-                self.render_center_tabs(ui);
-            });
-
-        // Bottom panel area
-        ui.child_window("BottomPanels")
-            .size([0.0, self.dock_layout.bottom_height])
-            .build(|| {
-                for panel_id in self.dock_layout.bottom_panels.clone() {
-                    if let Some(panel) = self.panels.get_mut(&panel_id) {
-                        if panel.is_open && !panel.is_floating {
-                            self.render_panel_content(ui, panel);
-                        }
-                    }
-                }
-            });
+    pub fn is_open(&self, panel: EditorPanelType) -> bool {
+        self.dock_layout.is_open(panel)
     }
 
-    fn render_center_tabs(&mut self, ui: &Ui) {
-        // Custom tab bar implementation for better styling
-        let tab_height = 32.0;
-
-        ui.child_window("TabBar")
-            .size([0.0, tab_height])
-            .flags(WindowFlags::NO_SCROLLBAR)
-            .build(|| {
-                self.render_custom_tab_bar(ui);
-            });
-
-        // Active tab content
-        ui.child_window("TabContent")
-            .size([0.0, -1.0])
-            .build(|| {
-                if let Some(active_id) = &self.active_panel.clone() {
-                    if let Some(panel) = self.panels.get_mut(active_id) {
-                        self.render_panel_content(ui, panel);
-                    }
-                }
-            });
+    /// Open a closed panel in its home area, or close an open one
+    pub fn set_open(&mut self, panel: EditorPanelType, open: bool) {
+        if open && !self.is_open(panel) {
+            if let Some(home) = self.panels.get(&panel).map(|p| p.home) {
+                self.dock_panel(panel, home);
+            }
+        } else if !open {
+            self.close_panel(panel);
+        }
     }
 
-    fn render_custom_tab_bar(&mut self, ui: &Ui) {
-        let mut tab_to_close: Option<String> = None;
-
-        for (index, panel_id) in self.dock_layout.center_tabs.iter().enumerate() {
-            if let Some(panel) = self.panels.get(panel_id) {
-                if !panel.is_open || panel.is_floating {
-                    continue;
-                }
-
-                if index > 0 {
-                    ui.same_line();
-                }
-
-                let is_active = self.active_panel.as_ref() == Some(panel_id);
-
-                // Tab styling
-                let style_token = if is_active {
-                    Some(ui.push_style_color(StyleColor::Button, PulsarTheme::TAB_ACTIVE))
-                } else {
-                    Some(ui.push_style_color(StyleColor::Button, PulsarTheme::TAB_INACTIVE))
-                };
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
 
-                let hover_token = if is_active {
-                    Some(ui.push_style_color(StyleColor::ButtonHovered, PulsarTheme::BLUE_HOVER))
-                } else {
-                    Some(ui.push_style_color(StyleColor::ButtonHovered, PulsarTheme::TAB_HOVER))
-                };
+    pub fn dock_panel(&mut self, panel: EditorPanelType, area: DockArea) {
+        self.dock_layout.remove(panel);
+        self.dock_layout.area_mut(area).push(panel);
+        self.set_collapsed(area, false);
+        if area == DockArea::Center {
+            self.dock_layout.active_center = Some(panel);
+        }
+        if let Some(panel) = self.panels.get_mut(&panel) {
+            panel.home = area;
+        }
+    }
 
-                // Tab label with icon and modified indicator
-                let modified_indicator = if panel.is_modified { "●" } else { "" };
-                let tab_label = format!("{} {} {}", panel.icon, panel.title, modified_indicator);
+    /// Take a panel out of its dock area into a window at `position`
+    pub fn float_panel(&mut self, panel: EditorPanelType, position: [f32; 2], size: [f32; 2]) {
+        self.dock_layout.remove(panel);
+        self.dock_layout.floating.push(FloatingPanel { panel, position, size });
+        self.unplaced.push(panel);
+    }
 
-                if ui.button(&tab_label) {
-                    self.active_panel = Some(panel_id.clone());
-                }
+    /// Move a panel into an OS window of its own. `position` is relative to the
+    /// main window's client area.
+    pub fn detach_panel(&mut self, panel: EditorPanelType, position: [f32; 2], size: [f32; 2]) {
+        if !self.panels.get(&panel).map_or(false, |p| p.can_detach) {
+            return;
+        }
+        self.dock_layout.remove(panel);
+        self.dock_layout.detached.push(FloatingPanel { panel, position, size });
+    }
 
-                if let Some(token) = hover_token {
-                    token.pop();
-                }
-                if let Some(token) = style_token {
-                    token.pop();
-                }
+    /// Remember where a detached panel's window was moved or resized to
+    pub fn move_detached(&mut self, panel: EditorPanelType, position: [f32; 2], size: [f32; 2]) {
+        if let Some(detached) = self.dock_layout.detached.iter_mut().find(|f| f.panel == panel) {
+            detached.position = position;
+            detached.size = size;
+        }
+    }
 
-                // Close button
-                if panel.can_close {
-                    ui.same_line();
-                    let close_id = format!("×##{}", panel_id);
-                    if ui.small_button(&close_id) {
-                        tab_to_close = Some(panel_id.clone());
-                    }
-                }
+    pub fn close_panel(&mut self, panel: EditorPanelType) {
+        if self.panels.get(&panel).map_or(false, |p| p.can_close) {
+            self.dock_layout.remove(panel);
+        }
+    }
 
-                // Context menu for tab
-                if ui.is_item_clicked_with_button(MouseButton::Right) {
-                    ui.open_popup(&format!("tab_context_{}", panel_id));
-                }
+    /// Size limits of a dock area along the axis its edge splitter moves: the
+    /// largest minimum and smallest maximum of the panels docked there
+    fn area_limits(&self, area: DockArea) -> (f32, f32) {
+        let axis = if area == DockArea::Bottom { 1 } else { 0 };
+        let (min, max) = self.dock_layout
+            .area(area)
+            .iter()
+            .filter_map(|p| self.panels.get(p))
+            .fold((MIN_DOCK_SIZE, f32::MAX), |(min, max), p| (min.max(p.min_size[axis]), max.min(p.max_size[axis])));
+        (min, max.max(min))
+    }
 
-                ui.popup(&format!("tab_context_{}", panel_id), || {
-                    if ui.menu_item("Float Window") {
-                        self.float_panel(panel_id);
-                    }
-                    if panel.can_close && ui.menu_item("Close Tab") {
-                        tab_to_close = Some(panel_id.clone());
-                    }
-                    if ui.menu_item("Close Other Tabs") {
-                        // Implement close others
-                    }
-                });
-            }
+    /// Width or height of a dock area as stored, within its limits
+    fn docked_size(&self, area: DockArea) -> f32 {
+        let layout = &self.dock_layout;
+        if layout.area(area).is_empty() {
+            return 0.0;
         }
-
-        // Handle tab closing
-        if let Some(panel_id) = tab_to_close {
-            self.close_panel(&panel_id);
+        if layout.collapsed.contains(&area) {
+            return COLLAPSED_SIZE;
         }
-
-        // Add new tab button
-        ui.same_line();
-        let add_style = ui.push_style_color(StyleColor::Button, PulsarTheme::BLUE_PRIMARY);
-        if ui.small_button("+") {
-            // Show panel selector
-            ui.open_popup("add_panel");
+        let (min, max) = self.area_limits(area);
+        match area {
+            DockArea::Left => layout.left_width,
+            DockArea::Right => layout.right_width,
+            DockArea::Bottom => layout.bottom_height,
+            DockArea::Center => 0.0,
         }
-        add_style.pop();
+        .clamp(min, max)
+    }
 
-        // Add panel popup
-        ui.popup("add_panel", || {
-            if ui.menu_item("Blueprint Editor") {
-                self.add_center_tab("blueprint_editor");
+    /// Lay out the dock areas inside `area` ([x, y, width, height]) and draw the splitters
+    pub fn begin_frame(&mut self, ui: &Ui, area: [f32; 4]) {
+        self.rects.clear();
+        let [x, y, width, height] = area;
+
+        // Keep the sizes the user dragged in proportion when the window is resized
+        if let Some([_, _, old_width, old_height]) = self.area {
+            let layout = &mut self.dock_layout;
+            if old_width > 1.0 && width != old_width {
+                layout.left_width *= width / old_width;
+                layout.right_width *= width / old_width;
             }
-            if ui.menu_item("Material Editor") {
-                self.add_center_tab("material_editor");
+            if old_height > 1.0 && height != old_height {
+                layout.bottom_height *= height / old_height;
             }
-            if ui.menu_item("Animation Editor") {
-                self.add_center_tab("animation_editor");
+        }
+        self.area = Some(area);
+
+        // A window too small for the stored sizes squeezes the dock areas for this
+        // frame only, so they come back when it grows again
+        let mut left = self.docked_size(DockArea::Left);
+        let mut right = self.docked_size(DockArea::Right);
+        let room = (width - MIN_CENTER_SIZE).max(0.0);
+        if left + right > room {
+            let scale = room / (left + right);
+            left *= scale;
+            right *= scale;
+        }
+        let bottom = self.docked_size(DockArea::Bottom).min((height - MIN_CENTER_SIZE).max(0.0));
+
+        let gap = |size: f32| if size > 0.0 { size + SPLITTER_SIZE } else { 0.0 };
+        let top_height = height - gap(bottom);
+
+        let mut splitters = Vec::new();
+        let mut strips = Vec::new();
+        for (dock_area, rect, vertical) in [
+            (DockArea::Left, [x, y, left, top_height], true),
+            (DockArea::Right, [x + width - right, y, right, top_height], true),
+            (DockArea::Bottom, [x, y + top_height + SPLITTER_SIZE, width, bottom], false),
+        ] {
+            if self.dock_layout.area(dock_area).is_empty() {
+                continue;
             }
-        });
-    }
-
-    fn render_floating_windows(&mut self, ui: &Ui) {
-        let floating_ids: Vec<String> = self.floating_windows.clone();
-
-        for panel_id in floating_ids {
-            if let Some(panel) = self.panels.get_mut(&panel_id) {
-                if panel.is_open && panel.is_floating {
-                    self.render_floating_panel(ui, panel);
-                }
+            if self.dock_layout.collapsed.contains(&dock_area) {
+                strips.push((dock_area, rect));
+            } else {
+                self.stack_panels(dock_area, rect, vertical, &mut splitters);
             }
         }
-    }
+        // Center panels share one window and switch with tabs
+        if let Some(active) = self.dock_layout.active_center {
+            self.rects.insert(active, [x + gap(left), y, width - gap(left) - gap(right), top_height]);
+        }
 
-    fn render_floating_panel(&mut self, ui: &Ui, panel: &mut Panel) {
-        let window_flags = WindowFlags::NO_COLLAPSE | WindowFlags::RESIZE_FROM_ANY_SIDE;
+        if left > 0.0 {
+            splitters.push((Splitter::LeftEdge, [x + left, y, SPLITTER_SIZE, top_height]));
+        }
+        if right > 0.0 {
+            splitters.push((Splitter::RightEdge, [x + width - gap(right), y, SPLITTER_SIZE, top_height]));
+        }
+        if bottom > 0.0 {
+            splitters.push((Splitter::BottomEdge, [x, y + top_height, width, SPLITTER_SIZE]));
+        }
 
-        ui.window(&format!("{} {}", panel.icon, panel.title))
-            .position(panel.position, Condition::FirstUseEver)
-            .size(panel.size, Condition::FirstUseEver)
-            .size_constraints(panel.min_size, [f32::INFINITY, f32::INFINITY])
-            .flags(window_flags)
+        // Splitters live in a window behind the panels, showing through the gaps
+        ui.window("##DockSpace")
+            .position([x, y], Condition::Always)
+            .size([width, height], Condition::Always)
+            .flags(
+                WindowFlags::NO_DECORATION
+                | WindowFlags::NO_MOVE
+                | WindowFlags::NO_BRING_TO_FRONT_ON_FOCUS
+                | WindowFlags::NO_NAV_FOCUS
+                | WindowFlags::NO_BACKGROUND
+                | WindowFlags::NO_SAVED_SETTINGS
+            )
             .build(|| {
-                // Update panel position and size
-                panel.position = ui.window_pos();
-                panel.size = ui.window_size();
-
-                self.render_panel_content(ui, panel);
+                for (dock_area, rect) in strips {
+                    self.render_collapsed_strip(ui, dock_area, rect);
+                }
+                for (splitter, rect) in splitters {
+                    self.render_splitter(ui, splitter, rect);
+                }
             });
     }
 
-    fn render_panel_content(&self, ui: &Ui, panel: &Panel) {
-        // Render the actual panel content based on type
-        match panel.content_type {
-            PanelContentType::SceneHierarchy => self.render_scene_hierarchy(ui),
-            PanelContentType::Properties => self.render_properties(ui),
-            PanelContentType::AssetBrowser => self.render_asset_browser(ui),
-            PanelContentType::Console => self.render_console(ui),
-            PanelContentType::Profiler => self.render_profiler(ui),
-            PanelContentType::LevelEditor => self.render_level_editor(ui),
-            PanelContentType::ScriptEditor => self.render_script_editor(ui),
-            PanelContentType::BlueprintEditor => self.render_blueprint_editor(ui),
-            PanelContentType::MaterialEditor => self.render_material_editor(ui),
-            PanelContentType::AnimationEditor => self.render_animation_editor(ui),
-            PanelContentType::ParticleEditor => self.render_particle_editor(ui),
-            PanelContentType::SoundEditor => self.render_sound_editor(ui),
-            PanelContentType::TerrainEditor => self.render_terrain_editor(ui),
-            PanelContentType::NavMeshEditor => self.render_navmesh_editor(ui),
-            PanelContentType::PhysicsDebug => self.render_physics_debug(ui),
-        }
-    }
-
-    // Panel management methods
-    pub fn float_panel(&mut self, panel_id: &str) {
-        if let Some(panel) = self.panels.get_mut(panel_id) {
-            panel.is_floating = true;
-            self.floating_windows.push(panel_id.to_string());
-
-            // Remove from dock layout
-            self.dock_layout.left_panels.retain(|id| id != panel_id);
-            self.dock_layout.right_panels.retain(|id| id != panel_id);
-            self.dock_layout.bottom_panels.retain(|id| id != panel_id);
-            self.dock_layout.center_tabs.retain(|id| id != panel_id);
-        }
-    }
-
-    pub fn dock_panel(&mut self, panel_id: &str, dock_area: DockArea) {
-        if let Some(panel) = self.panels.get_mut(panel_id) {
-            panel.is_floating = false;
-            self.floating_windows.retain(|id| id != panel_id);
-
-            // Add to appropriate dock area
-            match dock_area {
-                DockArea::Left => self.dock_layout.left_panels.push(panel_id.to_string()),
-                DockArea::Right => self.dock_layout.right_panels.push(panel_id.to_string()),
-                DockArea::Bottom => self.dock_layout.bottom_panels.push(panel_id.to_string()),
-                DockArea::Center => self.dock_layout.center_tabs.push(panel_id.to_string()),
+    /// Divide a dock area between its panels by weight, top to bottom or left to right
+    fn stack_panels(&mut self, area: DockArea, rect: [f32; 4], vertical: bool, splitters: &mut Vec<(Splitter, [f32; 4])>) {
+        let layout = &self.dock_layout;
+        let panels = layout.area(area);
+        let gaps = SPLITTER_SIZE * panels.len().saturating_sub(1) as f32;
+        let length = if vertical { rect[3] } else { rect[2] } - gaps;
+        let total: f32 = panels.iter().map(|&p| layout.weight(p)).sum();
+        let mut offset = 0.0;
+        for (i, &panel) in panels.iter().enumerate() {
+            let size = length * layout.weight(panel) / total;
+            let panel_rect = if vertical {
+                [rect[0], rect[1] + offset, rect[2], size]
+            } else {
+                [rect[0] + offset, rect[1], size, rect[3]]
+            };
+            self.rects.insert(panel, panel_rect);
+            offset += size;
+            if i + 1 < panels.len() {
+                let splitter = if vertical {
+                    [rect[0], rect[1] + offset, rect[2], SPLITTER_SIZE]
+                } else {
+                    [rect[0] + offset, rect[1], SPLITTER_SIZE, rect[3]]
+                };
+                splitters.push((Splitter::Between(area, i), splitter));
             }
+            offset += SPLITTER_SIZE;
         }
     }
 
-    pub fn close_panel(&mut self, panel_id: &str) {
-        if let Some(panel) = self.panels.get_mut(panel_id) {
-            if panel.can_close {
-                panel.is_open = false;
-                self.floating_windows.retain(|id| id != panel_id);
+    /// A collapsed dock area: a strip against the window edge with a button per panel
+    /// that expands the area again
+    fn render_collapsed_strip(&mut self, ui: &Ui, area: DockArea, [x, y, width, height]: [f32; 4]) {
+        ui.get_window_draw_list()
+            .add_rect([x, y], [x + width, y + height], PulsarTheme::DARK_PANEL)
+            .filled(true)
+            .build();
+
+        let button_size = COLLAPSED_SIZE - 4.0;
+        let mut pos = [x + 2.0, y + 2.0];
+        let mut expand = false;
+        for panel in self.dock_layout.area(area).clone() {
+            let Some(info) = self.panels.get(&panel) else { continue };
+            ui.set_cursor_screen_pos(pos);
+            expand |= ui.button_with_size(format!("{}##collapsed_{:?}", info.icon, panel), [button_size, button_size]);
+            if ui.is_item_hovered() {
+                ui.tooltip_text(&info.title);
+            }
+            if area == DockArea::Bottom {
+                pos[0] += COLLAPSED_SIZE;
+            } else {
+                pos[1] += COLLAPSED_SIZE;
+            }
+        }
+        if expand {
+            self.dock_layout.collapsed.retain(|&a| a != area);
+        }
+    }
 
-                // Remove from dock layout
-                self.dock_layout.left_panels.retain(|id| id != panel_id);
-                self.dock_layout.right_panels.retain(|id| id != panel_id);
-                self.dock_layout.bottom_panels.retain(|id| id != panel_id);
-                self.dock_layout.center_tabs.retain(|id| id != panel_id);
+    fn render_splitter(&mut self, ui: &Ui, splitter: Splitter, [x, y, width, height]: [f32; 4]) {
+        let horizontal = width > height;
+        ui.set_cursor_screen_pos([x, y]);
+        ui.invisible_button(format!("##splitter_{:?}", splitter), [width.max(1.0), height.max(1.0)]);
 
-                // If this was the active panel, switch to another
-                if self.active_panel.as_ref() == Some(panel_id) {
-                    self.active_panel = self.dock_layout.center_tabs.first().cloned();
-                }
+        if ui.is_item_hovered() || ui.is_item_active() {
+            ui.set_mouse_cursor(Some(if horizontal { MouseCursor::ResizeNS } else { MouseCursor::ResizeEW }));
+            ui.get_window_draw_list()
+                .add_rect([x, y], [x + width, y + height], PulsarTheme::BLUE_PRIMARY)
+                .filled(true)
+                .build();
+        }
+
+        let edge = match splitter {
+            Splitter::LeftEdge => Some(DockArea::Left),
+            Splitter::RightEdge => Some(DockArea::Right),
+            Splitter::BottomEdge => Some(DockArea::Bottom),
+            Splitter::Between(..) => None,
+        };
+        if let Some(area) = edge {
+            // Double-clicking an edge collapses or expands its dock area
+            if ui.is_item_hovered() && ui.is_mouse_double_clicked(MouseButton::Left) {
+                self.set_collapsed(area, !self.dock_layout.collapsed.contains(&area));
+                return;
+            }
+            if ui.is_item_activated() {
+                self.resize_start = self.docked_size(area);
             }
         }
-    }
+        if !ui.is_item_active() {
+            return;
+        }
 
-    pub fn add_center_tab(&mut self, panel_id: &str) {
-        if !self.dock_layout.center_tabs.contains(&panel_id.to_string()) {
-            self.dock_layout.center_tabs.push(panel_id.to_string());
-            self.active_panel = Some(panel_id.to_string());
+        match (edge, self.area) {
+            (Some(area), Some([area_x, area_y, area_width, area_height])) => {
+                // Follow the mouse rather than summing deltas, so the edge stays under it
+                let mouse = ui.io().mouse_pos;
+                let size = match area {
+                    DockArea::Left => mouse[0] - area_x,
+                    DockArea::Right => area_x + area_width - mouse[0],
+                    _ => area_y + area_height - mouse[1],
+                };
+                self.resize_area(area, size);
+            }
+            (None, _) => self.drag_between(ui, splitter),
+            _ => {}
         }
     }
 
-    // Content rendering methods (will be moved to separate files)
-    fn render_scene_hierarchy(&self, ui: &Ui) {
-        ui.text("🌳 Scene Objects");
-        ui.separator();
-        // Scene hierarchy content...
+    /// Resize a dock area as its edge is dragged. Dragging well past the smallest size
+    /// collapses the area against the window edge, keeping the size it had before.
+    fn resize_area(&mut self, area: DockArea, size: f32) {
+        let (min, max) = self.area_limits(area);
+        if size < min * 0.5 {
+            let size = self.resize_start;
+            self.set_size(area, size);
+            self.set_collapsed(area, true);
+            return;
+        }
+        self.set_collapsed(area, false);
+        self.set_size(area, size.clamp(min, max));
     }
 
-    fn render_properties(&self, ui: &Ui) {
-        ui.text("🔧 Properties");
-        ui.separator();
-        // Properties content...
+    fn set_size(&mut self, area: DockArea, size: f32) {
+        let layout = &mut self.dock_layout;
+        match area {
+            DockArea::Left => layout.left_width = size,
+            DockArea::Right => layout.right_width = size,
+            DockArea::Bottom => layout.bottom_height = size,
+            DockArea::Center => {}
+        }
     }
 
-    fn render_asset_browser(&self, ui: &Ui) {
-        ui.text("📁 Asset Browser");
-        ui.separator();
-        // Asset browser content...
+    /// Fold a dock area into a strip of buttons at the window edge, or unfold it
+    pub fn set_collapsed(&mut self, area: DockArea, collapsed: bool) {
+        let layout = &mut self.dock_layout;
+        layout.collapsed.retain(|&a| a != area);
+        if collapsed && area != DockArea::Center {
+            layout.collapsed.push(area);
+        }
     }
 
-    fn render_console(&self, ui: &Ui) {
-        ui.text("📋 Console");
-        ui.separator();
-        // Console content...
+    /// Move weight between the two panels either side of a splitter in proportion
+    /// to the drag, within both panels' size limits
+    fn drag_between(&mut self, ui: &Ui, splitter: Splitter) {
+        let Splitter::Between(area, index) = splitter else { return };
+        let panels = self.dock_layout.area(area);
+        let (Some(&a), Some(&b)) = (panels.get(index), panels.get(index + 1)) else { return };
+        let (Some(rect_a), Some(rect_b)) = (self.rects.get(&a), self.rects.get(&b)) else { return };
+        let (Some(panel_a), Some(panel_b)) = (self.panels.get(&a), self.panels.get(&b)) else { return };
+        let axis = if area == DockArea::Bottom { 0 } else { 1 };
+        let (size_a, size_b) = (rect_a[axis + 2], rect_b[axis + 2]);
+
+        let lowest = (panel_a.min_size[axis] - size_a).max(size_b - panel_b.max_size[axis]);
+        let highest = (panel_a.max_size[axis] - size_a).min(size_b - panel_b.min_size[axis]);
+        if lowest > highest {
+            return; // no room to move either way
+        }
+        let moved = ui.io().mouse_delta[axis].clamp(lowest, highest);
+        if moved != 0.0 {
+            let layout = &mut self.dock_layout;
+            let (weight_a, weight_b) = (layout.weight(a), layout.weight(b));
+            let per_pixel = (weight_a + weight_b) / (size_a + size_b).max(1.0);
+            layout.weights.insert(a, weight_a + moved * per_pixel);
+            layout.weights.insert(b, weight_b - moved * per_pixel);
+        }
     }
 
-    fn render_profiler(&self, ui: &Ui) {
-        ui.text("📊 Profiler");
-        ui.separator();
-        // Profiler content...
-    }
+    /// Panels with a window this frame: every docked and floating panel, plus the
+    /// active one of the center tabs. Panels in a collapsed area have none.
+    pub fn visible_panels(&self) -> Vec<EditorPanelType> {
+        let layout = &self.dock_layout;
+        [DockArea::Left, DockArea::Right, DockArea::Bottom]
+            .into_iter()
+            .filter(|area| !layout.collapsed.contains(area))
+            .flat_map(|area| layout.area(area).iter().copied())
+            .chain(layout.active_center)
+            .chain(layout.floating.iter().map(|f| f.panel))
+            .collect()
+    }
+
+    /// Begin the window of a visible panel. Draw the panel's content while the
+    /// returned token is alive.
+    pub fn begin_panel<'ui>(&mut self, ui: &'ui Ui, panel: EditorPanelType) -> Option<WindowToken<'ui>> {
+        let info = self.panels.get(&panel)?;
+        let can_close = info.can_close;
+        let title = format!("{} {}###panel_{:?}", info.icon, info.title, panel);
+        let (min_size, max_size) = (info.min_size, info.max_size);
+
+        let mut open = true;
+        let mut window = ui.window(title);
+        match self.rects.get(&panel) {
+            Some(&[x, y, width, height]) => {
+                window = window
+                    .position([x, y], Condition::Always)
+                    .size([width, height], Condition::Always)
+                    .flags(WindowFlags::NO_MOVE | WindowFlags::NO_RESIZE | WindowFlags::NO_COLLAPSE | WindowFlags::NO_SAVED_SETTINGS);
+            }
+            None => {
+                let floating = self.dock_layout.floating.iter().find(|f| f.panel == panel)?;
+                // Imgui keeps a window where the user left it, unless it was just floated
+                let condition = if self.unplaced.contains(&panel) { Condition::Always } else { Condition::Appearing };
+                self.unplaced.retain(|&p| p != panel);
+                match self.drag {
+                    Some(PanelDrag { panel: dragged, grab_offset: Some(offset) }) if dragged == panel => {
+                        let mouse = ui.io().mouse_pos;
+                        window = window.position([mouse[0] - offset[0], mouse[1] - offset[1]], Condition::Always);
+                    }
+                    _ => window = window.position(floating.position, condition),
+                }
+                window = window
+                    .size(floating.size, condition)
+                    .size_constraints(min_size, max_size)
+                    .flags(WindowFlags::NO_COLLAPSE | WindowFlags::NO_SAVED_SETTINGS);
+            }
+        }
+        if can_close {
+            window = window.opened(&mut open);
+        }
+        let token = window.begin();
+        if !open {
+            self.actions.push(PanelAction::Close(panel));
+        }
+        let token = token?;
 
-    fn render_level_editor(&self, ui: &Ui) {
-        ui.text("🗺️ Level Editor");
-        ui.separator();
-        // Level editor content...
+        self.handle_title_bar(ui, panel);
+        if let Some(floating) = self.dock_layout.floating.iter_mut().find(|f| f.panel == panel) {
+            floating.position = ui.window_pos();
+            floating.size = ui.window_size();
+        }
+        if self.dock_layout.active_center == Some(panel) && self.dock_layout.center_tabs.len() > 1 {
+            self.render_center_tabs(ui);
+        }
+        Some(token)
     }
 
-    fn render_script_editor(&self, ui: &Ui) {
-        ui.text("📜 Script Editor");
-        ui.separator();
-        // Script editor content...
-    }
+    /// Dragging a title bar floats the panel; right-clicking it opens the dock menu
+    fn handle_title_bar(&mut self, ui: &Ui, panel: EditorPanelType) {
+        let window_pos = ui.window_pos();
+        let mouse = ui.io().mouse_pos;
+        let on_title = ui.is_window_hovered() && mouse[1] < window_pos[1] + ui.frame_height();
 
-    fn render_blueprint_editor(&self, ui: &Ui) {
-        ui.text("🔧 Blueprint Editor");
-        ui.separator();
-        // Blueprint editor content...
-    }
+        if on_title && ui.is_mouse_clicked(MouseButton::Left) {
+            self.pressed = Some((panel, [mouse[0] - window_pos[0], mouse[1] - window_pos[1]]));
+        }
+        if on_title && ui.is_mouse_clicked(MouseButton::Right) {
+            ui.open_popup("panel_menu");
+        }
 
-    fn render_material_editor(&self, ui: &Ui) {
-        ui.text("🎨 Material Editor");
-        ui.separator();
-        // Material editor content...
+        let docked_area = self.dock_layout.docked_area(panel);
+        let (can_close, can_detach) = self.panels.get(&panel).map_or((false, false), |p| (p.can_close, p.can_detach));
+        ui.popup("panel_menu", || {
+            for (label, area) in [
+                ("⬅ Dock Left", DockArea::Left),
+                ("➡ Dock Right", DockArea::Right),
+                ("⬇ Dock Bottom", DockArea::Bottom),
+                ("⏺ Dock Center", DockArea::Center),
+            ] {
+                if ui.menu_item_config(label).enabled(docked_area != Some(area)).build() {
+                    self.actions.push(PanelAction::Dock(panel, area));
+                }
+            }
+            if ui.menu_item_config("🗗 Float").enabled(docked_area.is_some()).build() {
+                self.actions.push(PanelAction::Float(panel));
+            }
+            if ui.menu_item_config("🗔 Detach to Window").enabled(can_detach).build() {
+                self.actions.push(PanelAction::Detach(panel));
+            }
+            ui.separator();
+            if ui.menu_item_config("✖ Close").enabled(can_close).build() {
+                self.actions.push(PanelAction::Close(panel));
+            }
+        });
     }
 
-    fn render_animation_editor(&self, ui: &Ui) {
-        ui.text("🎭 Animation Editor");
+    /// Switch between the panels docked in the center
+    fn render_center_tabs(&mut self, ui: &Ui) {
+        for (i, &panel) in self.dock_layout.center_tabs.iter().enumerate() {
+            let Some(info) = self.panels.get(&panel) else { continue };
+            if i > 0 {
+                ui.same_line();
+            }
+            let is_active = self.dock_layout.active_center == Some(panel);
+            let _button_token = ui.push_style_color(
+                StyleColor::Button,
+                if is_active { PulsarTheme::TAB_ACTIVE } else { PulsarTheme::TAB_INACTIVE },
+            );
+            if ui.button(format!("{} {}##center_tab_{:?}", info.icon, info.panel_type.display_name(), panel)) {
+                self.actions.push(PanelAction::Activate(panel));
+            }
+        }
         ui.separator();
-        // Animation editor content...
     }
 
-    fn render_particle_editor(&self, ui: &Ui) {
-        ui.text("✨ Particle Editor");
-        ui.separator();
-        // Particle editor content...
-    }
+    /// Where a docked or floating panel's window was drawn, as [x, y, width, height]
+    fn window_rect(&self, panel: EditorPanelType) -> Option<[f32; 4]> {
+        if let Some(&rect) = self.rects.get(&panel) {
+            return Some(rect);
+        }
+        let floating = self.dock_layout.floating.iter().find(|f| f.panel == panel)?;
+        Some([floating.position[0], floating.position[1], floating.size[0], floating.size[1]])
+    }
+
+    /// Apply title bar and menu requests, and finish or start a panel drag.
+    /// `drop_target` is the drop hint under the mouse.
+    pub fn end_frame(&mut self, ui: &Ui, drop_target: Option<DockArea>) {
+        for action in std::mem::take(&mut self.actions) {
+            match action {
+                PanelAction::Dock(panel, area) => self.dock_panel(panel, area),
+                PanelAction::Float(panel) => {
+                    let [x, y, width, height] = self.rects.get(&panel).copied().unwrap_or([100.0, 100.0, 400.0, 300.0]);
+                    self.float_panel(panel, [x + 40.0, y + 40.0], [width.max(300.0), height.max(200.0)]);
+                }
+                PanelAction::Detach(panel) => {
+                    let [x, y, width, height] = self.window_rect(panel).unwrap_or([100.0, 100.0, 400.0, 300.0]);
+                    self.detach_panel(panel, [x + 40.0, y + 40.0], [width.max(300.0), height.max(200.0)]);
+                }
+                PanelAction::Close(panel) => self.close_panel(panel),
+                PanelAction::Activate(panel) => self.dock_layout.active_center = Some(panel),
+            }
+        }
 
-    fn render_sound_editor(&self, ui: &Ui) {
-        ui.text("🎵 Sound Editor");
-        ui.separator();
-        // Sound editor content...
-    }
+        let mouse = ui.io().mouse_pos;
+        if let Some(drag) = self.drag {
+            if !ui.is_mouse_down(MouseButton::Left) {
+                self.drag = None;
+                let display_size = ui.io().display_size;
+                let outside = mouse[0] < 0.0 || mouse[1] < 0.0 || mouse[0] > display_size[0] || mouse[1] > display_size[1];
+                if let Some(area) = drop_target {
+                    self.dock_panel(drag.panel, area);
+                } else if outside {
+                    // Dropped outside the editor: give the panel an OS window there
+                    if let Some([x, y, width, height]) = self.window_rect(drag.panel) {
+                        self.detach_panel(drag.panel, [x, y], [width, height]);
+                    }
+                }
+            }
+            return;
+        }
 
-    fn render_terrain_editor(&self, ui: &Ui) {
-        ui.text("🏔️ Terrain Editor");
-        ui.separator();
-        // Terrain editor content...
+        let Some((panel, grab_offset)) = self.pressed else { return };
+        if !ui.is_mouse_down(MouseButton::Left) {
+            self.pressed = None;
+        } else if ui.is_mouse_dragging(MouseButton::Left) {
+            self.pressed = None;
+            if self.dock_layout.is_floating(panel) {
+                self.drag = Some(PanelDrag { panel, grab_offset: None });
+            } else if let Some(&[_, _, width, height]) = self.rects.get(&panel) {
+                let position = [mouse[0] - grab_offset[0], mouse[1] - grab_offset[1]];
+                self.float_panel(panel, position, [width.clamp(300.0, 800.0), height.clamp(200.0, 600.0)]);
+                // Keep the grab point under the mouse even though the window got smaller
+                let offset = [grab_offset[0].min(width.clamp(300.0, 800.0) - 20.0), grab_offset[1]];
+                self.drag = Some(PanelDrag { panel, grab_offset: Some(offset) });
+            }
+        }
     }
+}
 
-    fn render_navmesh_editor(&self, ui: &Ui) {
-        ui.text("🧭 NavMesh Editor");
-        ui.separator();
-        // NavMesh editor content...
+impl Default for PanelManager {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    fn render_physics_debug(&self, ui: &Ui) {
-        ui.text("⚡ Physics Debug");
-        ui.separator();
-        // Physics debug content...
+/// Where a panel docks the first time it is opened
+fn default_home(panel: EditorPanelType) -> DockArea {
+    match panel {
+        EditorPanelType::Hierarchy => DockArea::Left,
+        EditorPanelType::Inspector
+        | EditorPanelType::Profiler
+        | EditorPanelType::Physics
+        | EditorPanelType::Lighting
+        | EditorPanelType::Weather => DockArea::Right,
+        EditorPanelType::Console | EditorPanelType::AssetBrowser | EditorPanelType::Animation => DockArea::Bottom,
+        _ => DockArea::Center,
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub enum DockArea {
-    Left,
-    Right,
-    Bottom,
-    Center,
-}
\ No newline at end of file
+/// Smallest and largest size a panel is shown at, as [width, height]
+fn size_limits(panel: EditorPanelType) -> ([f32; 2], [f32; 2]) {
+    match panel {
+        EditorPanelType::Hierarchy | EditorPanelType::Inspector => ([180.0, 120.0], [700.0, f32::MAX]),
+        EditorPanelType::Console | EditorPanelType::AssetBrowser => ([200.0, 100.0], [f32::MAX, 700.0]),
+        _ => ([200.0, 120.0], [f32::MAX, f32::MAX]),
+    }
+}
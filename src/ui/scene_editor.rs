@@ -0,0 +1,604 @@
+use imgui::*;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::console;
+use crate::editor_camera::{CameraMode, EditorCamera, Projection, ScreenProjection};
+use crate::gizmo::{Gizmo, GizmoSpace, GizmoTool};
+use crate::history::History;
+use crate::keybindings::{self, KeyScope};
+use crate::picking;
+use crate::play_mode::{PlaySession, PlayState};
+use crate::project::{self, SceneFile};
+use crate::scene::{ComponentRegistry, EntityId, Scene, SelectMode, Selection};
+use crate::scene_renderer::{self, ViewportFrame};
+use crate::simulation;
+use crate::tab_system::{SceneDocument, TabContent};
+use crate::textures::{self, TextureHandle};
+use crate::ui::editor_actions;
+use crate::ui::theme::PulsarTheme;
+
+/// 3D level editor tab: a scene with its own selection, undo history, camera and
+/// play session. The Hierarchy and Inspector panels edit the active one.
+pub struct SceneEditor {
+    scene: Scene,
+    selection: Selection,
+    history: History<Scene>,
+    viewport_texture: TextureHandle,
+    camera: EditorCamera,
+    gizmo: Gizmo,
+    marquee_start: Option<[f32; 2]>,
+    play_session: Option<PlaySession>,
+    // Project file
+    project_path: Option<PathBuf>,
+    pending_project_action: Option<ProjectAction>,
+    discard_action: Option<ProjectAction>, // New or Open waiting on the unsaved changes prompt
+    project_error: Option<String>,
+}
+
+/// Project file operations requested from menus and toolbars
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProjectAction {
+    New,
+    Open,
+    Save,
+    SaveAs,
+}
+
+/// Editor state stored in project files alongside the scene
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SceneEditorState {
+    selection: Vec<u64>, // primary last
+    level_camera: EditorCamera,
+}
+
+impl SceneEditor {
+    pub fn new() -> Self {
+        Self {
+            scene: Scene::with_default_content(),
+            selection: Selection::new(),
+            history: History::default(),
+            viewport_texture: textures::create_handle(),
+            camera: EditorCamera::default(),
+            gizmo: Gizmo::new(),
+            marquee_start: None,
+            play_session: None,
+            project_path: None,
+            pending_project_action: None,
+            discard_action: None,
+            project_error: None,
+        }
+    }
+
+    fn render_level_editor(&mut self, ui: &Ui) {
+        // Main toolbar with level operations
+        {
+            let _toolbar_bg = ui.push_style_color(StyleColor::ChildBg, PulsarTheme::DARKER_PANEL);
+            ui.child_window("LevelToolbar")
+                .size([0.0, 40.0])
+                .border(false)
+                .build(|| {
+                    ui.text("Level:");
+                    ui.same_line();
+
+                    {
+                        let _btn_color = ui.push_style_color(StyleColor::Button, PulsarTheme::BLUE_PRIMARY);
+                        if ui.button_with_size("🆕 New", [60.0, 28.0]) {
+                            self.pending_project_action = Some(ProjectAction::New);
+                        }
+                    }
+                    ui.same_line();
+                    if ui.button_with_size("📁 Load", [60.0, 28.0]) {
+                        self.pending_project_action = Some(ProjectAction::Open);
+                    }
+                    ui.same_line();
+                    if ui.button_with_size("💾 Save", [60.0, 28.0]) {
+                        self.pending_project_action = Some(ProjectAction::Save);
+                    }
+                    ui.same_line();
+                    ui.text("|");
+                    ui.same_line();
+
+                    let play_color = ui.push_style_color(StyleColor::Button, PulsarTheme::PLAY_GREEN);
+                    match self.play_session.as_ref().map(PlaySession::is_paused) {
+                        None => {
+                            if ui.button_with_size("▶ Play", [60.0, 28.0]) {
+                                self.start_play();
+                            }
+                        }
+                        Some(paused) => {
+                            if ui.button_with_size(if paused { "▶ Resume" } else { "⏸ Pause" }, [75.0, 28.0]) {
+                                self.toggle_pause();
+                            }
+                            ui.same_line();
+                            ui.disabled(!paused, || {
+                                if ui.button_with_size("⏭ Step", [60.0, 28.0]) {
+                                    self.step_play();
+                                }
+                            });
+                            ui.same_line();
+                            let _stop_color = ui.push_style_color(StyleColor::Button, [0.6, 0.2, 0.2, 0.8]);
+                            if ui.button_with_size("⏹ Stop", [60.0, 28.0]) {
+                                self.stop_play();
+                            }
+                        }
+                    }
+                    play_color.pop();
+                    ui.same_line();
+                    if ui.button_with_size("🔨 Build", [60.0, 28.0]) {}
+                });
+        }
+
+        ui.spacing();
+
+        // Tool selection bar
+        {
+            let _tool_bg = ui.push_style_color(StyleColor::ChildBg, PulsarTheme::DARKER_PANEL);
+            ui.child_window("ToolBar")
+                .size([0.0, 40.0])
+                .border(false)
+                .build(|| {
+                    ui.text("Tools:");
+                    ui.same_line();
+
+                    for tool in GizmoTool::ALL {
+                        let _tool_color = (self.gizmo.tool == tool)
+                            .then(|| ui.push_style_color(StyleColor::Button, PulsarTheme::TAB_ACTIVE));
+                        if ui.button_with_size(tool.label(), [70.0, 28.0]) {
+                            self.gizmo.tool = tool;
+                        }
+                        ui.same_line();
+                    }
+                    let space_label = match self.gizmo.space {
+                        GizmoSpace::World => "🌐 World",
+                        GizmoSpace::Local => "📦 Local",
+                    };
+                    if ui.button_with_size(space_label, [70.0, 28.0]) {
+                        self.gizmo.space = match self.gizmo.space {
+                            GizmoSpace::World => GizmoSpace::Local,
+                            GizmoSpace::Local => GizmoSpace::World,
+                        };
+                    }
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text("Gizmo axes (scaling always uses local axes)");
+                    }
+                    ui.same_line();
+                    ui.checkbox("Snap", &mut self.gizmo.snap_enabled);
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text("Snap to the grid, 15° and 0.1x steps (hold Ctrl to toggle while dragging)");
+                    }
+                    ui.same_line();
+                    ui.text("|");
+                    ui.same_line();
+                    if ui.button_with_size("🎨 Paint", [60.0, 28.0]) {}
+                    ui.same_line();
+                    ui.text("|");
+                    ui.same_line();
+
+                    // Camera projection and right-drag mode
+                    let camera = &mut self.camera;
+                    let projection_label = match camera.projection {
+                        Projection::Perspective => "📷 Persp",
+                        Projection::Orthographic => "📐 Ortho",
+                    };
+                    if ui.button_with_size(projection_label, [75.0, 28.0]) {
+                        camera.toggle_projection();
+                    }
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text(keybindings::with_shortcut("Switch between perspective and orthographic", editor_actions::TOGGLE_PROJECTION));
+                    }
+                    for mode in CameraMode::ALL {
+                        ui.same_line();
+                        let _mode_color = (camera.mode == mode)
+                            .then(|| ui.push_style_color(StyleColor::Button, PulsarTheme::TAB_ACTIVE));
+                        if ui.button_with_size(mode.display_name(), [50.0, 28.0]) {
+                            camera.mode = mode;
+                        }
+                    }
+                });
+        }
+
+        ui.spacing();
+
+        // 3D Viewport, rendered offscreen by the scene renderer
+        let pos = ui.cursor_screen_pos();
+        let avail = ui.content_region_avail();
+        let size = [avail[0] - 10.0, avail[1] - 10.0];
+
+        // Ensure positive size to avoid ClipRect assertion
+        if size[0] > 0.0 && size[1] > 0.0 {
+            // Covers the viewport so camera drags are captured here instead of moving the window
+            ui.invisible_button_flags(
+                "##level_viewport",
+                size,
+                ButtonFlags::MOUSE_BUTTON_LEFT | ButtonFlags::MOUSE_BUTTON_RIGHT | ButtonFlags::MOUSE_BUTTON_MIDDLE,
+            );
+            let hovered = ui.is_item_hovered();
+            self.camera.handle_input(ui, size[1], hovered, ui.is_item_active());
+            // Viewport shortcuts apply from the next frame
+            if hovered || ui.is_item_active() {
+                keybindings::claim_scope(KeyScope::Viewport);
+            }
+
+            // The target is created during this frame's render, so the first frame shows black
+            let max = [pos[0] + size[0], pos[1] + size[1]];
+            {
+                let draw_list = ui.get_window_draw_list();
+                match textures::texture_id(self.viewport_texture) {
+                    Some(texture_id) => draw_list.add_image(texture_id, pos, max).build(),
+                    None => draw_list.add_rect(pos, max, PulsarTheme::PURE_BLACK).filled(true).build(),
+                }
+            }
+
+            // Gizmo drags update the scene before it is submitted for rendering
+            let projection = self.camera.screen_projection(pos, size);
+            self.gizmo.render(ui, &projection, &mut self.scene, &mut self.history, self.selection.primary(), hovered);
+            self.handle_viewport_selection(ui, &projection, hovered);
+
+            let scale = ui.io().display_framebuffer_scale;
+            let pixel_size = [(size[0] * scale[0]) as u32, (size[1] * scale[1]) as u32];
+            scene_renderer::submit(ViewportFrame {
+                target: self.viewport_texture,
+                size: pixel_size,
+                view_proj: self.camera.view_projection(size[0] / size[1]),
+                clear_color: [0.0, 0.0, 0.0, 1.0],
+                items: scene_renderer::collect_draw_items(&self.scene, &self.selection),
+            });
+
+            let draw_list = ui.get_window_draw_list();
+
+            // Viewport border with blue glow, orange while playing
+            let border_color = if self.play_session.is_some() { PulsarTheme::PLAY_TEMPORARY } else { PulsarTheme::BLUE_PRIMARY };
+            draw_list
+                .add_rect(pos, max, border_color)
+                .thickness(2.0)
+                .build();
+
+            // Viewport info overlay
+            let info_pos = [pos[0] + 10.0, pos[1] + 10.0];
+            match &self.play_session {
+                Some(session) => {
+                    let status = if session.is_paused() { "⏸ Paused" } else { "▶ Playing" };
+                    let mut time = format!("{}  {:.2}s ({} steps) - changes are temporary", status, session.simulation.time(), session.simulation.steps());
+                    let time_scale = console::cvar(simulation::TIME_SCALE).map_or(1.0, |value| value.as_f32());
+                    if time_scale != 1.0 {
+                        time.push_str(&format!(" | time_scale {}", time_scale));
+                    }
+                    draw_list.add_text(info_pos, PulsarTheme::PLAY_TEMPORARY, &time);
+                }
+                None => draw_list.add_text(info_pos, PulsarTheme::TEXT_SECONDARY, "3D Viewport"),
+            }
+            let help = match keybindings::shortcut_label(editor_actions::FRAME_SELECTION) {
+                Some(shortcut) => format!("{} | {}: Focus", self.camera.mode.help_text(), shortcut),
+                None => self.camera.mode.help_text().to_string(),
+            };
+            draw_list.add_text([info_pos[0], info_pos[1] + 20.0], PulsarTheme::TEXT_MUTED, help);
+        }
+    }
+
+    /// Point the camera at the selected entities
+    fn frame_selection(&mut self) {
+        let bounds = self
+            .selection
+            .iter()
+            .filter_map(|id| self.scene.world_bounds(id))
+            .reduce(|(a_min, a_max), (b_min, b_max)| (a_min.min(b_min), a_max.max(b_max)));
+        if let Some((min, max)) = bounds {
+            self.camera.frame_bounds(min, max);
+        }
+    }
+
+    /// Click to pick an entity, drag to box-select. Shift adds to the selection, Ctrl toggles.
+    fn handle_viewport_selection(&mut self, ui: &Ui, projection: &ScreenProjection, hovered: bool) {
+        let io = ui.io();
+        let mouse = io.mouse_pos;
+        let over_gizmo = self.gizmo.is_hovered() || self.gizmo.is_dragging();
+        if hovered && !io.key_alt && !over_gizmo && ui.is_mouse_clicked(MouseButton::Left) {
+            self.marquee_start = Some(mouse);
+        }
+
+        let Some(start) = self.marquee_start else { return };
+        let dragged = (mouse[0] - start[0]).abs() > 4.0 || (mouse[1] - start[1]).abs() > 4.0;
+
+        if ui.is_mouse_down(MouseButton::Left) {
+            if dragged {
+                let origin = projection.origin();
+                let size = projection.size();
+                let draw_list = ui.get_window_draw_list();
+                draw_list.with_clip_rect_intersect(origin, [origin[0] + size[0], origin[1] + size[1]], || {
+                    draw_list.add_rect(start, mouse, [0.2, 0.5, 1.0, 0.15]).filled(true).build();
+                    draw_list.add_rect(start, mouse, PulsarTheme::BLUE_PRIMARY).build();
+                });
+            }
+            return;
+        }
+
+        self.marquee_start = None;
+        let mode = SelectMode::from_modifiers(io.key_shift, io.key_ctrl);
+        if dragged {
+            self.selection.select_many(picking::marquee(&self.scene, projection, start, mouse), mode);
+        } else {
+            self.selection.click(picking::pick(&self.scene, projection.ray(mouse)), mode);
+        }
+    }
+
+}
+
+impl SceneEditor {
+    fn start_play(&mut self) {
+        if self.play_session.is_some() {
+            return;
+        }
+        self.gizmo.cancel_drag(&mut self.scene);
+        self.history.seal();
+        self.play_session = Some(PlaySession::start(&mut self.scene, &mut self.history));
+    }
+
+    fn stop_play(&mut self) {
+        let Some(session) = self.play_session.take() else { return };
+        self.gizmo.cancel_drag(&mut self.scene);
+        session.stop(&mut self.scene, &mut self.history);
+        self.selection.retain_existing(&self.scene);
+    }
+
+    fn toggle_pause(&mut self) {
+        if let Some(session) = &mut self.play_session {
+            session.state = match session.state {
+                PlayState::Playing => PlayState::Paused,
+                PlayState::Paused => PlayState::Playing,
+            };
+        }
+    }
+
+    fn step_play(&mut self) {
+        if let Some(session) = &mut self.play_session {
+            session.simulation.step(&mut self.scene);
+        }
+    }
+}
+
+impl SceneEditor {
+    fn run_project_action(&mut self, action: ProjectAction) {
+        // New and Open replace the scene, so unsaved changes are asked about first
+        if matches!(action, ProjectAction::New | ProjectAction::Open) && self.is_dirty() {
+            self.discard_action = Some(action);
+            return;
+        }
+        self.run_confirmed_action(action);
+    }
+
+    /// Run a project action once any unsaved changes have been dealt with
+    fn run_confirmed_action(&mut self, action: ProjectAction) {
+        match action {
+            ProjectAction::New => {
+                self.stop_play();
+                self.scene = Scene::with_default_content();
+                self.selection.clear();
+                self.camera = EditorCamera::default();
+                self.project_path = None;
+                self.history.clear();
+            }
+            ProjectAction::Open => {
+                if let Some(path) = project::pick_open_path("Open Project") {
+                    self.stop_play();
+                    if let Err(e) = self.open_project(path) {
+                        self.project_error = Some(e);
+                    }
+                }
+            }
+            ProjectAction::Save => match self.project_path.clone() {
+                Some(path) => self.save_project(path),
+                None => self.run_confirmed_action(ProjectAction::SaveAs),
+            },
+            ProjectAction::SaveAs => {
+                if let Some(path) = project::pick_save_path("Save Project As", &self.scene.name) {
+                    self.save_project(path);
+                }
+            }
+        }
+    }
+
+    fn editor_state(&self) -> SceneEditorState {
+        SceneEditorState {
+            selection: self.selection.iter().map(|id| id.0).collect(),
+            level_camera: self.camera.clone(),
+        }
+    }
+
+    fn save_project(&mut self, path: PathBuf) {
+        let state = self.editor_state();
+        // Project files always hold the edited scene, never the running game, so
+        // while playing the scene set aside by the session is saved
+        let (scene, history) = match &mut self.play_session {
+            Some(session) => session.edited_mut(),
+            None => (&mut self.scene, &mut self.history),
+        };
+        // The scene takes the file's name, but only once the file is written
+        let mut file = SceneFile::new(scene, state);
+        if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+            file.scene.name = stem.to_string();
+        }
+        match file.save(&path) {
+            Ok(()) => {
+                scene.name = file.scene.name;
+                history.mark_saved();
+                self.project_path = Some(path);
+            }
+            Err(e) => self.project_error = Some(format!("Failed to save project:\n{}", e)),
+        }
+    }
+
+    /// Load a project file into this editor, replacing its scene. On failure the
+    /// editor is left as it was and the message to show is returned.
+    pub fn open_project(&mut self, path: PathBuf) -> Result<(), String> {
+        let result = SceneFile::<SceneEditorState>::load(&path)
+            .and_then(|file| Ok((file.build_scene(&path, &ComponentRegistry::default())?, file.editor)));
+
+        match result {
+            Ok((scene, state)) => {
+                self.scene = scene;
+                self.selection.select_many(state.selection.into_iter().map(EntityId), SelectMode::Replace);
+                self.selection.retain_existing(&self.scene);
+                self.camera = state.level_camera;
+                self.project_path = Some(path);
+                self.history.clear();
+                Ok(())
+            }
+            Err(e) => Err(format!("Failed to open project:\n{}", e)),
+        }
+    }
+}
+
+/// Modal showing why a project failed to open or save, until dismissed
+pub fn render_project_error_modal(ui: &Ui, error: &mut Option<String>) {
+    let shown = ui.modal_popup_config("Project Error")
+        .always_auto_resize(true)
+        .build(|| {
+            if let Some(message) = error.as_ref() {
+                ui.text_colored([1.0, 0.5, 0.5, 1.0], message);
+            }
+            ui.spacing();
+            if ui.button_with_size("OK", [80.0, 28.0]) || ui.is_key_pressed(Key::Escape) {
+                *error = None;
+                ui.close_current_popup();
+            }
+        })
+        .is_some();
+
+    // Opens from the next frame
+    if !shown && error.is_some() {
+        ui.open_popup("Project Error");
+    }
+}
+
+impl TabContent for SceneEditor {
+    fn render(&mut self, ui: &Ui) {
+        self.render_level_editor(ui);
+
+        if let Some(action) = self.pending_project_action.take() {
+            self.run_project_action(action);
+        }
+        render_project_error_modal(ui, &mut self.project_error);
+    }
+
+    fn get_title(&self) -> &str {
+        &self.scene.name
+    }
+
+    /// Unsaved changes to the edited scene, ignoring anything done during play
+    fn is_dirty(&self) -> bool {
+        match &self.play_session {
+            Some(session) => session.is_dirty(),
+            None => self.history.is_dirty(),
+        }
+    }
+
+    fn get_icon(&self) -> Option<&str> {
+        Some("🌍")
+    }
+
+    fn run_action(&mut self, id: &str) {
+        match id {
+            editor_actions::FRAME_SELECTION => self.frame_selection(),
+            editor_actions::TOGGLE_PROJECTION => self.camera.toggle_projection(),
+            _ => {}
+        }
+    }
+
+    /// Advance the running game, even while another tab is shown
+    fn update(&mut self, ui: &Ui) {
+        if let Some(session) = &mut self.play_session {
+            if session.state == PlayState::Playing {
+                session.simulation.update(&mut self.scene, ui.io().delta_time);
+            }
+        }
+    }
+
+    fn scene_document(&mut self) -> Option<SceneDocument<'_>> {
+        Some(SceneDocument {
+            scene: &mut self.scene,
+            history: &mut self.history,
+            selection: &mut self.selection,
+            play_session: self.play_session.as_mut(),
+        })
+    }
+
+    fn memory_size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.scene.memory_size()
+            + self.history.memory_used()
+            + self.play_session.as_ref().map_or(0, PlaySession::memory_size)
+    }
+
+    fn can_save(&self) -> bool {
+        true
+    }
+
+    fn save(&mut self, save_as: bool) {
+        self.run_project_action(if save_as { ProjectAction::SaveAs } else { ProjectAction::Save });
+    }
+
+    fn file_path(&self) -> Option<&Path> {
+        self.project_path.as_deref()
+    }
+
+    fn open_file(&mut self, path: PathBuf) {
+        if let Err(e) = self.open_project(path) {
+            self.project_error = Some(e);
+        }
+    }
+
+    fn wants_discard(&self) -> bool {
+        self.discard_action.is_some()
+    }
+
+    fn resume_discard(&mut self, proceed: bool) {
+        if let Some(action) = self.discard_action.take().filter(|_| proceed) {
+            self.run_confirmed_action(action);
+        }
+    }
+
+    fn undo_sequence(&self) -> Option<u64> {
+        self.history.undo_sequence()
+    }
+
+    fn redo_sequence(&self) -> Option<u64> {
+        self.history.redo_sequence()
+    }
+
+    fn undo_label(&self) -> Option<String> {
+        self.history.undo_label()
+    }
+
+    fn redo_label(&self) -> Option<String> {
+        self.history.redo_label()
+    }
+
+    fn seal_history(&mut self) {
+        self.history.seal();
+    }
+
+    fn undo(&mut self) {
+        self.gizmo.cancel_drag(&mut self.scene);
+        self.history.undo(&mut self.scene);
+        self.selection.retain_existing(&self.scene);
+    }
+
+    fn redo(&mut self) {
+        self.gizmo.cancel_drag(&mut self.scene);
+        self.history.redo(&mut self.scene);
+        self.selection.retain_existing(&self.scene);
+    }
+}
+
+impl Drop for SceneEditor {
+    fn drop(&mut self) {
+        textures::release(self.viewport_texture);
+    }
+}
+
+impl Default for SceneEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
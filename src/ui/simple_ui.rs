@@ -1,113 +1,124 @@
 use imgui::*;
+use std::collections::HashMap;
+use std::path::Path;
+use crate::actions::{ActionRegistry, MenuEntry};
+use crate::history::History;
+use crate::keybindings;
+use crate::logging;
+use crate::project;
+use crate::scene::EntityId;
+use crate::session::{self, Preferences, Session};
+use crate::tab_system::{CloseTab, OpenTab, Tab, TabAction, TabId, TabSystem};
+use crate::ui::asset_browser::AssetBrowser;
+use crate::ui::command_palette::CommandPalette;
+use crate::ui::console_panel::ConsolePanel;
+use crate::ui::detached_window::{self, DetachedBarAction, DetachedContent, DetachedDrag, DetachedWindow, DETACHED_TAB_SIZE};
+use crate::ui::editor_actions;
+use crate::ui::editor_panels::{EditorPanelRenderer, EditorPanelType, PanelContext};
+use crate::ui::editor_tabs;
+use crate::ui::hierarchy_panel::HierarchyPanel;
+use crate::ui::inspector_panel::InspectorPanel;
+use crate::ui::keybindings_window::KeybindingsWindow;
+use crate::ui::layout_manager::LayoutManager;
+use crate::ui::panel_system::{DockArea, PanelManager};
+use crate::ui::scene_editor::{self, ProjectAction, SceneEditor};
 use crate::ui::theme::PulsarTheme;
 
 /// Simple AMOLED UI that works with imgui 0.10.0
 pub struct SimpleGameUI {
-    show_hierarchy: bool,
-    show_inspector: bool,
-    show_console: bool,
-    show_asset_browser: bool,
-    // Panel sizes for resizing
-    left_panel_width: f32,
-    right_panel_width: f32,
-    bottom_panel_height: f32,
-    // Tab system
-    active_tab: EditorTab,
-    available_tabs: Vec<EditorTab>,
-    show_tab_search: bool,
-    tab_search_query: String,
+    // Docked and floating panels around the editor area
+    panels: PanelManager,
+    layout: LayoutManager,
+    content_area: [f32; 4], // where panels were docked last frame
+    detached_item_active: bool, // a widget in a detached window was held last frame
+    // Panel or tab window being dragged back towards the main window
+    detached_drag: Option<DetachedDrag>,
+    // Commands the menu bar, shortcuts and command palette run
+    actions: ActionRegistry,
+    palette: CommandPalette,
+    keybindings_window: KeybindingsWindow,
+    // Editor tabs in the Level Editor panel
+    tabs: TabSystem,
+    tab_history: History<TabSystem>,
+    // Panels showing the active tab's scene
+    hierarchy_panel: HierarchyPanel,
+    inspector_panel: InspectorPanel,
+    console_panel: ConsolePanel,
+    asset_browser: AssetBrowser,
+    pending_project_action: Option<ProjectAction>,
+    project_error: Option<String>, // why File > Open Project failed
+    // Close, exit or in-tab replace waiting on the unsaved changes prompt
+    pending_close: Option<PendingClose>,
+    should_exit: bool,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum EditorTab {
-    LevelEditor,
-    ScriptEditor,
-    BlueprintEditor,
-    MaterialEditor,
-    AnimationEditor,
-    ParticleEditor,
-    AudioEditor,
-    TerrainEditor,
-    PhysicsDebug,
-    Profiler,
+/// Tabs to close once the user has decided what happens to their unsaved changes
+struct PendingClose {
+    tabs: Vec<TabId>,
+    kind: CloseKind,
 }
 
-impl EditorTab {
-    pub fn display_name(&self) -> &'static str {
-        match self {
-            Self::LevelEditor => "Level Editor",
-            Self::ScriptEditor => "Script Editor",
-            Self::BlueprintEditor => "Blueprint Editor",
-            Self::MaterialEditor => "Material Editor",
-            Self::AnimationEditor => "Animation Editor",
-            Self::ParticleEditor => "Particle Editor",
-            Self::AudioEditor => "Audio Editor",
-            Self::TerrainEditor => "Terrain Editor",
-            Self::PhysicsDebug => "Physics Debug",
-            Self::Profiler => "Profiler",
-        }
-    }
-
-    pub fn icon(&self) -> &'static str {
-        match self {
-            Self::LevelEditor => "🌍",
-            Self::ScriptEditor => "📜",
-            Self::BlueprintEditor => "🔧",
-            Self::MaterialEditor => "🎨",
-            Self::AnimationEditor => "🎬",
-            Self::ParticleEditor => "✨",
-            Self::AudioEditor => "🔊",
-            Self::TerrainEditor => "🏔️",
-            Self::PhysicsDebug => "⚡",
-            Self::Profiler => "📊",
-        }
-    }
-
-    pub fn description(&self) -> &'static str {
-        match self {
-            Self::LevelEditor => "Design and build game levels",
-            Self::ScriptEditor => "Write and debug game scripts",
-            Self::BlueprintEditor => "Visual scripting system",
-            Self::MaterialEditor => "Create and edit materials",
-            Self::AnimationEditor => "Create character animations",
-            Self::ParticleEditor => "Design particle effects",
-            Self::AudioEditor => "Edit and manage audio",
-            Self::TerrainEditor => "Sculpt and paint terrain",
-            Self::PhysicsDebug => "Debug physics simulation",
-            Self::Profiler => "Performance monitoring",
-        }
-    }
-
-    pub fn all_tabs() -> Vec<Self> {
-        vec![
-            Self::LevelEditor,
-            Self::ScriptEditor,
-            Self::BlueprintEditor,
-            Self::MaterialEditor,
-            Self::AnimationEditor,
-            Self::ParticleEditor,
-            Self::AudioEditor,
-            Self::TerrainEditor,
-            Self::PhysicsDebug,
-            Self::Profiler,
-        ]
-    }
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CloseKind {
+    Tabs,
+    Exit, // close the whole editor rather than the tabs
+    Replace, // the tabs stay open and load something else, see `TabContent::wants_discard`
+    Undo, // undo an open tab, see `SimpleGameUI::undo`
+    Redo, // redo a close tab
 }
 
 impl SimpleGameUI {
     pub fn new() -> Self {
+        Self::from_session(&Session::default())
+    }
+
+    /// Reopen the tabs and panel layout of a saved session
+    pub fn from_session(session: &Session) -> Self {
+        let mut tabs = TabSystem::new();
+        editor_tabs::register_editor_tabs(&mut tabs);
+        let restored: Vec<Option<TabId>> = session.tabs.iter().map(|state| tabs.restore_tab(state)).collect();
+        if let Some(id) = session.active_tab.and_then(|i| restored.get(i).copied().flatten()) {
+            tabs.set_active(id);
+        }
+        if restored.iter().all(Option::is_none) {
+            tabs.add_tab(editor_tabs::LEVEL_EDITOR); // Start with Level Editor open
+        }
+
+        let mut actions = ActionRegistry::new();
+        editor_actions::register_editor_actions(&mut actions);
+        editor_actions::register_tab_actions(&mut actions, &tabs);
+        keybindings::load(&mut actions);
+        actions.set_recent(session.preferences.recent_actions.clone());
+
+        if session.preferences.log_to_file {
+            let path = logging::default_log_file();
+            if let Err(e) = logging::set_log_file(Some(&path)) {
+                log::error!("Failed to open log file {}: {}", path.display(), e);
+            }
+        }
+
+        let mut console_panel = ConsolePanel::new();
+        console_panel.set_history(session.preferences.console_history.clone());
+
         Self {
-            show_hierarchy: true,
-            show_inspector: true,
-            show_console: true,
-            show_asset_browser: true,
-            left_panel_width: 250.0,
-            right_panel_width: 300.0,
-            bottom_panel_height: 200.0,
-            active_tab: EditorTab::LevelEditor,  // Default to Level Editor
-            available_tabs: vec![EditorTab::LevelEditor],  // Start with Level Editor open
-            show_tab_search: false,
-            tab_search_query: String::new(),
+            panels: PanelManager::with_layout(session.panels.clone()),
+            layout: LayoutManager::load(),
+            content_area: [0.0; 4],
+            detached_item_active: false,
+            detached_drag: None,
+            actions,
+            palette: CommandPalette::new(),
+            keybindings_window: KeybindingsWindow::new(),
+            tabs,
+            tab_history: History::default(),
+            hierarchy_panel: HierarchyPanel::new(),
+            inspector_panel: InspectorPanel::new(),
+            console_panel,
+            asset_browser: AssetBrowser::new(),
+            pending_project_action: None,
+            project_error: None,
+            pending_close: None,
+            should_exit: false,
         }
     }
 
@@ -118,739 +129,722 @@ impl SimpleGameUI {
             return;
         }
 
+        // Edits made while a widget is held (e.g. a slider drag) merge into one undo
+        // step. Sealing here rather than in the tabs splits Inspector edits even while
+        // their tab isn't drawn. Detached windows have imgui contexts of their own.
+        let item_active = ui.is_any_item_active() || std::mem::take(&mut self.detached_item_active);
+        if !item_active {
+            self.tabs.seal_histories();
+        }
+
+        self.handle_shortcuts(ui);
+        self.tabs.update(ui);
+
         // Main menu bar
         self.render_main_menu_bar(ui);
 
-        // Calculate available space
+        // Dock panels in the space below the menu bar
         let menu_height = ui.frame_height();
-        let available_height = display_size[1] - menu_height;
-        let available_width = display_size[0];
+        let area = [0.0, menu_height, display_size[0], display_size[1] - menu_height];
+        self.content_area = area;
+        self.layout.update(ui, &mut self.panels, area);
+        // Hints show for panels dragged here and for detached windows dragged over the editor
+        let dragged_at = if self.panels.is_dragging() {
+            Some(ui.io().mouse_pos)
+        } else {
+            self.detached_drag.map(|drag| drag.cursor)
+        };
+        let drop_target = self.layout.render_drop_hints(ui, area, dragged_at);
+        self.render_panels(ui, area, drop_target);
+
+        // Tabs torn off the bar
+        if let Some(action) = self.tabs.render_floating_tabs(ui) {
+            self.apply_tab_action(action);
+        }
 
-        // Update responsive panel sizing
-        self.update_responsive_panel_sizes(available_width, available_height);
+        // Render tab search modal if open (render last for proper z-order)
+        if let Some(action) = self.tabs.render_new_tab_modal(ui) {
+            self.apply_tab_action(action);
+        }
 
-        // Render tab bar
-        self.render_tab_bar(ui, menu_height, available_width);
+        if self.palette.is_open() {
+            let disabled: Vec<String> = self.actions.actions().iter()
+                .filter(|action| !self.action_enabled(&action.id))
+                .map(|action| action.id.clone())
+                .collect();
+            let enabled = |id: &str| !disabled.iter().any(|d| d == id);
+            if let Some(id) = self.palette.render(ui, &self.actions, &enabled) {
+                self.run_action(&id);
+            }
+        }
 
-        // Render panels as separate windows with proper positioning
-        let tab_bar_height = 35.0;
-        self.render_separate_panels(ui, menu_height + tab_bar_height, available_width, available_height - tab_bar_height);
+        if self.keybindings_window.render(ui, &mut self.actions) {
+            keybindings::save(&self.actions);
+        }
 
-        // Render tab search modal if open (render last for proper z-order)
-        if self.show_tab_search {
-            self.render_tab_search_modal(ui);
+        if let Some(action) = self.pending_project_action.take() {
+            self.run_project_action(action);
         }
-    }
+        scene_editor::render_project_error_modal(ui, &mut self.project_error);
 
-    fn render_main_menu_bar(&mut self, ui: &Ui) {
-        if let Some(_menu_bar) = ui.begin_main_menu_bar() {
-            // File menu
-            if let Some(_file_menu) = ui.begin_menu("File") {
-                if ui.menu_item("New Project") {}
-                if ui.menu_item("Open Project") {}
-                if ui.menu_item("Save Project") {}
-                ui.separator();
-                if ui.menu_item("Exit") {
-                    std::process::exit(0);
-                }
+        // New or Load pressed inside a tab with unsaved changes
+        if self.pending_close.is_none() {
+            if let Some(id) = self.tabs.discard_request() {
+                self.request_close(vec![id], CloseKind::Replace);
             }
+        }
 
-            // View menu
-            if let Some(_view_menu) = ui.begin_menu("View") {
-                if ui.menu_item_config("Hierarchy")
-                    .selected(self.show_hierarchy)
-                    .build()
-                {
-                    self.show_hierarchy = !self.show_hierarchy;
-                }
-                if ui.menu_item_config("Inspector")
-                    .selected(self.show_inspector)
-                    .build()
-                {
-                    self.show_inspector = !self.show_inspector;
-                }
-                if ui.menu_item_config("Console")
-                    .selected(self.show_console)
-                    .build()
-                {
-                    self.show_console = !self.show_console;
-                }
-                if ui.menu_item_config("Asset Browser")
-                    .selected(self.show_asset_browser)
-                    .build()
-                {
-                    self.show_asset_browser = !self.show_asset_browser;
-                }
-            }
+        self.render_unsaved_changes_modal(ui);
+    }
 
-            // Help menu
-            if let Some(_help_menu) = ui.begin_menu("Help") {
-                if ui.menu_item("About") {}
-            }
+    /// Ask to exit the editor. Exits straight away unless tabs have unsaved changes,
+    /// in which case the user is asked first and may cancel.
+    pub fn request_exit(&mut self) {
+        // Closed tabs held by the tab history need no check: undo and redo only take
+        // a dirty tab out of the editor through this same prompt
+        let dirty = self.tabs.dirty_tabs();
+        self.request_close(dirty, CloseKind::Exit);
+    }
 
-            // FPS counter
-            let fps = crate::frame_counter::get_fps();
-            let fps_text = format!("FPS: {}", fps);
-            let text_size = ui.calc_text_size(&fps_text);
-            let menu_width = ui.io().display_size[0];
-            ui.set_cursor_pos([menu_width - text_size[0] - 16.0, 4.0]);
-            ui.text_colored(PulsarTheme::TEXT_SECONDARY, &fps_text);
-        }
+    /// Whether the user has confirmed exiting
+    pub fn should_exit(&self) -> bool {
+        self.should_exit
     }
 
-    fn render_separate_panels(&mut self, ui: &Ui, menu_height: f32, available_width: f32, available_height: f32) {
-        // Calculate panel positions and sizes
-        let center_x = self.left_panel_width;
-        let center_width = available_width - self.left_panel_width - self.right_panel_width;
-        let center_height = available_height - if self.show_console || self.show_asset_browser { self.bottom_panel_height } else { 0.0 };
+    /// Panels and tabs that want OS windows of their own
+    pub fn detached_windows(&self) -> Vec<DetachedWindow> {
+        let panels = self.panels.layout().detached.iter().filter_map(|detached| {
+            let panel = self.panels.panel(detached.panel)?;
+            Some(DetachedWindow {
+                content: DetachedContent::Panel(detached.panel),
+                title: format!("{} {}", panel.icon, panel.title),
+                position: detached.position,
+                size: detached.size,
+            })
+        });
+        let tabs = self.tabs.detached_tabs().into_iter().map(|(id, label, position)| DetachedWindow {
+            content: DetachedContent::Tab(id),
+            title: label,
+            position,
+            size: DETACHED_TAB_SIZE,
+        });
+        panels.chain(tabs).collect()
+    }
 
-        // Left panel - Hierarchy
-        if self.show_hierarchy {
-            ui.window("Hierarchy")
-                .position([0.0, menu_height], Condition::FirstUseEver)
-                .size([self.left_panel_width, center_height], Condition::FirstUseEver)
-                .flags(WindowFlags::NO_MOVE | WindowFlags::NO_COLLAPSE)
-                .resizable(true)
-                .build(|| {
-                    self.render_hierarchy_content(ui);
-                });
+    /// Draw a detached panel or tab filling its OS window. `origin` is where that
+    /// window is relative to the main one.
+    pub fn render_detached(&mut self, ui: &Ui, content: DetachedContent, origin: [f32; 2]) {
+        let display_size = ui.io().display_size;
+        if display_size[0] <= 0.0 || display_size[1] <= 0.0 {
+            return;
         }
+        let Some(window) = self.detached_windows().into_iter().find(|w| w.content == content) else { return };
 
-        // Center panel - Active tab content (always visible)
-        let window_title = format!("{} - {}", self.active_tab.icon(), self.active_tab.display_name());
-        ui.window(&window_title)
-            .position([center_x, menu_height], Condition::Always)
-            .size([center_width.max(200.0), center_height], Condition::Always)
-            .flags(WindowFlags::NO_MOVE | WindowFlags::NO_COLLAPSE | WindowFlags::NO_RESIZE)
+        ui.window("##Detached")
+            .position([0.0, 0.0], Condition::Always)
+            .size(display_size, Condition::Always)
+            .flags(WindowFlags::NO_DECORATION | WindowFlags::NO_MOVE | WindowFlags::NO_SAVED_SETTINGS)
             .build(|| {
-                self.render_active_tab_content(ui);
+                match detached_window::render_title_bar(ui, &window, origin, &mut self.detached_drag) {
+                    Some(DetachedBarAction::Move(position)) => self.move_detached(content, position, window.size),
+                    Some(DetachedBarAction::Drop(cursor)) => {
+                        if let Some(area) = LayoutManager::drop_target(self.content_area, cursor) {
+                            self.dock_detached(content, Some(area));
+                        }
+                    }
+                    Some(DetachedBarAction::Dock) => self.dock_detached(content, None),
+                    None => {}
+                }
+                ui.separator();
+
+                match content {
+                    DetachedContent::Panel(panel) => self.render_panel(ui, panel),
+                    DetachedContent::Tab(id) => self.tabs.render_detached(ui, id),
+                }
+                self.detached_item_active |= ui.is_any_item_active();
             });
+    }
 
-        // Right panel - Inspector
-        if self.show_inspector {
-            ui.window("Inspector")
-                .position([center_x + center_width, menu_height], Condition::FirstUseEver)
-                .size([self.right_panel_width, center_height], Condition::FirstUseEver)
-                .flags(WindowFlags::NO_MOVE | WindowFlags::NO_COLLAPSE)
-                .resizable(true)
-                .build(|| {
-                    self.render_inspector_content(ui);
-                });
+    /// Remember where a detached window was moved or resized to
+    pub fn move_detached(&mut self, content: DetachedContent, position: [f32; 2], size: [f32; 2]) {
+        match content {
+            DetachedContent::Panel(panel) => self.panels.move_detached(panel, position, size),
+            DetachedContent::Tab(id) => self.tabs.move_detached_tab(id, position),
         }
+    }
 
-        // Bottom panels (render these last to ensure proper Z-order)
-        let bottom_y = menu_height + center_height;
-        let bottom_width = available_width;
-
-        if self.show_console && self.show_asset_browser {
-            // Split bottom area between console and asset browser
-            let console_width = bottom_width * 0.6;
-            let asset_width = bottom_width * 0.4;
-
-            ui.window("Console")
-                .position([0.0, bottom_y], Condition::FirstUseEver)
-                .size([console_width, self.bottom_panel_height], Condition::FirstUseEver)
-                .flags(WindowFlags::NO_MOVE | WindowFlags::NO_COLLAPSE)
-                .resizable(true)
-                .build(|| {
-                    self.render_console_content(ui);
-                });
-
-            ui.window("Asset Browser")
-                .position([console_width, bottom_y], Condition::FirstUseEver)
-                .size([asset_width, self.bottom_panel_height], Condition::FirstUseEver)
-                .flags(WindowFlags::NO_MOVE | WindowFlags::NO_COLLAPSE)
-                .resizable(true)
-                .build(|| {
-                    self.render_asset_browser_content(ui);
-                });
-        } else if self.show_console {
-            ui.window("Console")
-                .position([0.0, bottom_y], Condition::FirstUseEver)
-                .size([bottom_width, self.bottom_panel_height], Condition::FirstUseEver)
-                .flags(WindowFlags::NO_MOVE | WindowFlags::NO_COLLAPSE)
-                .resizable(true)
-                .build(|| {
-                    self.render_console_content(ui);
-                });
-        } else if self.show_asset_browser {
-            ui.window("Asset Browser")
-                .position([0.0, bottom_y], Condition::FirstUseEver)
-                .size([bottom_width, self.bottom_panel_height], Condition::FirstUseEver)
-                .flags(WindowFlags::NO_MOVE | WindowFlags::NO_COLLAPSE)
-                .resizable(true)
-                .build(|| {
-                    self.render_asset_browser_content(ui);
-                });
+    /// The user closed a detached window. Tabs ask about unsaved changes first.
+    pub fn close_detached(&mut self, content: DetachedContent) {
+        match content {
+            DetachedContent::Panel(panel) => self.panels.close_panel(panel),
+            DetachedContent::Tab(id) => self.request_close(vec![id], CloseKind::Tabs),
         }
     }
 
-    fn render_hierarchy_content(&self, ui: &Ui) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "🌳 Scene Hierarchy");
-        ui.separator();
-
-        if let Some(_tree) = ui.tree_node("📁 Scene Root") {
-            if let Some(_player_tree) = ui.tree_node("🎮 Player") {
-                ui.text("🎯 PlayerController");
-                ui.text("📷 Camera");
-                ui.text("🎭 MeshRenderer");
-            }
-            if let Some(_env_tree) = ui.tree_node("🌍 Environment") {
-                ui.text("☀️ DirectionalLight");
-                ui.text("🏔️ Terrain");
-                ui.text("🌊 Water");
+    /// Put detached content back into the main window: panels into `area` or where
+    /// they came from, tabs into the tab bar
+    fn dock_detached(&mut self, content: DetachedContent, area: Option<DockArea>) {
+        match content {
+            DetachedContent::Panel(panel) => {
+                let home = self.panels.panel(panel).map_or(DockArea::Center, |p| p.home);
+                self.panels.dock_panel(panel, area.unwrap_or(home));
             }
+            DetachedContent::Tab(id) => self.tabs.dock_tab(id),
         }
     }
 
-    fn render_inspector_content(&self, ui: &Ui) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "🔍 Inspector");
-        ui.separator();
-
-        ui.text("Selected: None");
-        ui.spacing();
+    /// Open tabs and panel layout, to be restored on the next launch. The session
+    /// belongs to the active tab's project, or else the first open project.
+    pub fn session(&self) -> Session {
+        let (tabs, active_tab) = self.tabs.tab_states();
+        Session {
+            project: self.tabs.project_file().map(Path::to_path_buf),
+            tabs,
+            active_tab,
+            panels: self.panels.layout().clone(),
+            preferences: Preferences {
+                recent_actions: self.actions.recent().to_vec(),
+                log_to_file: logging::log_file().is_some(),
+                console_history: self.console_panel.history().to_vec(),
+            },
+            ..Session::default()
+        }
+    }
 
-        if ui.collapsing_header("Transform", TreeNodeFlags::DEFAULT_OPEN) {
-            ui.text("Position:");
-            let mut pos = [0.0f32, 0.0, 0.0];
-            ui.input_float3("##pos", &mut pos).build();
+    fn render_main_menu_bar(&mut self, ui: &Ui) {
+        let mut clicked = None;
+        if let Some(_menu_bar) = ui.begin_main_menu_bar() {
+            for menu in self.actions.menus() {
+                if let Some(_menu) = ui.begin_menu(&menu) {
+                    clicked = self.render_menu(ui, &menu).or(clicked);
+                }
+            }
 
-            ui.text("Rotation:");
-            let mut rot = [0.0f32, 0.0, 0.0];
-            ui.input_float3("##rot", &mut rot).build();
+            // FPS counter
+            let fps = crate::frame_counter::get_fps();
+            let fps_text = format!("FPS: {}", fps);
+            let text_size = ui.calc_text_size(&fps_text);
+            let menu_width = ui.io().display_size[0];
+            ui.set_cursor_pos([menu_width - text_size[0] - 16.0, 4.0]);
+            ui.text_colored(PulsarTheme::TEXT_SECONDARY, &fps_text);
+        }
 
-            ui.text("Scale:");
-            let mut scale = [1.0f32, 1.0, 1.0];
-            ui.input_float3("##scale", &mut scale).build();
+        if let Some(id) = clicked {
+            self.run_action(&id);
         }
     }
 
-    fn render_level_editor_content(&self, ui: &Ui) {
-        // Main toolbar with level operations
-        {
-            let _toolbar_bg = ui.push_style_color(StyleColor::ChildBg, PulsarTheme::DARKER_PANEL);
-            ui.child_window("LevelToolbar")
-                .size([0.0, 40.0])
-                .border(false)
-                .build(|| {
-                    ui.text("Level:");
-                    ui.same_line();
-
-                    {
-                        let _btn_color = ui.push_style_color(StyleColor::Button, PulsarTheme::BLUE_PRIMARY);
-                        if ui.button_with_size("🆕 New", [60.0, 28.0]) {}
+    /// Items of a menu laid out in the action registry. Returns the action clicked.
+    fn render_menu(&mut self, ui: &Ui, menu: &str) -> Option<String> {
+        let mut clicked = None;
+        if menu == editor_actions::LAYOUT_MENU {
+            self.layout.render_layout_menu(ui);
+        }
+        for entry in self.actions.menu_entries(menu) {
+            match entry {
+                MenuEntry::Separator => ui.separator(),
+                MenuEntry::Submenu(path) => {
+                    let name = path.rsplit('/').next().unwrap_or_default();
+                    if let Some(_submenu) = ui.begin_menu(name) {
+                        clicked = self.render_menu(ui, &path).or(clicked);
                     }
-                    ui.same_line();
-                    if ui.button_with_size("📁 Load", [60.0, 28.0]) {}
-                    ui.same_line();
-                    if ui.button_with_size("💾 Save", [60.0, 28.0]) {}
-                    ui.same_line();
-                    ui.text("|");
-                    ui.same_line();
-
+                }
+                MenuEntry::Action(id) => {
+                    let Some(action) = self.actions.get(&id) else { continue };
+                    let shortcut = action.shortcut().map(|s| s.to_string()).unwrap_or_default();
+                    if ui.menu_item_config(self.menu_label(&id, &action.name))
+                        .shortcut(shortcut)
+                        .selected(self.action_checked(&id))
+                        .enabled(self.action_enabled(&id))
+                        .build()
                     {
-                        let _play_color = ui.push_style_color(StyleColor::Button, [0.2, 0.7, 0.2, 1.0]);
-                        if ui.button_with_size("▶ Play", [60.0, 28.0]) {}
+                        clicked = Some(id);
                     }
-                    ui.same_line();
-                    if ui.button_with_size("🔨 Build", [60.0, 28.0]) {}
-                });
+                }
+            }
         }
+        clicked
+    }
 
-        ui.spacing();
+    /// Name of an action in the menu bar, which for undo and redo says what they undo
+    fn menu_label(&self, id: &str, name: &str) -> String {
+        let target = match id {
+            editor_actions::UNDO => self.undo_target().map(|t| self.history_label(t, true)),
+            editor_actions::REDO => self.redo_target().map(|t| self.history_label(t, false)),
+            _ => None,
+        };
+        match target {
+            Some(label) if !label.is_empty() => format!("{} {}", name, label),
+            _ => name.to_string(),
+        }
+    }
+
+    /// Whether an action can run now. Disabled actions are greyed out in menus and
+    /// the command palette, and their shortcuts do nothing.
+    fn action_enabled(&self, id: &str) -> bool {
+        match id {
+            editor_actions::CLOSE_TAB => self.tabs.active().is_some(),
+            editor_actions::SAVE | editor_actions::SAVE_AS => {
+                self.tabs.active().map_or(false, |tab| tab.content().can_save())
+            }
+            editor_actions::UNDO => self.undo_target().is_some(),
+            editor_actions::REDO => self.redo_target().is_some(),
+            _ if id.starts_with(editor_actions::VIEWPORT) => self.tabs.active().is_some(),
+            _ => match editor_actions::action_panel(id) {
+                Some(panel) => self.panels.panel(panel).map_or(false, |info| info.can_close),
+                None => true,
+            },
+        }
+    }
 
-        // Tool selection bar
-        {
-            let _tool_bg = ui.push_style_color(StyleColor::ChildBg, PulsarTheme::DARKER_PANEL);
-            ui.child_window("ToolBar")
-                .size([0.0, 40.0])
-                .border(false)
-                .build(|| {
-                    ui.text("Tools:");
-                    ui.same_line();
+    /// Whether a menu item shows a check mark, as open panels do
+    fn action_checked(&self, id: &str) -> bool {
+        editor_actions::action_panel(id).map_or(false, |panel| self.panels.is_open(panel))
+    }
 
-                    {
-                        let _select_color = ui.push_style_color(StyleColor::Button, PulsarTheme::TAB_ACTIVE);
-                        if ui.button_with_size("🎩 Select", [70.0, 28.0]) {}
+    fn run_action(&mut self, id: &str) {
+        self.actions.record_use(id);
+        match id {
+            editor_actions::NEW_PROJECT => self.pending_project_action = Some(ProjectAction::New),
+            editor_actions::OPEN_PROJECT => self.pending_project_action = Some(ProjectAction::Open),
+            editor_actions::NEW_TAB => self.tabs.show_new_tab_popup = true,
+            editor_actions::CLOSE_TAB => {
+                if let Some(tab) = self.tabs.active().map(Tab::id) {
+                    self.request_close(vec![tab], CloseKind::Tabs);
+                }
+            }
+            editor_actions::SAVE => self.pending_project_action = Some(ProjectAction::Save),
+            editor_actions::SAVE_AS => self.pending_project_action = Some(ProjectAction::SaveAs),
+            editor_actions::EXIT => self.request_exit(),
+            editor_actions::UNDO => self.undo(),
+            editor_actions::REDO => self.redo(),
+            editor_actions::KEYBOARD_SHORTCUTS => self.keybindings_window.open = true,
+            editor_actions::COMMAND_PALETTE => {
+                editor_actions::register_layout_actions(&mut self.actions, &self.layout.preset_names());
+                self.palette.open();
+            }
+            editor_actions::SAVE_LAYOUT_AS => self.layout.open_save_as(),
+            editor_actions::RESET_LAYOUT => self.layout.reset_layout(),
+            editor_actions::MANAGE_LAYOUTS => self.layout.open_manager(),
+            editor_actions::ABOUT => {}
+            _ => {
+                if id.starts_with(editor_actions::VIEWPORT) {
+                    if let Some(tab) = self.tabs.active_mut() {
+                        tab.content_mut().run_action(id);
                     }
-                    ui.same_line();
-                    if ui.button_with_size("↔ Move", [60.0, 28.0]) {}
-                    ui.same_line();
-                    if ui.button_with_size("🔄 Rotate", [70.0, 28.0]) {}
-                    ui.same_line();
-                    if ui.button_with_size("🔍 Scale", [60.0, 28.0]) {}
-                    ui.same_line();
-                    ui.text("|");
-                    ui.same_line();
-                    if ui.button_with_size("🎨 Paint", [60.0, 28.0]) {}
-                });
+                } else if id.starts_with(editor_actions::HIERARCHY) {
+                    if let Some(document) = self.tabs.active_mut().and_then(|tab| tab.content_mut().scene_document()) {
+                        self.hierarchy_panel.run_action(id, document.scene, document.history, document.selection);
+                    }
+                } else if id.starts_with(editor_actions::ASSETS) {
+                    self.asset_browser.run_action(id);
+                } else if let Some(panel) = editor_actions::action_panel(id) {
+                    let open = self.panels.is_open(panel);
+                    self.panels.set_open(panel, !open);
+                } else if let Some(name) = id.strip_prefix(editor_actions::APPLY_LAYOUT) {
+                    self.layout.request_layout(name);
+                } else if let Some(type_name) = id.strip_prefix(editor_actions::OPEN_TAB) {
+                    self.apply_tab_action(TabAction::Open(type_name.to_string()));
+                }
+            }
         }
+    }
 
-        ui.spacing();
-
-        // 3D Viewport with enhanced visuals
-        let pos = ui.cursor_screen_pos();
-        let avail = ui.content_region_avail();
-        let size = [avail[0] - 10.0, avail[1] - 10.0];
-
-        // Ensure positive size to avoid ClipRect assertion
-        if size[0] > 0.0 && size[1] > 0.0 {
-            // Main viewport background (deep space black)
-            ui.get_window_draw_list()
-                .add_rect(pos, [pos[0] + size[0], pos[1] + size[1]], PulsarTheme::PURE_BLACK)
-                .filled(true)
-                .build();
-
-            // Grid pattern for 3D viewport (subtle blue grid)
-            let grid_size = 40.0;
-            let grid_color = [0.05, 0.1, 0.15, 0.8];
-            for x in (0..(size[0] as i32)).step_by(grid_size as usize) {
-                ui.get_window_draw_list()
-                    .add_line([pos[0] + x as f32, pos[1]], [pos[0] + x as f32, pos[1] + size[1]], grid_color)
-                    .build();
-            }
-            for y in (0..(size[1] as i32)).step_by(grid_size as usize) {
-                ui.get_window_draw_list()
-                    .add_line([pos[0], pos[1] + y as f32], [pos[0] + size[0], pos[1] + y as f32], grid_color)
-                    .build();
+    fn render_panels(&mut self, ui: &Ui, area: [f32; 4], drop_target: Option<DockArea>) {
+        // The Level Editor panel is titled after the tab it shows
+        let title = match self.tabs.selected() {
+            Some(tab) => format!("{} - {}", tab.content().get_icon().unwrap_or(""), tab.content().get_title()),
+            None => "Editor".to_string(),
+        };
+        self.panels.set_title(EditorPanelType::LevelEditor, &title);
+
+        self.panels.begin_frame(ui, area);
+        for panel in self.panels.visible_panels() {
+            if let Some(_window) = self.panels.begin_panel(ui, panel) {
+                self.render_panel(ui, panel);
             }
+        }
+        self.panels.end_frame(ui, drop_target);
+    }
 
-            // Viewport border with blue glow
-            ui.get_window_draw_list()
-                .add_rect(pos, [pos[0] + size[0], pos[1] + size[1]], PulsarTheme::BLUE_PRIMARY)
-                .thickness(2.0)
-                .build();
-
-            // Viewport info overlay
-            let info_pos = [pos[0] + 10.0, pos[1] + 10.0];
-            ui.get_window_draw_list()
-                .add_text(info_pos, PulsarTheme::TEXT_SECONDARY, "3D Viewport");
-            ui.get_window_draw_list()
-                .add_text([info_pos[0], info_pos[1] + 20.0], PulsarTheme::TEXT_MUTED, "WASD: Move | Mouse: Look | F: Focus");
-
-            // 3D Gizmo at center (RGB axes)
-            let center = [pos[0] + size[0] / 2.0, pos[1] + size[1] / 2.0];
-            let gizmo_size = 15.0;
-
-            // X-axis (Red)
-            ui.get_window_draw_list()
-                .add_line([center[0], center[1]], [center[0] + gizmo_size, center[1]], [1.0, 0.3, 0.3, 1.0])
-                .thickness(3.0)
-                .build();
-            ui.get_window_draw_list()
-                .add_circle([center[0] + gizmo_size, center[1]], 4.0, [1.0, 0.2, 0.2, 1.0])
-                .filled(true)
-                .build();
-
-            // Y-axis (Green)
-            ui.get_window_draw_list()
-                .add_line([center[0], center[1]], [center[0], center[1] - gizmo_size], [0.3, 1.0, 0.3, 1.0])
-                .thickness(3.0)
-                .build();
-            ui.get_window_draw_list()
-                .add_circle([center[0], center[1] - gizmo_size], 4.0, [0.2, 1.0, 0.2, 1.0])
-                .filled(true)
-                .build();
-
-            // Z-axis (Blue)
-            ui.get_window_draw_list()
-                .add_line([center[0], center[1]], [center[0] - gizmo_size * 0.7, center[1] + gizmo_size * 0.7], [0.3, 0.3, 1.0, 1.0])
-                .thickness(3.0)
-                .build();
-            ui.get_window_draw_list()
-                .add_circle([center[0] - gizmo_size * 0.7, center[1] + gizmo_size * 0.7], 4.0, [0.2, 0.2, 1.0, 1.0])
-                .filled(true)
-                .build();
-
-            // Center origin point
-            ui.get_window_draw_list()
-                .add_circle(center, 6.0, PulsarTheme::TEXT_PRIMARY)
-                .filled(true)
-                .build();
-
-            ui.dummy(size);
-        }
-    }
-
-    fn render_console_content(&self, ui: &Ui) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "💻 Console");
-        ui.separator();
-
-        ui.child_window("ConsoleOutput")
-            .size([0.0, -30.0])
-            .build(|| {
-                ui.text_colored([0.5, 1.0, 0.5, 1.0], "[INFO] Pulsar Engine initialized");
-                ui.text_colored([1.0, 1.0, 0.5, 1.0], "[WARN] Missing texture: default.png");
-                ui.text_colored([1.0, 0.5, 0.5, 1.0], "[ERROR] Failed to load script: test.rs");
-                ui.text_colored([0.5, 1.0, 0.5, 1.0], "[INFO] Level loaded successfully");
-            });
+    fn render_panel(&mut self, ui: &Ui, panel: EditorPanelType) {
+        match panel {
+            EditorPanelType::LevelEditor => {
+                self.render_tab_bar(ui);
+                self.tabs.render_active(ui);
+            }
+            EditorPanelType::Hierarchy => self.render_hierarchy_content(ui),
+            EditorPanelType::Inspector => self.render_inspector_content(ui),
+            EditorPanelType::Console => self.console_panel.render(ui),
+            EditorPanelType::AssetBrowser => {
+                self.asset_browser.set_project(self.tabs.project_file());
+                self.asset_browser.render(ui);
+            }
+            _ => {
+                let content_region = ui.content_region_avail();
+                let document = self.tabs.active_mut().and_then(|tab| tab.content_mut().scene_document());
+                let mut ctx = document.map(|document| PanelContext {
+                    scene: document.scene,
+                    history: document.history,
+                    selection: document.selection,
+                    hierarchy: &mut self.hierarchy_panel,
+                    inspector: &mut self.inspector_panel,
+                });
+                EditorPanelRenderer::render_panel_content(ui, panel, content_region, ctx.as_mut());
+            }
+        }
+    }
 
-        ui.separator();
-        let mut command = String::new();
-        ui.input_text("Command", &mut command).build();
+    fn render_hierarchy_content(&mut self, ui: &Ui) {
+        let Some(document) = self.tabs.active_mut().and_then(|tab| tab.content_mut().scene_document()) else {
+            ui.text_colored(PulsarTheme::TEXT_MUTED, "The active tab has no scene");
+            return;
+        };
+
+        // While playing, flag the entities that differ from the edited scene
+        let colors = match &document.play_session {
+            Some(session) => session
+                .changed_entities(document.scene)
+                .into_iter()
+                .map(|id| (id, if session.is_kept(id) { PulsarTheme::PLAY_KEPT } else { PulsarTheme::PLAY_TEMPORARY }))
+                .collect(),
+            None => HashMap::new(),
+        };
+        self.hierarchy_panel.set_label_colors(colors);
+        self.hierarchy_panel.render(ui, document.scene, document.history, document.selection);
     }
 
-    fn render_asset_browser_content(&self, ui: &Ui) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "📁 Asset Browser");
-        ui.separator();
+    fn render_inspector_content(&mut self, ui: &Ui) {
+        let Some(document) = self.tabs.active_mut().and_then(|tab| tab.content_mut().scene_document()) else {
+            ui.text_colored(PulsarTheme::TEXT_MUTED, "The active tab has no scene");
+            return;
+        };
+
+        if let Some(session) = document.play_session {
+            ui.text_colored(PulsarTheme::PLAY_TEMPORARY, "▶ Playing: edits are discarded on Stop");
+            let keepable: Vec<EntityId> = document.selection.iter().filter(|id| session.can_keep(*id)).collect();
+            if !keepable.is_empty() {
+                let mut keep = keepable.iter().all(|id| session.is_kept(*id));
+                if ui.checkbox("📌 Keep changes to selection", &mut keep) {
+                    for id in keepable {
+                        session.set_kept(id, keep);
+                    }
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Copy the name and components of the selected entities back into the scene when play stops");
+                }
+            }
+            ui.separator();
+        }
+        self.inspector_panel.render(ui, document.scene, document.history, document.selection);
+    }
 
-        ui.child_window("AssetGrid")
-            .size([0.0, -30.0])
+    fn render_tab_bar(&mut self, ui: &Ui) {
+        let mut action = None;
+        ui.child_window("TabBar")
+            .size([0.0, 34.0])
+            .scroll_bar(false)
             .build(|| {
-                let assets = vec!["texture1.png", "model.fbx", "audio.wav", "script.rs", "material.mat"];
-                for (i, asset) in assets.iter().enumerate() {
-                    if i > 0 && i % 3 != 0 { ui.same_line(); }
-
-                    let pos = ui.cursor_screen_pos();
-                    let thumbnail_size = [48.0, 48.0];
-
-                    // Asset thumbnail
-                    ui.get_window_draw_list()
-                        .add_rect(pos, [pos[0] + thumbnail_size[0], pos[1] + thumbnail_size[1]], PulsarTheme::DARK_PANEL)
-                        .filled(true)
-                        .build();
-
-                    ui.dummy(thumbnail_size);
-                    ui.text(asset);
-                    if i % 3 == 2 { ui.spacing(); }
-                }
+                action = self.tabs.render_tab_bar(ui);
             });
+        if let Some(action) = action {
+            self.apply_tab_action(action);
+        }
     }
 
-    fn render_tab_bar(&mut self, ui: &Ui, menu_height: f32, available_width: f32) {
-        ui.window("Tab Bar")
-            .position([0.0, menu_height], Condition::Always)
-            .size([available_width, 35.0], Condition::Always)
-            .flags(WindowFlags::NO_DECORATION | WindowFlags::NO_MOVE | WindowFlags::NO_RESIZE)
-            .build(|| {
-                // Render existing tabs - each in their own scope to ensure proper token cleanup
-                for (i, tab) in self.available_tabs.clone().iter().enumerate() {
-                    if i > 0 {
-                        ui.same_line();
-                    }
+    /// Opening and closing tabs goes through the tab history so it can be undone;
+    /// rearranging them does not
+    fn apply_tab_action(&mut self, action: TabAction) {
+        match action {
+            TabAction::Activate(id) => self.tabs.set_active(id),
+            TabAction::Open(type_name) => self.tab_history.execute(&mut self.tabs, Box::new(OpenTab::new(&type_name))),
+            TabAction::Close(id) => self.request_close(vec![id], CloseKind::Tabs),
+            TabAction::Move { id, to } => self.tabs.move_tab(id, to),
+            TabAction::SetPinned(id, pinned) => self.tabs.set_pinned(id, pinned),
+            TabAction::Float(id, position) => self.tabs.float_tab(id, position),
+            TabAction::Detach(id, position) => self.tabs.detach_tab(id, position),
+            TabAction::Dock(id) => self.tabs.dock_tab(id),
+        }
+    }
 
-                    let is_active = *tab == self.active_tab;
-                    let tab_label = format!("{} {}", tab.icon(), tab.display_name());
+    /// Close `tabs`, or exit, asking first if any of them have unsaved changes
+    fn request_close(&mut self, tabs: Vec<TabId>, kind: CloseKind) {
+        let close = PendingClose { tabs, kind };
+        if self.unsaved_tabs(&close).is_empty() {
+            self.finish_close(close);
+        } else {
+            self.pending_close = Some(close);
+        }
+    }
 
-                    // Each tab button in its own scope for proper token management
-                    {
-                        let _button_token = if is_active {
-                            ui.push_style_color(StyleColor::Button, PulsarTheme::TAB_ACTIVE)
-                        } else {
-                            ui.push_style_color(StyleColor::Button, PulsarTheme::TAB_INACTIVE)
-                        };
-
-                        let _hover_token = if is_active {
-                            ui.push_style_color(StyleColor::ButtonHovered, PulsarTheme::BLUE_HOVER)
-                        } else {
-                            ui.push_style_color(StyleColor::ButtonHovered, PulsarTheme::TAB_HOVER)
-                        };
-
-                        if ui.button_with_size(&tab_label, [150.0, 28.0]) {
-                            self.active_tab = tab.clone();
-                        }
-                        // Tokens automatically dropped at end of scope
-                    }
+    fn unsaved_tabs(&self, close: &PendingClose) -> Vec<TabId> {
+        let dirty = self.tabs.dirty_tabs();
+        close.tabs.iter().copied().filter(|id| dirty.contains(id)).collect()
+    }
 
-                    // Close button for tabs (except if it's the only one)
-                    if self.available_tabs.len() > 1 {
-                        ui.same_line();
-                        let should_remove = {
-                            let _close_token = ui.push_style_color(StyleColor::Button, [0.6, 0.2, 0.2, 0.8]);
-                            ui.small_button(&format!("×##{}", i))
-                            // _close_token automatically dropped here
-                        };
-
-                        if should_remove {
-                            // Remove this tab
-                            if is_active && self.available_tabs.len() > 1 {
-                                // Switch to another tab before removing
-                                let next_index = if i > 0 { i - 1 } else { 1 };
-                                if next_index < self.available_tabs.len() {
-                                    self.active_tab = self.available_tabs[next_index].clone();
-                                }
-                            }
-                            self.available_tabs.remove(i);
-                            return; // Exit early to avoid index issues
-                        }
+    fn finish_close(&mut self, close: PendingClose) {
+        match close.kind {
+            CloseKind::Tabs => {
+                for id in close.tabs {
+                    if let Some(command) = CloseTab::new(&self.tabs, id) {
+                        self.tab_history.execute(&mut self.tabs, Box::new(command));
                     }
                 }
+            }
+            CloseKind::Exit => self.should_exit = true,
+            CloseKind::Replace => self.resume_discard(&close.tabs, true),
+            CloseKind::Undo => {
+                self.tab_history.undo(&mut self.tabs);
+            }
+            CloseKind::Redo => {
+                self.tab_history.redo(&mut self.tabs);
+            }
+        }
+    }
 
-                // Add new tab button - in its own scope
-                {
-                    ui.same_line();
-                    let _add_button_token = ui.push_style_color(StyleColor::Button, PulsarTheme::BLUE_PRIMARY);
-                    let _add_hover_token = ui.push_style_color(StyleColor::ButtonHovered, PulsarTheme::BLUE_HOVER);
-
-                    if ui.button_with_size("+ Add Tab", [100.0, 28.0]) {
-                        self.show_tab_search = true;
-                        self.tab_search_query.clear();
-                    }
-
-                    if ui.is_item_hovered() {
-                        ui.tooltip_text("Add new editor tab (Ctrl+T)");
-                    }
-                    // Tokens automatically dropped at end of scope
-                }
-            });
+    /// Let tabs waiting on the prompt go ahead with, or drop, what they were doing
+    fn resume_discard(&mut self, ids: &[TabId], proceed: bool) {
+        for &id in ids {
+            if let Some(tab) = self.tabs.get_mut(id) {
+                tab.content_mut().resume_discard(proceed);
+            }
+        }
     }
 
-    fn render_tab_search_modal(&mut self, ui: &Ui) {
-        // Create a modal popup with proper visibility
-        let center = ui.io().display_size;
+    /// Save every listed tab that can save. Returns false, showing the tab, if one
+    /// of them is still dirty afterwards.
+    fn save_tabs(&mut self, ids: &[TabId]) -> bool {
+        for &id in ids {
+            let Some(tab) = self.tabs.get_mut(id) else { continue };
+            if !tab.content().can_save() {
+                continue;
+            }
+            tab.content_mut().save(false);
+            if tab.content().is_dirty() {
+                self.tabs.set_active(id);
+                return false;
+            }
+        }
+        true
+    }
 
-        // Draw a semi-transparent overlay behind the modal
-        let draw_list = ui.get_background_draw_list();
-        draw_list
-            .add_rect([0.0, 0.0], center, [0.0, 0.0, 0.0, 0.7])
-            .filled(true)
-            .build();
+    /// "Save / Don't Save / Cancel" prompt for the tabs of a pending close or exit
+    fn render_unsaved_changes_modal(&mut self, ui: &Ui) {
+        let Some(close) = &self.pending_close else { return };
+        let unsaved = self.unsaved_tabs(close);
+        let kind = close.kind;
 
-        // Modal window with higher z-order
-        ui.window("Add Editor Tab")
-            .position([center[0] * 0.5 - 300.0, center[1] * 0.5 - 250.0], Condition::Always)
-            .size([600.0, 500.0], Condition::Always)
-            .flags(WindowFlags::NO_RESIZE | WindowFlags::NO_MOVE | WindowFlags::NO_COLLAPSE | WindowFlags::ALWAYS_AUTO_RESIZE)
-            .focus_on_appearing(true)
+        let mut choice = None;
+        let shown = ui.modal_popup_config("Unsaved Changes")
+            .always_auto_resize(true)
             .build(|| {
-                // Title with larger text
-                {
-                    let _title_color = ui.push_style_color(StyleColor::Text, PulsarTheme::BLUE_PRIMARY);
-                    ui.text("🎯 Choose Editor to Add");
-                }
-                ui.separator();
+                ui.text(match kind {
+                    CloseKind::Tabs | CloseKind::Undo | CloseKind::Redo => "Save changes before closing?",
+                    CloseKind::Exit => "Save changes before exiting?",
+                    CloseKind::Replace => "Save changes before replacing the document?",
+                });
                 ui.spacing();
-
-                // Search input with focus
-                ui.text("Search:");
-                if ui.input_text("##search", &mut self.tab_search_query)
-                    .auto_select_all(true)
-                    .build()
-                {
-                    // Auto focus on first load
+                for id in &unsaved {
+                    let Some(tab) = self.tabs.get(*id) else { continue };
+                    ui.bullet_text(tab.label());
+                    if !tab.content().can_save() {
+                        ui.same_line();
+                        ui.text_colored(PulsarTheme::TEXT_MUTED, "(can't be saved, changes will be lost)");
+                    }
                 }
                 ui.spacing();
-
-                // Filter available tabs
-                let all_tabs = EditorTab::all_tabs();
-                let filtered_tabs: Vec<EditorTab> = all_tabs
-                    .into_iter()
-                    .filter(|tab| {
-                        // Don't show tabs that are already open
-                        !self.available_tabs.contains(tab) &&
-                        // Filter by search query
-                        (self.tab_search_query.is_empty() ||
-                         tab.display_name().to_lowercase().contains(&self.tab_search_query.to_lowercase()) ||
-                         tab.description().to_lowercase().contains(&self.tab_search_query.to_lowercase()))
-                    })
-                    .collect();
-
-                // Scrollable list of tabs with better styling
-                ui.child_window("TabList")
-                    .size([0.0, -80.0])
-                    .border(true)
-                    .build(|| {
-                        for tab in &filtered_tabs {
-                            let label = format!("{} {}", tab.icon(), tab.display_name());
-
-                            {
-                                let _button_color = ui.push_style_color(StyleColor::Button, PulsarTheme::DARKER_PANEL);
-                                let _hover_color = ui.push_style_color(StyleColor::ButtonHovered, PulsarTheme::BLUE_PRIMARY);
-
-                                if ui.button_with_size(&label, [550.0, 45.0]) {
-                                    self.available_tabs.push(tab.clone());
-                                    self.active_tab = tab.clone();
-                                    self.show_tab_search = false;
-                                    return;
-                                }
-                            }
-
-                            // Show description below
-                            ui.text_colored(PulsarTheme::TEXT_MUTED, &format!("    {}", tab.description()));
-                            ui.spacing();
-                        }
-
-                        if filtered_tabs.is_empty() {
-                            ui.text_colored(PulsarTheme::TEXT_MUTED, "❌ No tabs available or all tabs already open");
-                        }
-                    });
-
                 ui.separator();
-                ui.spacing();
 
-                // Bottom buttons
-                {
-                    let _cancel_color = ui.push_style_color(StyleColor::Button, [0.6, 0.2, 0.2, 1.0]);
-                    if ui.button_with_size("Cancel", [80.0, 30.0]) {
-                        self.show_tab_search = false;
-                    }
+                if ui.button_with_size("💾 Save", [100.0, 28.0]) {
+                    choice = Some(UnsavedChoice::Save);
                 }
                 ui.same_line();
-                ui.text_colored(PulsarTheme::TEXT_MUTED, "💡 Tip: Use Ctrl+T to quickly add tabs");
-
-                // Close on Escape key
-                if ui.is_key_pressed(imgui::Key::Escape) {
-                    self.show_tab_search = false;
+                if ui.button_with_size("Don't Save", [100.0, 28.0]) {
+                    choice = Some(UnsavedChoice::DontSave);
                 }
-            });
-    }
+                ui.same_line();
+                if ui.button_with_size("Cancel", [100.0, 28.0]) || ui.is_key_pressed(Key::Escape) {
+                    choice = Some(UnsavedChoice::Cancel);
+                }
+                if choice.is_some() {
+                    ui.close_current_popup();
+                }
+            })
+            .is_some();
 
-    fn render_active_tab_content(&self, ui: &Ui) {
-        match self.active_tab {
-            EditorTab::LevelEditor => self.render_level_editor_content(ui),
-            EditorTab::ScriptEditor => self.render_script_editor_content(ui),
-            EditorTab::BlueprintEditor => self.render_blueprint_editor_content(ui),
-            EditorTab::MaterialEditor => self.render_material_editor_content(ui),
-            EditorTab::AnimationEditor => self.render_animation_editor_content(ui),
-            EditorTab::ParticleEditor => self.render_particle_editor_content(ui),
-            EditorTab::AudioEditor => self.render_audio_editor_content(ui),
-            EditorTab::TerrainEditor => self.render_terrain_editor_content(ui),
-            EditorTab::PhysicsDebug => self.render_physics_debug_content(ui),
-            EditorTab::Profiler => self.render_profiler_content(ui),
+        // Opens from the next frame
+        if !shown {
+            ui.open_popup("Unsaved Changes");
         }
-    }
-
-    // Additional tab content renderers
-    fn render_script_editor_content(&self, ui: &Ui) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "📜 Script Editor");
-        ui.separator();
 
-        ui.text("Write and debug game scripts in Rust");
-        ui.spacing();
-
-        // Code editor placeholder
-        let mut code = String::from("// Pulsar Engine Script\nfn main() {\n    println!(\"Hello, Pulsar!\");\n}");
-        ui.input_text_multiline("##code", &mut code, [0.0, 300.0]).build();
-    }
-
-    fn render_blueprint_editor_content(&self, ui: &Ui) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "🔧 Blueprint Editor");
-        ui.separator();
-
-        ui.text("Visual scripting system for game logic");
-        ui.spacing();
-
-        // Node graph placeholder
-        let pos = ui.cursor_screen_pos();
-        let size = [600.0, 400.0];
-
-        ui.get_window_draw_list()
-            .add_rect(pos, [pos[0] + size[0], pos[1] + size[1]], PulsarTheme::PURE_BLACK)
-            .filled(true)
-            .build();
-
-        ui.get_window_draw_list()
-            .add_rect(pos, [pos[0] + size[0], pos[1] + size[1]], PulsarTheme::SUBTLE_BORDER)
-            .build();
-
-        ui.dummy(size);
+        let Some(choice) = choice else { return };
+        let Some(close) = self.pending_close.take() else { return };
+        match choice {
+            UnsavedChoice::Save => {
+                // A failed or cancelled save aborts the close, leaving that tab in front
+                if self.save_tabs(&unsaved) {
+                    self.finish_close(close);
+                } else if close.kind == CloseKind::Replace {
+                    self.resume_discard(&close.tabs, false);
+                }
+            }
+            UnsavedChoice::DontSave => self.finish_close(close),
+            UnsavedChoice::Cancel => {
+                if close.kind == CloseKind::Replace {
+                    self.resume_discard(&close.tabs, false);
+                }
+            }
+        }
     }
 
-    fn render_material_editor_content(&self, ui: &Ui) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "🎨 Material Editor");
-        ui.separator();
-
-        // Material properties
-        let mut albedo = [1.0f32, 1.0, 1.0, 1.0];
-        ui.color_edit4("Albedo", &mut albedo);
-
-        let mut metallic = 0.0f32;
-        ui.slider("Metallic", 0.0, 1.0, &mut metallic);
-
-        let mut roughness = 0.5f32;
-        ui.slider("Roughness", 0.0, 1.0, &mut roughness);
+    fn run_project_action(&mut self, action: ProjectAction) {
+        match action {
+            ProjectAction::New => self.apply_tab_action(TabAction::Open(editor_tabs::LEVEL_EDITOR.to_string())),
+            ProjectAction::Open => {
+                if let Some(path) = project::pick_open_path("Open Project") {
+                    let mut editor = SceneEditor::new();
+                    match editor.open_project(path.clone()) {
+                        Ok(()) => {
+                            let command = OpenTab::with_content(&mut self.tabs, editor_tabs::LEVEL_EDITOR, Box::new(editor));
+                            self.tab_history.execute(&mut self.tabs, Box::new(command));
+                            self.restore_project_session(&path);
+                        }
+                        Err(e) => self.project_error = Some(e),
+                    }
+                }
+            }
+            ProjectAction::Save | ProjectAction::SaveAs => {
+                if let Some(tab) = self.tabs.active_mut() {
+                    tab.content_mut().save(action == ProjectAction::SaveAs);
+                }
+            }
+        }
     }
 
-    fn render_animation_editor_content(&self, ui: &Ui) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "🎬 Animation Editor");
-        ui.separator();
-
-        // Timeline
-        let mut timeline = 0.0f32;
-        ui.slider("Timeline", 0.0, 10.0, &mut timeline);
-
-        ui.spacing();
-        ui.text("Animation Tracks:");
-        ui.bullet_text("Walk Cycle");
-        ui.bullet_text("Run Cycle");
-        ui.bullet_text("Attack Animation");
+    /// Bring back the panel layout and the other files open when `project` was last
+    /// worked on, keeping the project's own tab in front
+    fn restore_project_session(&mut self, project: &Path) {
+        let Some(saved) = session::load_project(project) else { return };
+        let opened = self.tabs.active().map(Tab::id);
+        let (open_tabs, _) = self.tabs.tab_states();
+        // Tabs without a file would only add empty documents
+        for state in &saved.tabs {
+            let Some(file) = &state.file else { continue };
+            if !open_tabs.iter().any(|open| open.file.as_ref() == Some(file)) {
+                self.tabs.restore_tab(state);
+            }
+        }
+        if let Some(id) = opened {
+            self.tabs.set_active(id);
+        }
+        self.panels.set_layout(saved.panels);
     }
+}
 
-    fn render_particle_editor_content(&self, ui: &Ui) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "✨ Particle Editor");
-        ui.separator();
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum UnsavedChoice {
+    Save,
+    DontSave,
+    Cancel,
+}
 
-        let mut emission_rate = 100.0f32;
-        ui.slider("Emission Rate", 1.0, 1000.0, &mut emission_rate);
+/// Which undo history an Edit > Undo/Redo applies to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HistoryTarget {
+    Document, // the active tab's own history
+    Tabs,
+}
 
-        let mut lifetime = 2.0f32;
-        ui.slider("Particle Lifetime", 0.1, 10.0, &mut lifetime);
+impl SimpleGameUI {
+    /// Run the action whose shortcut was pressed this frame, in the scope keyboard
+    /// focus is in
+    fn handle_shortcuts(&mut self, ui: &Ui) {
+        let focus = keybindings::take_focus(ui);
+        if self.keybindings_window.is_capturing() {
+            return;
+        }
+        let pressed = self.actions.pressed(ui, focus).map(|action| action.id.clone());
+        if let Some(id) = pressed.filter(|id| self.action_enabled(id)) {
+            self.run_action(&id);
+        }
     }
 
-    fn render_audio_editor_content(&self, ui: &Ui) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "🔊 Audio Editor");
-        ui.separator();
-
-        let mut master_volume = 0.8f32;
-        ui.slider("Master Volume", 0.0, 1.0, &mut master_volume);
-
-        let mut music_volume = 0.6f32;
-        ui.slider("Music Volume", 0.0, 1.0, &mut music_volume);
+    /// The history holding the most recent edit
+    fn undo_target(&self) -> Option<HistoryTarget> {
+        let document = self.tabs.active().and_then(|tab| tab.content().undo_sequence());
+        match (document, self.tab_history.undo_sequence()) {
+            (Some(document), Some(tabs)) => Some(if document > tabs { HistoryTarget::Document } else { HistoryTarget::Tabs }),
+            (Some(_), None) => Some(HistoryTarget::Document),
+            (None, Some(_)) => Some(HistoryTarget::Tabs),
+            (None, None) => None,
+        }
     }
 
-    fn render_terrain_editor_content(&self, ui: &Ui) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "🏔️ Terrain Editor");
-        ui.separator();
-
-        let mut brush_size = 10.0f32;
-        ui.slider("Brush Size", 1.0, 50.0, &mut brush_size);
-
-        let mut strength = 0.5f32;
-        ui.slider("Strength", 0.0, 1.0, &mut strength);
+    /// The history holding the most recently undone edit
+    fn redo_target(&self) -> Option<HistoryTarget> {
+        let document = self.tabs.active().and_then(|tab| tab.content().redo_sequence());
+        match (document, self.tab_history.redo_sequence()) {
+            (Some(document), Some(tabs)) => Some(if document < tabs { HistoryTarget::Document } else { HistoryTarget::Tabs }),
+            (Some(_), None) => Some(HistoryTarget::Document),
+            (None, Some(_)) => Some(HistoryTarget::Tabs),
+            (None, None) => None,
+        }
     }
 
-    fn render_physics_debug_content(&self, ui: &Ui) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "⚡ Physics Debug");
-        ui.separator();
-
-        ui.checkbox("Show Colliders", &mut true);
-        ui.checkbox("Show Velocities", &mut false);
-        ui.checkbox("Show Forces", &mut false);
+    fn history_label(&self, target: HistoryTarget, undo: bool) -> String {
+        let document = self.tabs.active().map(|tab| tab.content());
+        let label = match (target, undo) {
+            (HistoryTarget::Document, true) => document.and_then(|d| d.undo_label()),
+            (HistoryTarget::Document, false) => document.and_then(|d| d.redo_label()),
+            (HistoryTarget::Tabs, true) => self.tab_history.undo_label(),
+            (HistoryTarget::Tabs, false) => self.tab_history.redo_label(),
+        };
+        label.unwrap_or_default()
     }
 
-    fn render_profiler_content(&self, ui: &Ui) {
-        ui.text_colored(PulsarTheme::TEXT_PRIMARY, "📊 Profiler");
-        ui.separator();
-
-        ui.text("CPU Usage: 45%");
-        ProgressBar::new(0.45).build(ui);
-
-        ui.text("GPU Usage: 62%");
-        ProgressBar::new(0.62).build(ui);
-
-        ui.text("Memory: 1.2GB / 8.0GB");
-        ProgressBar::new(0.15).build(ui);
+    fn undo(&mut self) {
+        match self.undo_target() {
+            Some(HistoryTarget::Document) => {
+                if let Some(tab) = self.tabs.active_mut() {
+                    tab.content_mut().undo();
+                }
+            }
+            Some(HistoryTarget::Tabs) => {
+                // Undoing an open closes the tab, so it gets the same prompt as closing it
+                let opened = self.tab_history.undo_command()
+                    .and_then(|command| command.as_any().downcast_ref::<OpenTab>())
+                    .and_then(|command| command.tab_id());
+                match opened {
+                    Some(id) => self.request_close(vec![id], CloseKind::Undo),
+                    None => {
+                        self.tab_history.undo(&mut self.tabs);
+                    }
+                }
+            }
+            None => {}
+        }
     }
-}
 
-impl SimpleGameUI {
-    /// Update panel sizes based on window size for responsive resizing
-    fn update_responsive_panel_sizes(&mut self, available_width: f32, available_height: f32) {
-        // Calculate responsive panel sizes based on screen size
-        let base_left_width = (available_width * 0.18).max(150.0).min(available_width * 0.35);
-        let base_right_width = (available_width * 0.22).max(200.0).min(available_width * 0.35);
-        let base_bottom_height = (available_height * 0.25).max(100.0).min(available_height * 0.45);
-
-        // Smoothly transition to new sizes for better performance
-        let transition_speed = 0.1;
-        self.left_panel_width = self.left_panel_width + (base_left_width - self.left_panel_width) * transition_speed;
-        self.right_panel_width = self.right_panel_width + (base_right_width - self.right_panel_width) * transition_speed;
-        self.bottom_panel_height = self.bottom_panel_height + (base_bottom_height - self.bottom_panel_height) * transition_speed;
-
-        // Ensure minimum and maximum constraints
-        self.left_panel_width = self.left_panel_width.max(150.0).min(available_width * 0.4);
-        self.right_panel_width = self.right_panel_width.max(200.0).min(available_width * 0.4);
-        self.bottom_panel_height = self.bottom_panel_height.max(100.0).min(available_height * 0.5);
+    fn redo(&mut self) {
+        match self.redo_target() {
+            Some(HistoryTarget::Document) => {
+                if let Some(tab) = self.tabs.active_mut() {
+                    tab.content_mut().redo();
+                }
+            }
+            Some(HistoryTarget::Tabs) => {
+                let closed = self.tab_history.redo_command()
+                    .and_then(|command| command.as_any().downcast_ref::<CloseTab>())
+                    .map(|command| command.tab_id());
+                match closed {
+                    Some(id) => self.request_close(vec![id], CloseKind::Redo),
+                    None => {
+                        self.tab_history.redo(&mut self.tabs);
+                    }
+                }
+            }
+            None => {}
+        }
     }
 }
 
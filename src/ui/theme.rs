@@ -38,6 +38,14 @@ impl PulsarTheme {
     pub const SELECTION_HOVER: [f32; 4] = [0.145, 0.388, 0.922, 0.6];
     pub const SELECTION_ACTIVE: [f32; 4] = [0.145, 0.388, 0.922, 0.8];
 
+    // Play mode
+    pub const PLAY_GREEN: [f32; 4] = [0.2, 0.7, 0.2, 1.0];
+    pub const PLAY_TEMPORARY: [f32; 4] = [1.0, 0.65, 0.2, 1.0]; // edits that are discarded on stop
+    pub const PLAY_KEPT: [f32; 4] = [0.4, 0.85, 0.45, 1.0];
+
+    // Problems the user should fix, such as conflicting shortcuts
+    pub const WARNING: [f32; 4] = [0.95, 0.35, 0.3, 1.0];
+
     // Scrollbar
     pub const SCROLLBAR_BG: [f32; 4] = [0.01, 0.01, 0.01, 0.5];
     pub const SCROLLBAR_GRAB: [f32; 4] = [0.2, 0.2, 0.2, 0.5];
@@ -178,8 +186,7 @@ pub struct VisualEffects;
 
 impl VisualEffects {
     /// Draw a subtle glow effect around a rectangle
-    pub fn draw_glow(ui: &Ui, pos: [f32; 2], size: [f32; 2], color: [f32; 4], intensity: f32) {
-        let draw_list = ui.get_window_draw_list();
+    pub fn draw_glow(draw_list: &DrawListMut, pos: [f32; 2], size: [f32; 2], color: [f32; 4], intensity: f32) {
         let glow_size = 4.0 * intensity;
 
         // Multiple layers for smooth glow effect
@@ -0,0 +1,287 @@
+use std::time::Instant;
+use imgui::*;
+use imgui_wgpu::Renderer;
+use imgui_winit_support::{HiDpiMode, WinitPlatform};
+use winit::{
+    dpi::{LogicalSize, PhysicalPosition, PhysicalSize},
+    event::Event,
+    event_loop::EventLoopWindowTarget,
+    window::{Window, WindowBuilder},
+};
+
+use crate::console::{self, Value};
+use crate::scene_renderer::SceneRenderer;
+use crate::textures;
+use crate::ui;
+
+/// Format of every window's surface, so one imgui renderer can draw into all of them
+pub const SURFACE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8UnormSrgb;
+
+/// Console variable turning vertical sync on or off
+pub const VSYNC: &str = "vsync";
+
+/// Add the windows' console variables
+pub fn register_cvars() {
+    console::register_cvar(VSYNC, "Wait for the display to refresh before showing a frame", Value::Bool(true));
+}
+
+/// GPU objects shared by every window
+pub struct Gpu {
+    pub instance: wgpu::Instance,
+    pub adapter: wgpu::Adapter,
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+}
+
+/// An OS window with its own surface, imgui context and winit platform state. All
+/// windows draw with the same device, queue and imgui `Renderer`, so textures and
+/// 3D viewports can be shown in any of them.
+pub struct Viewport {
+    // Dropped before the window it was created from
+    surface: wgpu::Surface,
+    config: wgpu::SurfaceConfiguration,
+    context: Option<SuspendedContext>, // only current while this viewport uses it
+    platform: WinitPlatform,
+    last_frame: Instant,
+    pub window: Window,
+}
+
+/// A new imgui context for `window`, with the editor theme and fonts. The context is
+/// current when returned.
+pub fn create_context(window: &Window) -> Context {
+    let mut imgui = Context::create();
+    // Window layout is kept in the session file rather than imgui.ini
+    imgui.set_ini_filename(None);
+
+    // Apply complete AMOLED black theme
+    ui::PulsarTheme::apply_theme(&mut imgui);
+
+    // High-quality font rendering with better scaling
+    let hidpi_factor = window.scale_factor();
+    let base_font_size = 15.0; // Larger base font for better readability
+    let font_size = (base_font_size * hidpi_factor) as f32;
+
+    imgui.fonts().clear();
+    imgui.fonts().add_font(&[
+        FontSource::DefaultFontData {
+            config: Some(FontConfig {
+                oversample_h: 3,
+                oversample_v: 1,
+                pixel_snap_h: false,
+                size_pixels: font_size,
+                rasterizer_multiply: 1.5,
+                ..Default::default()
+            }),
+        },
+    ]);
+    imgui
+}
+
+impl Viewport {
+    /// Wrap a window, its surface and the current imgui context made for it, whose
+    /// fonts the renderer must already have. The context is suspended until used.
+    pub fn new(window: Window, surface: wgpu::Surface, gpu: &Gpu, mut context: Context) -> Self {
+        let size = window.inner_size();
+        let mut config = surface
+            .get_default_config(&gpu.adapter, size.width.max(1), size.height.max(1))
+            .expect("Surface is not supported by the adapter");
+        config.format = SURFACE_FORMAT;
+        config.usage = wgpu::TextureUsages::RENDER_ATTACHMENT;
+        config.present_mode = wgpu::PresentMode::AutoVsync;
+        surface.configure(&gpu.device, &config);
+
+        let mut platform = WinitPlatform::init(&mut context);
+        platform.attach_window(context.io_mut(), &window, HiDpiMode::Rounded);
+
+        Self {
+            surface,
+            config,
+            context: Some(context.suspend()),
+            platform,
+            last_frame: Instant::now(),
+            window,
+        }
+    }
+
+    /// Open a window for a panel or tab detached from the main window. `position`
+    /// is in screen pixels.
+    pub fn open(
+        target: &EventLoopWindowTarget<()>,
+        title: &str,
+        position: Option<PhysicalPosition<i32>>,
+        size: [f32; 2],
+        gpu: &Gpu,
+        renderer: &mut Renderer,
+    ) -> Option<Self> {
+        let mut builder = WindowBuilder::new()
+            .with_title(title)
+            .with_inner_size(LogicalSize::new(size[0].max(200.0), size[1].max(150.0)))
+            .with_resizable(true);
+        if let Some(position) = position {
+            builder = builder.with_position(position);
+        }
+        let window = builder
+            .build(target)
+            .map_err(|e| log::error!("Failed to open window for {}: {}", title, e))
+            .ok()?;
+        let surface = unsafe { gpu.instance.create_surface(&window) }
+            .map_err(|e| log::error!("Failed to create surface for {}: {}", title, e))
+            .ok()?;
+
+        // Each context has its own font atlas, uploaded to the shared renderer. Point
+        // the new atlas at no texture first, as reloading removes the old one.
+        let mut context = create_context(&window);
+        context.fonts().tex_id = TextureId::new(usize::MAX);
+        renderer.reload_font_texture(&mut context, &gpu.device, &gpu.queue);
+        Some(Self::new(window, surface, gpu, context))
+    }
+
+    /// Release the renderer's copy of this window's fonts before closing it
+    pub fn close(mut self, renderer: &mut Renderer) {
+        self.with_context(|context| {
+            renderer.textures.remove(context.fonts().tex_id);
+        });
+    }
+
+    /// Run `f` with this window's imgui context made current
+    pub fn with_context<R>(&mut self, f: impl FnOnce(&mut Context) -> R) -> R {
+        let mut context = self.activate();
+        let result = f(&mut context);
+        self.context = Some(context.suspend());
+        result
+    }
+
+    fn activate(&mut self) -> Context {
+        let suspended = self.context.take().expect("Viewport context is already in use");
+        match suspended.activate() {
+            Ok(context) => context,
+            Err(_) => panic!("Another imgui context is still current"),
+        }
+    }
+
+    /// Pass an event to imgui. Window events should only go to their own window.
+    pub fn handle_event(&mut self, event: &Event<()>) {
+        let mut context = self.activate();
+        self.platform.handle_event(context.io_mut(), &self.window, event);
+        self.context = Some(context.suspend());
+    }
+
+    pub fn resize(&mut self, device: &wgpu::Device, size: PhysicalSize<u32>) {
+        self.config.width = size.width.max(1);
+        self.config.height = size.height.max(1);
+        self.surface.configure(device, &self.config);
+    }
+
+    /// Client area position in logical pixels, relative to `other`'s client area
+    pub fn position_relative_to(&self, other: &Window) -> Option<[f32; 2]> {
+        let here = self.window.inner_position().ok()?;
+        let there = other.inner_position().ok()?;
+        let scale = other.scale_factor();
+        Some([
+            ((here.x - there.x) as f64 / scale) as f32,
+            ((here.y - there.y) as f64 / scale) as f32,
+        ])
+    }
+
+    /// Move the window so its client area is at `position`, given as for `position_relative_to`
+    pub fn move_relative_to(&self, other: &Window, position: [f32; 2]) {
+        let (Ok(there), Ok(inner), Ok(outer)) =
+            (other.inner_position(), self.window.inner_position(), self.window.outer_position())
+        else {
+            return; // e.g. Wayland, where windows can't place themselves
+        };
+        let scale = other.scale_factor();
+        self.window.set_outer_position(PhysicalPosition::new(
+            there.x + (position[0] as f64 * scale) as i32 - (inner.x - outer.x),
+            there.y + (position[1] as f64 * scale) as i32 - (inner.y - outer.y),
+        ));
+    }
+
+    /// Reconfigure the surface if the `vsync` console variable changed
+    fn apply_vsync(&mut self, gpu: &Gpu) {
+        let vsync = console::cvar(VSYNC).map_or(true, |value| value.as_bool());
+        let present_mode = if vsync { wgpu::PresentMode::AutoVsync } else { wgpu::PresentMode::AutoNoVsync };
+        if self.config.present_mode != present_mode {
+            self.config.present_mode = present_mode;
+            self.surface.configure(&gpu.device, &self.config);
+        }
+    }
+
+    /// Build one imgui frame with `build`, then draw it and any 3D viewports it
+    /// queued into this window
+    pub fn render(
+        &mut self,
+        gpu: &Gpu,
+        renderer: &mut Renderer,
+        scene_renderer: &mut SceneRenderer,
+        build: impl FnOnce(&Ui),
+    ) {
+        self.apply_vsync(gpu);
+        let frame = match self.surface.get_current_texture() {
+            Ok(frame) => frame,
+            Err(e) => {
+                log::warn!("Dropped frame: {e:?}");
+                return;
+            }
+        };
+
+        let mut context = self.activate();
+        let now = Instant::now();
+        context.io_mut().update_delta_time(now - self.last_frame);
+        self.last_frame = now;
+
+        self.platform
+            .prepare_frame(context.io_mut(), &self.window)
+            .expect("Failed to prepare frame");
+
+        let ui = context.frame();
+
+        // Check for valid display size to avoid ClipRect assertion
+        let io = ui.io();
+        if io.display_size[0] > 10.0 && io.display_size[1] > 10.0 {
+            build(ui);
+        }
+        self.platform.prepare_render(ui, &self.window);
+
+        // Upload textures queued by the UI before they are drawn
+        textures::flush(renderer, &gpu.device, &gpu.queue);
+
+        let mut encoder: wgpu::CommandEncoder = gpu.device.create_command_encoder(
+            &wgpu::CommandEncoderDescriptor { label: None }
+        );
+
+        // 3D viewports render into their own textures before the UI samples them
+        scene_renderer.render_pending(&gpu.device, &gpu.queue, &mut encoder, renderer);
+
+        let view = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color {
+                        r: 0.06,
+                        g: 0.06,
+                        b: 0.06,
+                        a: 1.0,
+                    }),
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+        });
+
+        let draw_data = context.render();
+        renderer
+            .render(draw_data, &gpu.queue, &gpu.device, &mut rpass)
+            .expect("Rendering failed");
+
+        drop(rpass);
+
+        gpu.queue.submit(Some(encoder.finish()));
+        frame.present();
+
+        self.context = Some(context.suspend());
+    }
+}